@@ -7,8 +7,12 @@
 //! - **WCF (Weighted Complexity Factor)**: Measures terrain complexity using Sobel edge detection
 //! - **Memory**: Bytes allocated during pathfinding
 //! - **Time**: Duration of the pathfinding operation
+//! - **Preprocessing**: Time spent building search structures before the
+//!   query (HPA*, CSV only); it is not part of **Time**
 //! - **Steps**: Number of nodes expanded by the algorithm
 //! - **Path Cost**: Total weight of the resulting path
+//! - **Path Length**: Euclidean length of the path (CSV only), so any-angle
+//!   planners can be compared against grid planners
 //!
 //! ## Data Persistence
 //! Benchmark results are serialized to JSON with both individual run data
@@ -16,21 +20,22 @@
 
 use std::path::PathBuf;
 use std::time::Duration;
-use std::{collections::HashMap, fmt, thread};
+use std::{fmt, thread};
 
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
-use crate::components::board::{Board, Tile, TileType};
-use crate::pathfinding::Agent;
-use crate::util;
+use crate::generation::{self, CaveRules, NoiseTerrain};
+use crate::grid::{get_coordinate_from_idx, get_idx_from_coordinate, Grid, Tile};
+use crate::pathfinding::{Agent, Heuristic, PathResult, SearchConfig};
+use crate::settings::GenerationMode;
 
 /// Container for pathfinding benchmark data.
 ///
 /// Stores raw data from multiple pathfinding runs and provides methods
 /// for computing aggregate statistics. Custom serialization includes
 /// both raw data and computed averages/totals.
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 pub struct PathData {
     /// Weighted Complexity Factor values for each run
     pub wcf: Vec<f64>,
@@ -40,8 +45,8 @@ pub struct PathData {
     pub time: Vec<Duration>,
     /// Nodes expanded for each run
     pub steps: Vec<u32>,
-    /// Total path cost for each run
-    pub path_cost: Vec<u32>,
+    /// Path cost under the run's cost model for each run
+    pub path_cost: Vec<f64>,
 }
 
 /// Custom serialization for PathData that includes computed aggregates.
@@ -85,14 +90,14 @@ impl PathData {
     /// * `memory` - Memory allocated in bytes
     /// * `time` - Duration of the pathfinding
     /// * `steps` - Nodes expanded
-    /// * `path_cost` - Total path weight
+    /// * `path_cost` - Path cost under the run's cost model
     pub fn update_all(
         &mut self,
         wcf: f64,
         memory: u64,
         time: Duration,
         steps: u32,
-        path_cost: u32,
+        path_cost: f64,
     ) {
         self.wcf.push(wcf);
         self.memory.push(memory);
@@ -101,6 +106,20 @@ impl PathData {
         self.path_cost.push(path_cost);
     }
 
+    /// Add the measurements of a successful `Agent::get_path` query.
+    ///
+    /// # Arguments
+    /// * `result` - The result to record
+    pub fn record(&mut self, result: &PathResult) {
+        self.update_all(
+            result.wcf,
+            result.memory,
+            result.time,
+            result.expansions,
+            result.cost,
+        );
+    }
+
     /// Calculate average WCF across all runs.
     pub fn avg_wcf(&self) -> f64 {
         self.wcf.iter().sum::<f64>() / self.wcf.len() as f64
@@ -122,8 +141,8 @@ impl PathData {
     }
 
     /// Calculate average path cost across all runs.
-    pub fn avg_path_cost(&self) -> u32 {
-        self.path_cost.iter().sum::<u32>() / self.path_cost.len().max(1) as u32
+    pub fn avg_path_cost(&self) -> f64 {
+        self.path_cost.iter().sum::<f64>() / self.path_cost.len().max(1) as f64
     }
 
     /// Calculate total memory used across all runs.
@@ -142,8 +161,8 @@ impl PathData {
     }
 
     /// Calculate total path cost across all runs.
-    pub fn total_path_cost(&self) -> u32 {
-        self.path_cost.iter().sum::<u32>()
+    pub fn total_path_cost(&self) -> f64 {
+        self.path_cost.iter().sum::<f64>()
     }
 }
 
@@ -176,7 +195,7 @@ impl fmt::Display for PathData {
 ///
 /// # Returns
 /// Normalized complexity factor (0.0 to 1.0, higher = more uniform)
-pub fn sobel_method(grid: &Vec<Tile>, width: u32, height: u32) -> f64 {
+pub fn sobel_method(grid: &[Tile], width: u32, height: u32) -> f64 {
     // Sobel kernels for edge detection in X and Y directions
     const X_KERNEL: [[i32; 3]; 3] = [[-1, 0, 1], [-2, 0, 2], [-1, 0, 1]];
     const Y_KERNEL: [[i32; 3]; 3] = [[-1, -2, -1], [0, 0, 0], [1, 2, 1]];
@@ -215,7 +234,7 @@ pub fn sobel_method(grid: &Vec<Tile>, width: u32, height: u32) -> f64 {
         for (di, &(dc, dr)) in deltas.iter().enumerate() {
             let neighbor_pos = (c + dc, r + dr);
             if let Some(tile) =
-                util::get_idx_from_coordinate(neighbor_pos, width, height).and_then(|i| grid.get(i))
+                get_idx_from_coordinate(neighbor_pos, width, height).and_then(|i| grid.get(i))
             {
                 if tile.is_floor() {
                     let col = di % 3;
@@ -234,10 +253,10 @@ pub fn sobel_method(grid: &Vec<Tile>, width: u32, height: u32) -> f64 {
         }
     }
 
-    return c_value / traversable_count;
+    c_value / traversable_count
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 /// Configuration for a single benchmark scenario.
 pub struct BenchmarkConfig {
     /// Grid width/height in tiles
@@ -248,6 +267,80 @@ pub struct BenchmarkConfig {
     pub weighted_pct: u32,
     /// Max weight value for weighted tiles
     pub weight_range: u8,
+    /// Generator of the grids
+    pub generation: GenerationMode,
+    /// Corridor width of generated mazes in tiles
+    pub corridor_width: u32,
+    /// Percentage of maze dead ends opened into loops (0-100)
+    pub loop_pct: u32,
+    /// Smoothing rules of generated caves
+    pub cave: CaveRules,
+    /// Scale, octaves and persistence of generated noise terrain
+    pub noise: NoiseTerrain,
+    /// Heuristic settings for the informed planners
+    pub search: SearchConfig,
+    /// Base seed for the generated grids, random per run if `None`
+    pub seed: Option<u64>,
+}
+
+impl BenchmarkConfig {
+    /// Generate the grid of the `run`-th run, with random starts and goals.
+    ///
+    /// Random grids use the obstacle and weighted percentages and the weight
+    /// range. Cities read them as building density, maximum building size and
+    /// maximum road spacing. Mazes use `corridor_width` and `loop_pct` and
+    /// place their agents at maximal distance. Caves start with the obstacle
+    /// percentage as walls and are smoothed by the `cave` rules. Noise
+    /// terrain is shaped by `noise`, with weights up to the weight range, the
+    /// obstacle percentage as mountains and the weighted percentage as lakes.
+    ///
+    /// # Returns
+    /// The seed the grid was generated from
+    pub fn generate(&self, grid: &mut Grid, run: u32) -> u64 {
+        let seed = self
+            .seed
+            .map(|seed| generation::derive_seed(seed, run as u64));
+        match self.generation {
+            GenerationMode::Random => generation::random_grid(
+                grid,
+                self.weight_range,
+                self.obstacle_pct as usize,
+                self.weighted_pct as usize,
+                true,
+                seed,
+            ),
+            GenerationMode::City => generation::organic_city(
+                grid,
+                0,
+                2,
+                self.weight_range as u32,
+                self.obstacle_pct as f32,
+                2,
+                self.weighted_pct,
+                true,
+                seed,
+            ),
+            GenerationMode::Maze(algorithm) => generation::maze(
+                grid,
+                algorithm,
+                self.corridor_width,
+                self.loop_pct as usize,
+                true,
+                seed,
+            ),
+            GenerationMode::Cave => {
+                generation::cave(grid, self.obstacle_pct as usize, &self.cave, true, seed)
+            }
+            GenerationMode::Noise(kind) => {
+                let terrain = NoiseTerrain {
+                    mountain_pct: self.obstacle_pct,
+                    lake_pct: self.weighted_pct,
+                    ..self.noise
+                };
+                generation::noise_terrain(grid, kind, self.weight_range, &terrain, true, seed)
+            }
+        }
+    }
 }
 
 /// Returns a default set of benchmark configurations that sweep across
@@ -267,6 +360,13 @@ pub fn default_benchmark_configs() -> Vec<BenchmarkConfig> {
                     obstacle_pct: op,
                     weighted_pct: wp,
                     weight_range: wr,
+                    generation: GenerationMode::City,
+                    corridor_width: 1,
+                    loop_pct: 0,
+                    cave: CaveRules::default(),
+                    noise: NoiseTerrain::default(),
+                    search: SearchConfig::default(),
+                    seed: None,
                 });
             }
         }
@@ -274,14 +374,127 @@ pub fn default_benchmark_configs() -> Vec<BenchmarkConfig> {
     configs
 }
 
+/// Build the cartesian product of the given sweep values.
+///
+/// Every combination of grid size, obstacle percentage, weighted percentage
+/// and weight range becomes one `BenchmarkConfig`.
+pub fn sweep_benchmark_configs(
+    grid_sizes: &[u32],
+    obstacle_pcts: &[u32],
+    weighted_pcts: &[u32],
+    weight_ranges: &[u8],
+) -> Vec<BenchmarkConfig> {
+    let mut configs = Vec::new();
+    for &gs in grid_sizes {
+        for &op in obstacle_pcts {
+            for &wp in weighted_pcts {
+                for &wr in weight_ranges {
+                    configs.push(BenchmarkConfig {
+                        grid_size: gs,
+                        obstacle_pct: op,
+                        weighted_pct: wp,
+                        weight_range: wr,
+                        generation: GenerationMode::City,
+                        corridor_width: 1,
+                        loop_pct: 0,
+                        cave: CaveRules::default(),
+                        noise: NoiseTerrain::default(),
+                        search: SearchConfig::default(),
+                        seed: None,
+                    });
+                }
+            }
+        }
+    }
+    configs
+}
+
+/// Repeat every configuration for each generation mode.
+///
+/// Maze modes are further repeated for every corridor width and loop
+/// percentage; the other modes ignore those two lists.
+pub fn sweep_generation_configs(
+    configs: &[BenchmarkConfig],
+    modes: &[GenerationMode],
+    corridor_widths: &[u32],
+    loop_pcts: &[u32],
+) -> Vec<BenchmarkConfig> {
+    let mut swept = Vec::new();
+    for config in configs {
+        for &generation in modes {
+            if !matches!(generation, GenerationMode::Maze(_)) {
+                swept.push(BenchmarkConfig {
+                    generation,
+                    ..*config
+                });
+                continue;
+            }
+            for &corridor_width in corridor_widths {
+                for &loop_pct in loop_pcts {
+                    swept.push(BenchmarkConfig {
+                        generation,
+                        corridor_width,
+                        loop_pct,
+                        ..*config
+                    });
+                }
+            }
+        }
+    }
+    swept
+}
+
+/// Repeat every configuration for each combination of heuristic and weight.
+///
+/// Used to compare how the heuristic choice changes expansions and path
+/// cost on the same grid settings.
+pub fn sweep_search_configs(
+    configs: &[BenchmarkConfig],
+    heuristics: &[Heuristic],
+    heuristic_weights: &[f32],
+) -> Vec<BenchmarkConfig> {
+    let mut swept = Vec::new();
+    for config in configs {
+        for &heuristic in heuristics {
+            for &heuristic_weight in heuristic_weights {
+                swept.push(BenchmarkConfig {
+                    search: SearchConfig {
+                        heuristic,
+                        heuristic_weight,
+                        ..config.search
+                    },
+                    ..*config
+                });
+            }
+        }
+    }
+    swept
+}
+
 /// Run benchmarks across multiple grid configurations and algorithms, writing results to CSV.
 ///
 /// For each combination of (config, algorithm), generates `iterations` random grids,
-/// runs pathfinding, and records per-run metrics to `output_path`.
+/// runs pathfinding, and records per-run metrics to `output_path`. Every grid is
+/// also solved with Dijkstra; its cost is written as `optimal_cost` next to each
+/// algorithm's `path_cost`, together with their ratio; both are priced by the
+/// config's `CostModel`, named in `cost_model`. The config's `MovementRule`
+/// is named in `movement`. Grids come from the config's `GenerationMode`,
+/// named in `generation` next to the maze's `corridor_width` and `loop_pct`
+/// and the `cave_rules` and `noise` terrain (see `BenchmarkConfig::generate`).
+/// The seed each grid was generated from is written in the `seed` column;
+/// with a base seed in the config, run `n` uses `derive_seed(seed, n)` so the
+/// whole CSV is reproducible.
+/// `path_length` is the Euclidean length of the path, which is what the
+/// any-angle planners (Theta*, Lazy Theta*) shorten. `preprocess_ms` is the
+/// time HPA* spent building its cluster graph, which `time_ms` leaves out.
+/// Every query runs within the config's `SearchBudget`; a query that runs
+/// out of it is still written, with its `status` (`timeout`,
+/// `expansion_limit`, ...) instead of `ok` and empty path columns. So is a
+/// query that fails (e.g. `unreachable`) where Dijkstra found a path.
 ///
 /// # Arguments
 /// * `configs` - Grid configurations to test
-/// * `algorithms` - Algorithm names (e.g. "A* search", "Breadth First Search", "JPSW", "Greedy")
+/// * `algorithms` - Algorithm names (e.g. "A* search", "Bidirectional A*", "Theta*", "D* Lite", "Breadth First Search", "Dijkstra", "JPSW", "HPA*", "Greedy")
 /// * `iterations` - Number of runs per (config, algorithm) pair
 /// * `output_path` - Path to the output CSV file
 pub fn run_overall_benchmark(
@@ -293,18 +506,33 @@ pub fn run_overall_benchmark(
     let file = std::fs::File::create(output_path).expect("Failed to create CSV file");
     let mut wtr = csv::Writer::from_writer(file);
 
-    wtr.write_record(&[
+    wtr.write_record([
         "algorithm",
         "grid_size",
         "obstacle_pct",
         "weighted_pct",
         "weight_range",
+        "generation",
+        "corridor_width",
+        "loop_pct",
+        "cave_rules",
+        "noise",
         "run",
         "wcf",
         "memory_bytes",
         "time_ms",
         "steps",
         "path_cost",
+        "optimal_cost",
+        "cost_ratio",
+        "heuristic",
+        "heuristic_weight",
+        "cost_model",
+        "movement",
+        "seed",
+        "path_length",
+        "preprocess_ms",
+        "status",
     ])
     .expect("Failed to write CSV header");
 
@@ -319,83 +547,61 @@ pub fn run_overall_benchmark(
             let algorithms: Vec<String> = algorithms.iter().map(|s| s.to_string()).collect();
             let config = *config;
             let handle = thread::spawn(move || {
-                let mut rows: Vec<[String; 11]> = Vec::new();
+                let mut rows: Vec<[String; 26]> = Vec::new();
                 for run in 0..iterations {
-                    let mut board = Board {
-                        location: sdl2::rect::Point::new(0, 0),
-                        width: config.grid_size,
-                        height: config.grid_size,
-                        tile_amount_x: config.grid_size,
-                        tile_amount_y: config.grid_size,
-                        active: false,
-                        id: String::from("benchmark_board"),
-                        selected_piece_type: TileType::Obstacle,
-                        cached_background: None,
-                        cached_grid: std::cell::RefCell::new(None),
-                        cached_texture: std::cell::RefCell::new(None),
-                        texture_dirty: std::cell::RefCell::new(true),
-                        multiple_agents: false,
-                        multiple_goals: false,
-                        agents: vec![],
-                        starts: vec![0],
-                        goals: vec![
-                            ((config.grid_size - 1) * config.grid_size + (config.grid_size - 1))
-                                as usize,
-                        ],
-                        updated_tiles: vec![],
+                    let mut grid = Grid::new(config.grid_size, config.grid_size);
+                    grid.starts = vec![0];
+                    grid.goals = vec![(config.grid_size * config.grid_size - 1) as usize];
+
+                    let seed = config.generate(&mut grid, run);
+                    let start_coord =
+                        get_coordinate_from_idx(grid.starts[0], grid.width, grid.height);
+                    let goal_coord = get_coordinate_from_idx(grid.goals[0], grid.width, grid.height);
+                    let mut agent = Agent {
+                        start: start_coord,
+                        goal: goal_coord,
+                        position: start_coord,
+                        path: vec![],
+                        waypoints: vec![],
                     };
 
-                    /*board.generate_random_grid(
-                        config.weight_range,
-                        config.obstacle_pct as usize,
-                        config.weighted_pct as usize,
-                        true,
-                    );*/
-
-                    board.generate_organic_city(
-                        0,
-                        2,
-                        config.weight_range as u32,
-                        config.obstacle_pct as f32,
-                        2,
-                        config.weighted_pct as u32,
-                        true,
-                    );
-                    for algorithm in &algorithms {
-                        let grid = board.grid();
+                    if !agent.is_path_possible(
+                        &grid.tiles,
+                        grid.width,
+                        grid.height,
+                        config.search.movement,
+                    ) {
+                        continue;
+                    }
 
-                        let start_coord = util::get_coordinate_from_idx(
-                            board.starts[0],
-                            board.tile_amount_x,
-                            board.tile_amount_y,
-                        );
-                        let goal_coord = util::get_coordinate_from_idx(
-                            board.goals[0],
-                            board.tile_amount_x,
-                            board.tile_amount_y,
-                        );
-                        let mut agent = Agent {
-                            start: start_coord,
-                            goal: goal_coord,
-                            position: start_coord,
-                            path: vec![],
-                        };
-
-                        if !agent.is_path_possible(&grid, board.tile_amount_x, board.tile_amount_y)
-                        {
-                            continue;
-                        }
+                    // Dijkstra is always optimal, so its cost is the reference
+                    // every algorithm's path cost is compared against
+                    let optimal = agent.get_path(
+                        "Dijkstra",
+                        &config.search,
+                        &grid.tiles,
+                        grid.width,
+                        grid.height,
+                    );
+                    let optimal_cost = optimal.is_success().then_some(optimal.cost);
 
-                        let (success, _path, wcf, memory, time, steps, path_cost) = agent.get_path(
+                    for algorithm in &algorithms {
+                        let result = agent.get_path(
                             algorithm,
-                            &grid,
-                            board.tile_amount_x,
-                            board.tile_amount_y,
+                            &config.search,
+                            &grid.tiles,
+                            grid.width,
+                            grid.height,
                         );
 
-                        if !success || _path.is_empty() {
+                        // A failure is only expected when Dijkstra failed too; any
+                        // other failure is written so wrong results show up
+                        if optimal_cost.is_none()
+                            && result.failure.is_some_and(|reason| !reason.is_exhausted())
+                        {
                             continue;
                         }
+                        let found = result.is_success().then_some(&result);
 
                         rows.push([
                             algorithm.to_string(),
@@ -403,12 +609,29 @@ pub fn run_overall_benchmark(
                             config.obstacle_pct.to_string(),
                             config.weighted_pct.to_string(),
                             config.weight_range.to_string(),
+                            config.generation.name().to_string(),
+                            config.corridor_width.to_string(),
+                            config.loop_pct.to_string(),
+                            config.cave.to_string(),
+                            config.noise.to_string(),
                             run.to_string(),
-                            format!("{:.6}", wcf),
-                            memory.to_string(),
-                            format!("{:.4}", time.as_secs_f64() * 1000.0),
-                            steps.to_string(),
-                            path_cost.to_string(),
+                            format!("{:.6}", result.wcf),
+                            result.memory.to_string(),
+                            format!("{:.4}", result.time.as_secs_f64() * 1000.0),
+                            result.expansions.to_string(),
+                            found.map_or(String::new(), |r| format!("{:.4}", r.cost)),
+                            optimal_cost.map_or(String::new(), |cost| format!("{:.4}", cost)),
+                            found.zip(optimal_cost).map_or(String::new(), |(r, cost)| {
+                                format!("{:.6}", r.cost / cost.max(f64::EPSILON))
+                            }),
+                            config.search.heuristic.name().to_string(),
+                            config.search.heuristic_weight.to_string(),
+                            config.search.cost_model.name().to_string(),
+                            config.search.movement.name().to_string(),
+                            seed.to_string(),
+                            found.map_or(String::new(), |r| format!("{:.4}", r.length)),
+                            format!("{:.4}", result.preprocessing.as_secs_f64() * 1000.0),
+                            result.failure.map_or("ok", |reason| reason.code()).to_string(),
                         ]);
                     }
                 }
@@ -417,11 +640,13 @@ pub fn run_overall_benchmark(
             handles.push(handle);
         }
 
+        // A panicking configuration must not silently drop its rows
         for handle in handles {
-            if let Ok(rows) = handle.join() {
-                for row in &rows {
-                    wtr.write_record(row).expect("Failed to write CSV row");
-                }
+            let rows = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for row in &rows {
+                wtr.write_record(row).expect("Failed to write CSV row");
             }
         }
     }
@@ -433,10 +658,8 @@ pub fn run_overall_benchmark(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        colors::WHITE,
-        components::board::{Tile, TileType},
-    };
+    use crate::generation::{MazeAlgorithm, NoiseKind};
+    use crate::grid::TileType;
     use std::time::Duration;
 
     fn make_empty_pathdata() -> PathData {
@@ -459,7 +682,7 @@ mod tests {
                 Duration::from_millis(30),
             ],
             steps: vec![100, 200, 300],
-            path_cost: vec![50, 60, 70],
+            path_cost: vec![50.0, 60.0, 70.0],
         }
     }
 
@@ -468,7 +691,7 @@ mod tests {
     #[test]
     fn test_update_all_adds_values() {
         let mut pd = make_empty_pathdata();
-        pd.update_all(0.5, 1024, Duration::from_millis(100), 42, 10);
+        pd.update_all(0.5, 1024, Duration::from_millis(100), 42, 10.0);
         assert_eq!(pd.wcf.len(), 1);
         assert_eq!(pd.memory.len(), 1);
         assert_eq!(pd.time.len(), 1);
@@ -477,7 +700,30 @@ mod tests {
         assert!((pd.wcf[0] - 0.5).abs() < 0.001);
         assert_eq!(pd.memory[0], 1024);
         assert_eq!(pd.steps[0], 42);
-        assert_eq!(pd.path_cost[0], 10);
+        assert_eq!(pd.path_cost[0], 10.0);
+    }
+
+    #[test]
+    fn test_record_path_result() {
+        let mut pd = make_empty_pathdata();
+        pd.record(&PathResult {
+            algorithm: "A* Search".to_string(),
+            path: vec![(1, 1), (0, 0)],
+            cost: 1.0,
+            length: 2f64.sqrt(),
+            waypoints: vec![],
+            expansions: 7,
+            time: Duration::from_millis(3),
+            preprocessing: Duration::ZERO,
+            memory: 256,
+            wcf: 0.25,
+            failure: None,
+        });
+        assert_eq!(pd.steps, vec![7]);
+        assert_eq!(pd.path_cost, vec![1.0]);
+        assert_eq!(pd.memory, vec![256]);
+        assert_eq!(pd.time, vec![Duration::from_millis(3)]);
+        assert!((pd.wcf[0] - 0.25).abs() < 0.001);
     }
 
     #[test]
@@ -489,7 +735,7 @@ mod tests {
                 i as u64 * 100,
                 Duration::from_millis(i as u64),
                 i,
-                i,
+                i as f64,
             );
         }
         assert_eq!(pd.wcf.len(), 5);
@@ -526,7 +772,7 @@ mod tests {
     #[test]
     fn test_avg_path_cost() {
         let pd = make_sample_pathdata();
-        assert_eq!(pd.avg_path_cost(), 60);
+        assert_eq!(pd.avg_path_cost(), 60.0);
     }
 
     // ------- Totals -------
@@ -552,7 +798,7 @@ mod tests {
     #[test]
     fn test_total_path_cost() {
         let pd = make_sample_pathdata();
-        assert_eq!(pd.total_path_cost(), 180);
+        assert_eq!(pd.total_path_cost(), 180.0);
     }
 
     // ------- Display -------
@@ -611,6 +857,133 @@ mod tests {
         assert_eq!(loaded.path_cost.len(), 3);
     }
 
+    // ------- sweep_benchmark_configs -------
+
+    #[test]
+    fn test_sweep_benchmark_configs_product() {
+        let configs = sweep_benchmark_configs(&[16, 32], &[0, 50], &[10], &[1, 5, 9]);
+        assert_eq!(configs.len(), 12);
+        assert!(configs.contains(&BenchmarkConfig {
+            grid_size: 32,
+            obstacle_pct: 50,
+            weighted_pct: 10,
+            weight_range: 9,
+            generation: GenerationMode::City,
+            corridor_width: 1,
+            loop_pct: 0,
+            cave: CaveRules::default(),
+            noise: NoiseTerrain::default(),
+            search: SearchConfig::default(),
+            seed: None,
+        }));
+    }
+
+    #[test]
+    fn test_sweep_generation_configs_only_repeats_mazes() {
+        let base = sweep_benchmark_configs(&[16, 32], &[0], &[10], &[1]);
+        let configs = sweep_generation_configs(
+            &base,
+            &[
+                GenerationMode::City,
+                GenerationMode::Maze(MazeAlgorithm::Prim),
+            ],
+            &[1, 2],
+            &[0, 25, 100],
+        );
+        // Per grid size: one city and 2 x 3 mazes
+        assert_eq!(configs.len(), 14);
+        assert!(configs.iter().any(|c| c.grid_size == 16
+            && c.generation == GenerationMode::Maze(MazeAlgorithm::Prim)
+            && c.corridor_width == 2
+            && c.loop_pct == 100));
+    }
+
+    #[test]
+    fn test_generate_caves_from_the_obstacle_percentage() {
+        let config = BenchmarkConfig {
+            generation: GenerationMode::Cave,
+            obstacle_pct: 100,
+            ..default_benchmark_configs()[0]
+        };
+        let mut grid = Grid::new(16, 16);
+        config.generate(&mut grid, 0);
+        // Only the randomly placed start and goal survive a full fill
+        let open = grid.tiles.iter().filter(|t| t.is_traversable()).count();
+        assert_eq!(open, 2);
+    }
+
+    #[test]
+    fn test_generate_noise_terrain_from_the_percentages() {
+        let config = BenchmarkConfig {
+            generation: GenerationMode::Noise(NoiseKind::Simplex),
+            grid_size: 20,
+            obstacle_pct: 25,
+            weighted_pct: 10,
+            weight_range: 10,
+            ..default_benchmark_configs()[0]
+        };
+        let mut grid = Grid::new(20, 20);
+        config.generate(&mut grid, 0);
+        let count = |tile_type| {
+            grid.tiles
+                .iter()
+                .filter(|t| t.tile_type() == tile_type)
+                .count()
+        };
+        assert_eq!(count(TileType::Obstacle), 100);
+        assert_eq!(count(TileType::Water), 40);
+        assert!(grid.tiles.iter().any(|t| t.weight == 10));
+    }
+
+    #[test]
+    fn test_generate_every_default_config() {
+        for (run, config) in default_benchmark_configs().iter().enumerate() {
+            let config = BenchmarkConfig {
+                grid_size: 16,
+                seed: Some(run as u64),
+                ..*config
+            };
+            let mut grid = Grid::new(16, 16);
+            grid.starts = vec![0];
+            grid.goals = vec![255];
+            config.generate(&mut grid, 0);
+            assert_eq!(grid.tiles.len(), 256);
+        }
+    }
+
+    #[test]
+    fn test_generate_uses_the_generation_mode() {
+        let config = BenchmarkConfig {
+            generation: GenerationMode::Maze(MazeAlgorithm::Kruskal),
+            seed: Some(4),
+            ..default_benchmark_configs()[0]
+        };
+        let mut grid = Grid::new(21, 21);
+        assert_eq!(config.generate(&mut grid, 3), generation::derive_seed(4, 3));
+        // A perfect maze of 10x10 cells
+        let open = grid.tiles.iter().filter(|t| t.is_traversable()).count();
+        assert_eq!(open, 199);
+    }
+
+    #[test]
+    fn test_sweep_search_configs_product() {
+        let base = sweep_benchmark_configs(&[16, 32], &[0], &[10], &[1]);
+        let configs = sweep_search_configs(
+            &base,
+            &[Heuristic::Manhattan, Heuristic::Octile, Heuristic::Zero],
+            &[1.0, 2.0],
+        );
+        assert_eq!(configs.len(), 12);
+        assert!(configs.iter().any(|c| c.grid_size == 32
+            && c.search.heuristic == Heuristic::Zero
+            && c.search.heuristic_weight == 2.0));
+    }
+
+    #[test]
+    fn test_sweep_benchmark_configs_empty_list() {
+        assert!(sweep_benchmark_configs(&[16], &[], &[10], &[1]).is_empty());
+    }
+
     // ------- sobel_method -------
 
     #[test]
@@ -619,7 +992,7 @@ mod tests {
         let mut grid = Vec::new();
         for y in 0..10 {
             for x in 0..10 {
-                grid.push(Tile::new((x, y), TileType::Floor, 10, 10, 1, false, WHITE));
+                grid.push(Tile::new((x, y), TileType::Floor, 1));
             }
         }
         let wcf = sobel_method(&grid, 10, 10);
@@ -633,15 +1006,7 @@ mod tests {
         for y in 0..10 {
             for x in 0..10 {
                 let weight = if x < 5 { 1 } else { 100 };
-                grid.push(Tile::new(
-                    (x, y),
-                    TileType::Floor,
-                    10,
-                    10,
-                    weight,
-                    false,
-                    WHITE,
-                ));
+                grid.push(Tile::new((x, y), TileType::Floor, weight));
             }
         }
         let wcf = sobel_method(&grid, 10, 10);
@@ -655,17 +1020,9 @@ mod tests {
         for y in 0..5 {
             for x in 0..5 {
                 if x == 2 && y == 2 {
-                    grid.push(Tile::new(
-                        (x, y),
-                        TileType::Obstacle,
-                        10,
-                        10,
-                        1,
-                        false,
-                        WHITE,
-                    ));
+                    grid.push(Tile::new((x, y), TileType::Obstacle, 1));
                 } else {
-                    grid.push(Tile::new((x, y), TileType::Floor, 10, 10, 1, false, WHITE));
+                    grid.push(Tile::new((x, y), TileType::Floor, 1));
                 }
             }
         }
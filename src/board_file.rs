@@ -0,0 +1,291 @@
+//! # Board File Module
+//!
+//! This module defines the versioned on-disk board format:
+//! - `BoardFile`: dimensions, start/goal lists, the movement rule and a
+//!   run-length encoded layer holding every tile's type and weight
+//! - `BOARD_FORMAT_VERSION`: the version written by this build
+//!
+//! ## Layer Encoding
+//! Each tile is reduced to a single code: `0` for an obstacle, `w` followed
+//! by the weight for water, otherwise its traversal weight (1-255).
+//! Consecutive equal codes are stored as a run `count*code` (or just `code`
+//! for a run of one), separated by spaces:
+//!
+//! ```text
+//! "40*1 3*0 7 12*1 5*w1"
+//! ```
+//!
+//! Start and goal tiles are restored from their lists, and path tiles are
+//! saved as floor since paths are not part of the map.
+//!
+//! Files without a `version` field are the original JSON format (version 1);
+//! the board loader still reads those and `.map` files, and saving writes the
+//! current version.
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{get_coordinate_from_idx, Grid, Tile, TileType};
+use crate::pathfinding::MovementRule;
+
+/// Version of the board format written by this build.
+pub const BOARD_FORMAT_VERSION: u32 = 2;
+
+/// Added to a water tile's weight to form its code.
+const WATER_CODE: u16 = 256;
+
+/// Pixel size of a tile when a file does not say otherwise.
+const DEFAULT_TILE_SIZE: [u32; 2] = [2, 2];
+
+/// A board as stored on disk.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BoardFile {
+    /// Format version, see `BOARD_FORMAT_VERSION`
+    pub version: u32,
+    /// Number of tiles horizontally
+    pub width: u32,
+    /// Number of tiles vertically
+    pub height: u32,
+    /// Pixel width and height of a tile when the board was saved
+    #[serde(default = "default_tile_size")]
+    pub tile_size: [u32; 2],
+    /// Tile indices of the agent starting positions
+    pub starts: Vec<usize>,
+    /// Tile indices of the goal positions
+    pub goals: Vec<usize>,
+    /// Allow multiple agents simultaneously
+    #[serde(default)]
+    pub multiple_agents: bool,
+    /// Allow multiple goal positions
+    #[serde(default)]
+    pub multiple_goals: bool,
+    /// Seed the map was generated from, if it was generated
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Neighbors the agents may move to on this board
+    #[serde(default)]
+    pub movement: MovementRule,
+    /// Run-length encoded tile codes in row-major order
+    pub layer: String,
+}
+
+fn default_tile_size() -> [u32; 2] {
+    DEFAULT_TILE_SIZE
+}
+
+impl BoardFile {
+    /// Encode a grid in the current format.
+    ///
+    /// Board options (`tile_size`, `multiple_agents`, ...) start at their
+    /// defaults and can be set on the result.
+    pub fn from_grid(grid: &Grid) -> Self {
+        BoardFile {
+            version: BOARD_FORMAT_VERSION,
+            width: grid.width,
+            height: grid.height,
+            tile_size: DEFAULT_TILE_SIZE,
+            starts: grid.starts.clone(),
+            goals: grid.goals.clone(),
+            multiple_agents: false,
+            multiple_goals: false,
+            seed: None,
+            movement: MovementRule::default(),
+            layer: encode_layer(&grid.tiles),
+        }
+    }
+
+    /// Decode the stored grid.
+    ///
+    /// # Returns
+    /// The grid, or an error if the file is from a newer version, the layer
+    /// is malformed or does not cover the grid, or a start/goal lies outside
+    pub fn to_grid(&self) -> Result<Grid, &'static str> {
+        if self.version > BOARD_FORMAT_VERSION {
+            return Err("Board file was written by a newer version");
+        }
+        let codes = decode_layer(&self.layer)?;
+        if codes.len() != (self.width * self.height) as usize {
+            return Err("Board layer does not match its dimensions");
+        }
+        if self
+            .starts
+            .iter()
+            .chain(&self.goals)
+            .any(|&idx| idx >= codes.len())
+        {
+            return Err("Start or goal lies outside the board");
+        }
+        let mut tiles: Vec<Tile> = codes
+            .iter()
+            .enumerate()
+            .map(|(idx, &code)| {
+                let position = get_coordinate_from_idx(idx, self.width, self.height);
+                match code {
+                    0 => Tile::new(position, TileType::Obstacle, 1),
+                    WATER_CODE.. => Tile::new(position, TileType::Water, (code - WATER_CODE) as u8),
+                    weight => Tile::new(position, TileType::Floor, weight as u8),
+                }
+            })
+            .collect();
+        for &idx in &self.starts {
+            tiles[idx].change_tile_type(TileType::Player);
+        }
+        for &idx in &self.goals {
+            tiles[idx].change_tile_type(TileType::Enemy);
+        }
+        Ok(Grid {
+            width: self.width,
+            height: self.height,
+            tiles,
+            starts: self.starts.clone(),
+            goals: self.goals.clone(),
+        })
+    }
+}
+
+/// Code stored for a tile: 0 for obstacles, `WATER_CODE` plus the weight for
+/// water, otherwise its weight.
+#[inline]
+fn tile_code(tile: &Tile) -> u16 {
+    match tile.tile_type() {
+        TileType::Obstacle => 0,
+        TileType::Water => WATER_CODE + tile.weight.max(1) as u16,
+        TileType::Weighted(weight) => weight.max(1) as u16,
+        _ => tile.weight.max(1) as u16,
+    }
+}
+
+/// Run-length encode the tile codes of `tiles`.
+fn encode_layer(tiles: &[Tile]) -> String {
+    let mut runs: Vec<String> = Vec::new();
+    let mut codes = tiles.iter().map(tile_code).peekable();
+    while let Some(code) = codes.next() {
+        let mut count = 1;
+        while codes.next_if_eq(&code).is_some() {
+            count += 1;
+        }
+        let code = match code {
+            WATER_CODE.. => format!("w{}", code - WATER_CODE),
+            weight => weight.to_string(),
+        };
+        runs.push(match count {
+            1 => code,
+            _ => format!("{}*{}", count, code),
+        });
+    }
+    runs.join(" ")
+}
+
+/// Expand a run-length encoded layer into one code per tile.
+fn decode_layer(layer: &str) -> Result<Vec<u16>, &'static str> {
+    let mut codes = Vec::new();
+    for run in layer.split_whitespace() {
+        let (count, code) = match run.split_once('*') {
+            Some((count, code)) => (count.parse::<usize>(), parse_code(code)),
+            None => (Ok(1), parse_code(run)),
+        };
+        match (count, code) {
+            (Ok(count), Ok(code)) => codes.extend(std::iter::repeat_n(code, count)),
+            _ => return Err("Malformed board layer"),
+        }
+    }
+    Ok(codes)
+}
+
+/// Parse a single tile code, either a weight or `w` and a water weight.
+fn parse_code(code: &str) -> Result<u16, std::num::ParseIntError> {
+    match code.strip_prefix('w') {
+        Some(weight) => weight
+            .parse::<u8>()
+            .map(|weight| WATER_CODE + weight as u16),
+        None => code.parse::<u8>().map(u16::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_grid() -> Grid {
+        let mut grid = Grid::new(4, 3);
+        grid.tiles[1].change_tile_type(TileType::Obstacle);
+        grid.tiles[2].change_tile_type(TileType::Obstacle);
+        grid.tiles[6].weight = 40;
+        grid.tiles[0].change_tile_type(TileType::Player);
+        grid.tiles[11].change_tile_type(TileType::Enemy);
+        grid.tiles[7].change_tile_type(TileType::Path);
+        grid.tiles[9].change_tile_type(TileType::Water);
+        grid.starts = vec![0];
+        grid.goals = vec![11];
+        grid
+    }
+
+    // ------- Layer encoding -------
+
+    #[test]
+    fn test_encode_layer_uses_runs() {
+        assert_eq!(
+            encode_layer(&sample_grid().tiles),
+            "1 2*0 3*1 40 2*1 w1 2*1"
+        );
+        assert_eq!(decode_layer("1 2*0 3*1"), Ok(vec![1, 0, 0, 1, 1, 1]));
+        assert_eq!(decode_layer(""), Ok(vec![]));
+        assert!(decode_layer("2*x").is_err());
+        assert!(decode_layer("300").is_err());
+        assert_eq!(decode_layer("2*w3"), Ok(vec![WATER_CODE + 3; 2]));
+    }
+
+    #[test]
+    fn test_open_grid_is_one_run() {
+        let file = BoardFile::from_grid(&Grid::new(512, 512));
+        assert_eq!(file.layer, "262144*1");
+    }
+
+    // ------- BoardFile -------
+
+    #[test]
+    fn test_round_trip_keeps_tiles_starts_and_goals() {
+        let grid = sample_grid();
+        let mut file = BoardFile::from_grid(&grid);
+        file.movement = MovementRule::FourConnected;
+        let json = serde_json::to_string(&file).unwrap();
+        let file: BoardFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(file.version, BOARD_FORMAT_VERSION);
+        assert_eq!(file.movement, MovementRule::FourConnected);
+        let loaded = file.to_grid().unwrap();
+        assert_eq!(loaded.starts, vec![0]);
+        assert_eq!(loaded.goals, vec![11]);
+        assert_eq!(loaded.tiles[0].tile_type(), TileType::Player);
+        assert_eq!(loaded.tiles[11].tile_type(), TileType::Enemy);
+        assert!(!loaded.tiles[2].is_traversable());
+        assert_eq!(loaded.tiles[6].weight, 40);
+        assert!(loaded.tiles[9].is_water());
+        // Paths are not saved
+        assert_eq!(loaded.tiles[7].tile_type(), TileType::Floor);
+        assert_eq!(loaded.tiles[7].position, (3, 1));
+    }
+
+    #[test]
+    fn test_to_grid_rejects_invalid_files() {
+        let mut file = BoardFile::from_grid(&sample_grid());
+        file.version = BOARD_FORMAT_VERSION + 1;
+        assert!(file.to_grid().is_err());
+
+        let mut file = BoardFile::from_grid(&sample_grid());
+        file.layer = String::from("11*1");
+        assert!(file.to_grid().is_err());
+
+        let mut file = BoardFile::from_grid(&sample_grid());
+        file.goals = vec![12];
+        assert!(file.to_grid().is_err());
+    }
+
+    #[test]
+    fn test_optional_fields_default() {
+        let json = r#"{"version":2,"width":2,"height":1,"starts":[],"goals":[],"layer":"2*1"}"#;
+        let file: BoardFile = serde_json::from_str(json).unwrap();
+        assert_eq!(file.tile_size, DEFAULT_TILE_SIZE);
+        assert_eq!(file.seed, None);
+        assert_eq!(file.movement, MovementRule::NoCornerCutting);
+        assert_eq!(file.to_grid().unwrap().tiles.len(), 2);
+    }
+}
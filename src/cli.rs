@@ -0,0 +1,1369 @@
+//! # Command-Line Interface Module
+//!
+//! This module parses the arguments given to the `pathmaker` binary.
+//! Without arguments the graphical application is started; the `bench`
+//! subcommand runs the benchmark suite headlessly so it can be driven from
+//! scripts on machines without a display, the `scen` subcommand checks
+//! a planner against a MovingAI scenario file, the `replan` subcommand
+//! compares incremental and full replanning as obstacles appear, the
+//! `export` subcommand renders boards to PNG images, and the `convert`
+//! subcommand rewrites boards in the current file format.
+//!
+//! ## Usage
+//! ```text
+//! pathmaker                      Start the graphical application
+//! pathmaker bench [OPTIONS]      Run benchmarks and write a CSV file
+//! pathmaker scen [OPTIONS]       Run MovingAI scenarios and report per bucket
+//! pathmaker replan [OPTIONS]     Compare D* Lite replanning with full replans
+//! pathmaker export [OPTIONS]     Render loaded or generated boards to PNG
+//! pathmaker convert [OPTIONS]    Rewrite a board in the current file format
+//! pathmaker help                 Print usage information
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::benchmarks::{self, BenchmarkConfig};
+use crate::components::board::scanner;
+use crate::cost::CostModel;
+use crate::generation::{CaveRules, NoiseTerrain};
+use crate::grid::Grid;
+use crate::pathfinding::{
+    get_configured_algorithm, Heuristic, MovementRule, SearchBudget, SearchConfig, ALGORITHMS,
+};
+use crate::settings::{GameSettings, GenerationMode};
+use crate::{movingai, replanning};
+
+/// Usage text printed for `help` and on argument errors.
+pub const USAGE: &str = "\
+Usage:
+  pathmaker                      Start the graphical application
+  pathmaker bench [OPTIONS]      Run benchmarks without opening a window
+  pathmaker scen [OPTIONS]       Run MovingAI scenarios against a planner
+  pathmaker replan [OPTIONS]     Compare D* Lite replanning with full replans
+  pathmaker export [OPTIONS]     Render boards to PNG without opening a window
+  pathmaker convert [OPTIONS]    Rewrite a board in the current file format
+  pathmaker help                 Print this message
+
+Bench options:
+  --algorithms <LIST>      Comma separated algorithm names
+                           (Greedy, Breadth First Search, Dijkstra, A* search,
+                           Bidirectional A*, Theta*, Lazy Theta*, D* Lite,
+                           JPSW, HPA*)
+  --grid-sizes <LIST>      Grid widths/heights in tiles     [default: 64,128,256,512]
+  --obstacles <LIST>       Obstacle percentages             [default: 0,25,50]
+  --weighted <LIST>        Weighted tile percentages        [default: 0,25,50,100]
+  --weight-ranges <LIST>   Maximum tile weights             [default: 1,10,100,255]
+  --generation <LIST>      Grid generators                  [default: City]
+                           (Random, City, Backtracker Maze, Prim Maze,
+                           Kruskal Maze, Wilson Maze, Cave, Perlin Terrain,
+                           Simplex Terrain, Value Terrain)
+  --corridor-widths <LIST> Maze corridor widths in tiles    [default: 1]
+  --loop-pcts <LIST>       Maze dead ends opened into loops [default: 0]
+  --cave-iterations <N>    Cave smoothing passes            [default: 5]
+  --cave-birth <N>         Wall neighbors making a wall     [default: 5]
+  --cave-death <N>         Wall neighbors keeping a wall    [default: 4]
+  --noise-scale <F>        Noise feature size in tiles      [default: 32]
+  --noise-octaves <N>      Noise layers added together      [default: 4]
+  --noise-persistence <F>  Amplitude kept per octave (0-1)  [default: 0.5]
+  --heuristics <LIST>      Heuristics for A* and JPSW       [default: Octile]
+                           (Manhattan, Octile, Euclidean, Chebyshev, Zero)
+  --heuristic-weights <LIST>
+                           Heuristic multipliers            [default: 1.0]
+  --cost-model <NAME>      Movement costs of every planner  [default: Uniform]
+                           (Uniform, Octile, Averaged)
+  --movement <NAME>        Neighbors of every planner       [default: No Corner Cutting]
+                           (No Corner Cutting, One Side Free, Corner Cutting,
+                           4-Connected)
+  --iterations <N>         Runs per configuration           [default: 15]
+  --seed <N>               Base seed for generated grids    [default: random]
+  --time-limit <MS>        Time budget of every query       [default: none]
+  --max-expansions <N>     Expansion budget of every query  [default: none]
+  --output <PATH>          Output CSV file                  [default: benchmark.csv]
+
+If none of the sweep options are given the default benchmark configurations
+are used, otherwise every combination of the given lists is run. Each
+configuration is repeated for every generator, heuristic and heuristic
+weight; mazes also for every corridor width and loop percentage, and ignore
+the obstacle and weight options. Caves use the obstacle percentages as the
+share of tiles that start as walls and keep only their largest region.
+Noise terrain turns the obstacle percentages into mountains on its highest
+tiles and the weighted percentages into lakes on its lowest.
+Queries that run out of their budget are written with their status.
+
+Scen options:
+  --map <PATH>             MovingAI .map file                (required)
+  --scen <PATH>            MovingAI .scen file               (required)
+  --algorithm <NAME>       Algorithm to evaluate            [default: A* search]
+  --heuristic <NAME>       Heuristic for A* and JPSW        [default: Octile]
+  --heuristic-weight <F>   Heuristic multiplier             [default: 1.0]
+  --time-limit <MS>        Time budget of every query       [default: none]
+  --max-expansions <N>     Expansion budget of every query  [default: none]
+  --output <PATH>          Also write the bucket report to a CSV file
+
+Reference lengths are octile distances, so planners always run under the
+Octile cost model and the No Corner Cutting movement rule.
+
+Replan options:
+  --algorithm <NAME>       Planner for the full replans     [default: A* search]
+  --grid-size <N>          Generated grid width/height      [default: 64]
+  --obstacles <N>          Generated obstacle percentage    [default: 25]
+  --weighted <N>           Generated weighted percentage    [default: 0]
+  --weight-range <N>       Generated maximum tile weight    [default: 10]
+  --changes <N>            Obstacles dropped per run        [default: 10]
+  --heuristic <NAME>       Heuristic for the full planner   [default: Chebyshev]
+  --iterations <N>         Runs                             [default: 15]
+  --seed <N>               Base seed for generated grids    [default: random]
+  --output <PATH>          Output CSV file                  [default: replanning.csv]
+
+Each run walks a random start towards a random goal while obstacles appear
+on the path ahead, replanning with D* Lite and from scratch.
+
+Export options:
+  --map <PATH>             Board to render (.json, .map, .png or .bmp)
+  --grid-size <N>          Generated grid width/height      [default: 64]
+  --obstacles <N>          Generated obstacle percentage    [default: 25]
+  --weighted <N>           Generated weighted percentage    [default: 0]
+  --weight-range <N>       Generated maximum tile weight    [default: 10]
+  --seed <N>               Base seed for generated grids    [default: random]
+  --generation <NAME>      Grid generator                   [default: Random]
+  --corridor-width <N>     Maze corridor width in tiles     [default: 1]
+  --loop-pct <N>           Maze dead ends opened into loops [default: 0]
+  --cave-iterations <N>    Cave smoothing passes            [default: 5]
+  --cave-birth <N>         Wall neighbors making a wall     [default: 5]
+  --cave-death <N>         Wall neighbors keeping a wall    [default: 4]
+  --noise-scale <F>        Noise feature size in tiles      [default: 32]
+  --noise-octaves <N>      Noise layers added together      [default: 4]
+  --noise-persistence <F>  Amplitude kept per octave (0-1)  [default: 0.5]
+  --count <N>              Number of grids to generate      [default: 1]
+  --algorithm <NAME>       Also draw the paths of this planner
+  --heuristic <NAME>       Heuristic for A* and JPSW        [default: Octile]
+  --heuristic-weight <F>   Heuristic multiplier             [default: 1.0]
+  --cost-model <NAME>      Movement costs of the planner    [default: Uniform]
+  --movement <NAME>        Neighbors of the planner         [default: board's rule]
+  --trace                  Also draw the planner's search trace
+  --scale <N>              Pixels per tile                  [default: 4]
+  --output <PATH>          Output PNG file                  [default: board.png]
+
+Without --map random grids are generated. With --count above 1 the files are
+numbered, e.g. board_0.png, board_1.png.
+
+Convert options:
+  --input <PATH>           Board to read                     (required)
+                           (.json of any version, .map, .png or .bmp)
+  --output <PATH>          Board file to write               (required)";
+
+/// Action selected by the command-line arguments.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Start the interactive SDL application
+    Gui,
+    /// Run the benchmark suite headlessly
+    Bench(BenchArgs),
+    /// Run MovingAI scenarios and report suboptimality per bucket
+    Scen(ScenArgs),
+    /// Compare incremental and full replanning headlessly
+    Replan(ReplanArgs),
+    /// Render boards to PNG images headlessly
+    Export(ExportArgs),
+    /// Rewrite a board file in the current format
+    Convert(ConvertArgs),
+    /// Print usage information
+    Help,
+}
+
+/// Options for the `bench` subcommand.
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    /// Algorithm names passed to `get_algorithm`
+    pub algorithms: Vec<String>,
+    /// Grid configurations to sweep over
+    pub configs: Vec<BenchmarkConfig>,
+    /// Number of runs per (config, algorithm) pair
+    pub iterations: u32,
+    /// Path of the CSV file to write
+    pub output: PathBuf,
+}
+
+/// Options for the `scen` subcommand.
+#[derive(Debug, PartialEq)]
+pub struct ScenArgs {
+    /// Path of the `.map` file
+    pub map: PathBuf,
+    /// Path of the `.scen` file
+    pub scen: PathBuf,
+    /// Algorithm name passed to `get_configured_algorithm`
+    pub algorithm: String,
+    /// Heuristic settings for the informed planners; the cost model and
+    /// movement rule are always those of the reference lengths
+    pub search: SearchConfig,
+    /// Optional CSV file for the bucket report
+    pub output: Option<PathBuf>,
+}
+
+/// Options for the `replan` subcommand.
+#[derive(Debug, PartialEq)]
+pub struct ReplanArgs {
+    /// Planner used for the full replans
+    pub algorithm: String,
+    /// Generation settings and heuristic of the grids
+    pub config: BenchmarkConfig,
+    /// Obstacles dropped per run
+    pub changes: u32,
+    /// Number of runs
+    pub iterations: u32,
+    /// Path of the CSV file to write
+    pub output: PathBuf,
+}
+
+/// Options for the `export` subcommand.
+#[derive(Debug, PartialEq)]
+pub struct ExportArgs {
+    /// Board file to render; random grids are generated if `None`
+    pub map: Option<PathBuf>,
+    /// Generation settings and heuristic used when there is no map
+    pub config: BenchmarkConfig,
+    /// Number of grids to generate
+    pub count: u32,
+    /// Planner whose paths are drawn, if any
+    pub algorithm: Option<String>,
+    /// Movement rule replacing the one saved with the board, if any
+    pub movement: Option<MovementRule>,
+    /// Draw the planner's search trace
+    pub trace: bool,
+    /// Pixels per tile
+    pub scale: u32,
+    /// Path of the PNG file to write
+    pub output: PathBuf,
+}
+
+/// Options for the `convert` subcommand.
+#[derive(Debug, PartialEq)]
+pub struct ConvertArgs {
+    /// Board file to read, in any supported format
+    pub input: PathBuf,
+    /// Path of the board file to write
+    pub output: PathBuf,
+}
+
+/// Parse the program arguments (excluding the binary name).
+///
+/// # Returns
+/// The selected command, or a message describing the invalid argument
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        None => Ok(Command::Gui),
+        Some("bench") => parse_bench_args(args).map(Command::Bench),
+        Some("scen") => parse_scen_args(args).map(Command::Scen),
+        Some("replan") => parse_replan_args(args).map(Command::Replan),
+        Some("export") => parse_export_args(args).map(Command::Export),
+        Some("convert") => parse_convert_args(args).map(Command::Convert),
+        Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+        Some(other) => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+/// Parse the options following the `bench` subcommand.
+fn parse_bench_args<I>(mut args: I) -> Result<BenchArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut algorithms: Vec<String> = ALGORITHMS.iter().map(|a| a.to_string()).collect();
+    let mut grid_sizes: Option<Vec<u32>> = None;
+    let mut obstacle_pcts: Option<Vec<u32>> = None;
+    let mut weighted_pcts: Option<Vec<u32>> = None;
+    let mut weight_ranges: Option<Vec<u8>> = None;
+    let mut heuristics = vec![Heuristic::default()];
+    let mut heuristic_weights = vec![SearchConfig::default().heuristic_weight];
+    let mut generation_modes = vec![GenerationMode::City];
+    let mut corridor_widths = vec![1];
+    let mut loop_pcts = vec![0];
+    let mut cave = CaveRules::default();
+    let mut noise = NoiseTerrain::default();
+    let mut cost_model = CostModel::default();
+    let mut movement = MovementRule::default();
+    let mut iterations: u32 = 15;
+    let mut seed: Option<u64> = None;
+    let mut budget = SearchBudget::default();
+    let mut output = PathBuf::from("benchmark.csv");
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--algorithms" => {
+                algorithms = value.split(',').map(|a| a.trim().to_string()).collect();
+                if let Some(unknown) = algorithms
+                    .iter()
+                    .find(|a| !ALGORITHMS.contains(&a.as_str()))
+                {
+                    return Err(format!("Unknown algorithm '{}'", unknown));
+                }
+            }
+            "--grid-sizes" => grid_sizes = Some(parse_list(&flag, &value)?),
+            "--obstacles" => obstacle_pcts = Some(parse_percentages(&flag, &value)?),
+            "--weighted" => weighted_pcts = Some(parse_percentages(&flag, &value)?),
+            "--weight-ranges" => weight_ranges = Some(parse_list(&flag, &value)?),
+            "--generation" => {
+                generation_modes = value
+                    .split(',')
+                    .map(parse_generation)
+                    .collect::<Result<_, _>>()?;
+            }
+            "--corridor-widths" => corridor_widths = parse_list(&flag, &value)?,
+            "--loop-pcts" => loop_pcts = parse_percentages(&flag, &value)?,
+            "--cave-iterations" | "--cave-birth" | "--cave-death" => {
+                parse_cave_rule(&mut cave, &flag, &value)?
+            }
+            "--noise-scale" | "--noise-octaves" | "--noise-persistence" => {
+                parse_noise_option(&mut noise, &flag, &value)?
+            }
+            "--heuristics" => {
+                heuristics = value
+                    .split(',')
+                    .map(parse_heuristic)
+                    .collect::<Result<_, _>>()?;
+            }
+            "--heuristic-weights" => heuristic_weights = parse_heuristic_weights(&flag, &value)?,
+            "--cost-model" => cost_model = parse_cost_model(&value)?,
+            "--movement" => movement = parse_movement(&value)?,
+            "--iterations" => {
+                iterations = value
+                    .parse()
+                    .map_err(|_| format!("Invalid value '{}' for '{}'", value, flag))?;
+            }
+            "--seed" => {
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid value '{}' for '{}'", value, flag))?,
+                );
+            }
+            "--time-limit" | "--max-expansions" => parse_budget(&mut budget, &flag, &value)?,
+            "--output" => output = PathBuf::from(value),
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+
+    if grid_sizes.as_ref().is_some_and(|sizes| sizes.contains(&0)) {
+        return Err(String::from("Grid sizes must be greater than 0"));
+    }
+    if corridor_widths.contains(&0) {
+        return Err(String::from("Corridor widths must be greater than 0"));
+    }
+
+    let configs = if grid_sizes.is_none()
+        && obstacle_pcts.is_none()
+        && weighted_pcts.is_none()
+        && weight_ranges.is_none()
+    {
+        benchmarks::default_benchmark_configs()
+    } else {
+        benchmarks::sweep_benchmark_configs(
+            &grid_sizes.unwrap_or_else(|| vec![64, 128, 256, 512]),
+            &obstacle_pcts.unwrap_or_else(|| vec![0, 25, 50]),
+            &weighted_pcts.unwrap_or_else(|| vec![0, 25, 50, 100]),
+            &weight_ranges.unwrap_or_else(|| vec![1, 10, 100, 255]),
+        )
+    };
+    let configs = benchmarks::sweep_generation_configs(
+        &configs,
+        &generation_modes,
+        &corridor_widths,
+        &loop_pcts,
+    );
+    let configs = benchmarks::sweep_search_configs(&configs, &heuristics, &heuristic_weights)
+        .into_iter()
+        .map(|config| BenchmarkConfig {
+            seed,
+            cave,
+            noise,
+            search: SearchConfig {
+                budget,
+                cost_model,
+                movement,
+                ..config.search
+            },
+            ..config
+        })
+        .collect();
+
+    Ok(BenchArgs {
+        algorithms,
+        configs,
+        iterations,
+        output,
+    })
+}
+
+/// Parse the options following the `scen` subcommand.
+fn parse_scen_args<I>(mut args: I) -> Result<ScenArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut map: Option<PathBuf> = None;
+    let mut scen: Option<PathBuf> = None;
+    let mut algorithm = String::from("A* search");
+    // Reference lengths are octile distances without corner cutting
+    let mut search = SearchConfig {
+        cost_model: CostModel::OCTILE,
+        movement: MovementRule::NoCornerCutting,
+        ..SearchConfig::default()
+    };
+    let mut output: Option<PathBuf> = None;
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--map" => map = Some(PathBuf::from(value)),
+            "--scen" => scen = Some(PathBuf::from(value)),
+            "--algorithm" => {
+                if !ALGORITHMS.contains(&value.as_str()) {
+                    return Err(format!("Unknown algorithm '{}'", value));
+                }
+                algorithm = value;
+            }
+            "--heuristic" => search.heuristic = parse_heuristic(&value)?,
+            "--heuristic-weight" => {
+                search.heuristic_weight = parse_heuristic_weights(&flag, &value)?[0];
+            }
+            "--time-limit" | "--max-expansions" => {
+                parse_budget(&mut search.budget, &flag, &value)?;
+            }
+            "--output" => output = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+
+    Ok(ScenArgs {
+        map: map.ok_or_else(|| String::from("Missing '--map'"))?,
+        scen: scen.ok_or_else(|| String::from("Missing '--scen'"))?,
+        algorithm,
+        search,
+        output,
+    })
+}
+
+/// Parse the options following the `replan` subcommand.
+fn parse_replan_args<I>(mut args: I) -> Result<ReplanArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut replan = ReplanArgs {
+        algorithm: String::from("A* search"),
+        config: BenchmarkConfig {
+            grid_size: 64,
+            obstacle_pct: 25,
+            weighted_pct: 0,
+            weight_range: 10,
+            generation: GenerationMode::Random,
+            corridor_width: 1,
+            loop_pct: 0,
+            cave: CaveRules::default(),
+            noise: NoiseTerrain::default(),
+            search: SearchConfig {
+                heuristic: Heuristic::Chebyshev,
+                ..SearchConfig::default()
+            },
+            seed: None,
+        },
+        changes: 10,
+        iterations: 15,
+        output: PathBuf::from("replanning.csv"),
+    };
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+        let invalid = || format!("Invalid value '{}' for '{}'", value, flag);
+        match flag.as_str() {
+            "--algorithm" => {
+                if !ALGORITHMS.contains(&value.as_str()) {
+                    return Err(format!("Unknown algorithm '{}'", value));
+                }
+                replan.algorithm = value;
+            }
+            "--grid-size" => replan.config.grid_size = value.parse().map_err(|_| invalid())?,
+            "--obstacles" => replan.config.obstacle_pct = parse_percentages(&flag, &value)?[0],
+            "--weighted" => replan.config.weighted_pct = parse_percentages(&flag, &value)?[0],
+            "--weight-range" => {
+                replan.config.weight_range = value.parse().map_err(|_| invalid())?
+            }
+            "--changes" => replan.changes = value.parse().map_err(|_| invalid())?,
+            "--heuristic" => replan.config.search.heuristic = parse_heuristic(&value)?,
+            "--iterations" => replan.iterations = value.parse().map_err(|_| invalid())?,
+            "--seed" => replan.config.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--output" => replan.output = PathBuf::from(&value),
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+
+    if replan.config.grid_size == 0 {
+        return Err(String::from("Grid size must be greater than 0"));
+    }
+    Ok(replan)
+}
+
+/// Parse the options following the `export` subcommand.
+fn parse_export_args<I>(mut args: I) -> Result<ExportArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut export = ExportArgs {
+        map: None,
+        config: BenchmarkConfig {
+            grid_size: 64,
+            obstacle_pct: 25,
+            weighted_pct: 0,
+            weight_range: 10,
+            generation: GenerationMode::Random,
+            corridor_width: 1,
+            loop_pct: 0,
+            cave: CaveRules::default(),
+            noise: NoiseTerrain::default(),
+            search: SearchConfig::default(),
+            seed: None,
+        },
+        count: 1,
+        algorithm: None,
+        movement: None,
+        trace: false,
+        scale: 4,
+        output: PathBuf::from("board.png"),
+    };
+
+    while let Some(flag) = args.next() {
+        if flag == "--trace" {
+            export.trace = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+        let invalid = || format!("Invalid value '{}' for '{}'", value, flag);
+        match flag.as_str() {
+            "--map" => export.map = Some(PathBuf::from(&value)),
+            "--grid-size" => export.config.grid_size = value.parse().map_err(|_| invalid())?,
+            "--obstacles" => export.config.obstacle_pct = parse_percentages(&flag, &value)?[0],
+            "--weighted" => export.config.weighted_pct = parse_percentages(&flag, &value)?[0],
+            "--weight-range" => {
+                export.config.weight_range = value.parse().map_err(|_| invalid())?
+            }
+            "--seed" => export.config.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--generation" => export.config.generation = parse_generation(&value)?,
+            "--corridor-width" => {
+                export.config.corridor_width = value.parse().map_err(|_| invalid())?
+            }
+            "--loop-pct" => export.config.loop_pct = parse_percentages(&flag, &value)?[0],
+            "--cave-iterations" | "--cave-birth" | "--cave-death" => {
+                parse_cave_rule(&mut export.config.cave, &flag, &value)?
+            }
+            "--noise-scale" | "--noise-octaves" | "--noise-persistence" => {
+                parse_noise_option(&mut export.config.noise, &flag, &value)?
+            }
+            "--count" => export.count = value.parse().map_err(|_| invalid())?,
+            "--algorithm" => {
+                if !ALGORITHMS.contains(&value.as_str()) {
+                    return Err(format!("Unknown algorithm '{}'", value));
+                }
+                export.algorithm = Some(value);
+            }
+            "--heuristic" => export.config.search.heuristic = parse_heuristic(&value)?,
+            "--heuristic-weight" => {
+                export.config.search.heuristic_weight = parse_heuristic_weights(&flag, &value)?[0];
+            }
+            "--cost-model" => export.config.search.cost_model = parse_cost_model(&value)?,
+            "--movement" => export.movement = Some(parse_movement(&value)?),
+            "--scale" => export.scale = value.parse().map_err(|_| invalid())?,
+            "--output" => export.output = PathBuf::from(&value),
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+
+    if export.config.grid_size == 0 {
+        return Err(String::from("Grid size must be greater than 0"));
+    }
+    if export.config.corridor_width == 0 {
+        return Err(String::from("Corridor width must be greater than 0"));
+    }
+    if export.scale == 0 {
+        return Err(String::from("Scale must be greater than 0"));
+    }
+    if export.trace && export.algorithm.is_none() {
+        return Err(String::from("'--trace' needs '--algorithm'"));
+    }
+    Ok(export)
+}
+
+/// Parse the options following the `convert` subcommand.
+fn parse_convert_args<I>(mut args: I) -> Result<ConvertArgs, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input: Option<PathBuf> = None;
+    let mut output: Option<PathBuf> = None;
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+        match flag.as_str() {
+            "--input" => input = Some(PathBuf::from(value)),
+            "--output" => output = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+
+    Ok(ConvertArgs {
+        input: input.ok_or_else(|| String::from("Missing '--input'"))?,
+        output: output.ok_or_else(|| String::from("Missing '--output'"))?,
+    })
+}
+
+/// Parse a heuristic name.
+fn parse_heuristic(value: &str) -> Result<Heuristic, String> {
+    Heuristic::from_name(value).ok_or_else(|| format!("Unknown heuristic '{}'", value.trim()))
+}
+
+/// Parse a cost model name.
+fn parse_cost_model(value: &str) -> Result<CostModel, String> {
+    CostModel::from_name(value).ok_or_else(|| format!("Unknown cost model '{}'", value.trim()))
+}
+
+/// Parse a generation mode name.
+fn parse_generation(value: &str) -> Result<GenerationMode, String> {
+    GenerationMode::from_name(value)
+        .ok_or_else(|| format!("Unknown generation mode '{}'", value.trim()))
+}
+
+/// Parse a `--cave-*` option into `rules`.
+fn parse_cave_rule(rules: &mut CaveRules, flag: &str, value: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid value '{}' for '{}'", value, flag);
+    match flag {
+        "--cave-iterations" => rules.iterations = value.parse().map_err(|_| invalid())?,
+        _ => {
+            let limit: u8 = value.parse().map_err(|_| invalid())?;
+            if limit > 8 {
+                return Err(format!("'{}' must be between 0 and 8", flag));
+            }
+            if flag == "--cave-birth" {
+                rules.birth_limit = limit;
+            } else {
+                rules.death_limit = limit;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `--noise-*` option into `terrain`.
+fn parse_noise_option(terrain: &mut NoiseTerrain, flag: &str, value: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid value '{}' for '{}'", value, flag);
+    match flag {
+        "--noise-octaves" => {
+            terrain.octaves = value.parse().map_err(|_| invalid())?;
+            if terrain.octaves == 0 {
+                return Err(format!("'{}' must be greater than 0", flag));
+            }
+        }
+        "--noise-scale" => {
+            terrain.scale = value.parse().map_err(|_| invalid())?;
+            if !terrain.scale.is_finite() || terrain.scale < 1.0 {
+                return Err(format!("'{}' must be at least 1", flag));
+            }
+        }
+        _ => {
+            terrain.persistence = value.parse().map_err(|_| invalid())?;
+            if !(0.0..=1.0).contains(&terrain.persistence) {
+                return Err(format!("'{}' must be between 0 and 1", flag));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse a movement rule name.
+fn parse_movement(value: &str) -> Result<MovementRule, String> {
+    MovementRule::from_name(value)
+        .ok_or_else(|| format!("Unknown movement rule '{}'", value.trim()))
+}
+
+/// Parse a comma separated list of heuristic weights (finite and non-negative).
+fn parse_heuristic_weights(flag: &str, value: &str) -> Result<Vec<f32>, String> {
+    let values: Vec<f32> = parse_list(flag, value)?;
+    if let Some(invalid) = values.iter().find(|v| !v.is_finite() || **v < 0.0) {
+        return Err(format!("Invalid heuristic weight {} for '{}'", invalid, flag));
+    }
+    Ok(values)
+}
+
+/// Parse `--time-limit` (milliseconds) or `--max-expansions` into `budget`.
+fn parse_budget(budget: &mut SearchBudget, flag: &str, value: &str) -> Result<(), String> {
+    let limit: u32 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for '{}'", value, flag))?;
+    match flag {
+        "--time-limit" => budget.max_time = Some(Duration::from_millis(limit as u64)),
+        _ => budget.max_expansions = Some(limit),
+    }
+    Ok(())
+}
+
+/// Parse a comma separated list of numbers.
+fn parse_list<T: std::str::FromStr>(flag: &str, value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse::<T>()
+                .map_err(|_| format!("Invalid value '{}' for '{}'", item.trim(), flag))
+        })
+        .collect()
+}
+
+/// Parse a comma separated list of percentages (0-100).
+fn parse_percentages(flag: &str, value: &str) -> Result<Vec<u32>, String> {
+    let values: Vec<u32> = parse_list(flag, value)?;
+    if let Some(invalid) = values.iter().find(|v| **v > 100) {
+        return Err(format!("Percentage {} for '{}' exceeds 100", invalid, flag));
+    }
+    Ok(values)
+}
+
+/// Run the benchmark suite described by `args`.
+///
+/// Only the benchmark module is used, so SDL is never initialised.
+pub fn run_bench(args: &BenchArgs) {
+    let algorithms: Vec<&str> = args.algorithms.iter().map(|a| a.as_str()).collect();
+    println!(
+        "Running {} configurations x {} algorithms x {} iterations",
+        args.configs.len(),
+        algorithms.len(),
+        args.iterations
+    );
+    benchmarks::run_overall_benchmark(&args.configs, &algorithms, args.iterations, &args.output);
+}
+
+/// Run the MovingAI scenarios described by `args` and print a bucket table.
+///
+/// # Returns
+/// An error message if a file cannot be read or does not match the map
+pub fn run_scen(args: &ScenArgs) -> Result<(), String> {
+    let grid = movingai::load_map(&args.map)?;
+    let scenarios = movingai::load_scenarios(&args.scen)?;
+    let algorithm = get_configured_algorithm(&args.algorithm, &args.search);
+    let results =
+        movingai::run_scenarios(&grid, &scenarios, algorithm.as_ref(), &args.search.budget)?;
+    let reports = movingai::bucket_reports(&results);
+
+    println!(
+        "{:>6} {:>9} {:>7} {:>7} {:>10} {:>10} {:>12} {:>10}",
+        "bucket", "scenarios", "solved", "optimal", "mean", "max", "expansions", "time_ms"
+    );
+    for report in &reports {
+        println!(
+            "{:>6} {:>9} {:>7} {:>7} {:>10.4} {:>10.4} {:>12} {:>10.2}",
+            report.bucket,
+            report.scenarios,
+            report.solved,
+            report.optimal,
+            report.mean_suboptimality,
+            report.max_suboptimality,
+            report.expansions,
+            report.time.as_secs_f64() * 1000.0
+        );
+    }
+    let too_short = results
+        .iter()
+        .filter(|r| r.is_shorter_than_optimal())
+        .count();
+    if too_short > 0 {
+        println!(
+            "Warning: {} paths are shorter than the reference optimum",
+            too_short
+        );
+    }
+
+    if let Some(output) = &args.output {
+        movingai::write_bucket_reports(&reports, &args.algorithm, output)?;
+    }
+    Ok(())
+}
+
+/// Run the replanning comparison described by `args`.
+///
+/// # Returns
+/// An error message if the CSV file cannot be written
+pub fn run_replan(args: &ReplanArgs) -> Result<(), String> {
+    println!(
+        "Comparing D* Lite with {} over {} runs of {} obstacles",
+        args.algorithm, args.iterations, args.changes
+    );
+    replanning::run_replanning_benchmark(
+        &[args.config],
+        &args.algorithm,
+        args.changes,
+        args.iterations,
+        &args.output,
+    )
+}
+
+/// Render the boards described by `args` to PNG files.
+///
+/// Boards are drawn on off-screen surfaces, so no window is opened. A
+/// generated grid whose paths cannot be found is still written, without
+/// paths.
+///
+/// # Returns
+/// An error message if the map cannot be loaded or an image cannot be written
+pub fn run_export(args: &ExportArgs) -> Result<(), String> {
+    let config = &args.config;
+    let count = if args.map.is_some() { 1 } else { args.count };
+    for index in 0..count {
+        let (mut board, seed) = match &args.map {
+            Some(map) => {
+                let settings = saved_settings();
+                let board = scanner::board_from(
+                    &map.to_string_lossy(),
+                    0,
+                    &settings.image_palette,
+                    &settings.terrain_costs,
+                )?;
+                (board, None)
+            }
+            None => {
+                let mut grid = Grid::new(config.grid_size, config.grid_size);
+                let seed = config.generate(&mut grid, index);
+                (scanner::board_from_grid(grid), Some(seed))
+            }
+        };
+        if let Some(movement) = args.movement {
+            board.movement = movement;
+        }
+        let output = numbered_output(&args.output, index, count);
+        if let Some(algorithm) = &args.algorithm {
+            if let Err(e) = board.solve_paths(algorithm, &config.search, args.trace) {
+                eprintln!("{}: {}", output.display(), e);
+            }
+        }
+        board.export_png(&output, args.scale, args.trace)?;
+        match seed {
+            Some(seed) => println!("Wrote {} (seed {})", output.display(), seed),
+            None => println!("Wrote {}", output.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Convert the board described by `args` to the current file format.
+///
+/// # Returns
+/// An error message if the input cannot be loaded or the output written
+pub fn run_convert(args: &ConvertArgs) -> Result<(), String> {
+    let settings = saved_settings();
+    let board = scanner::board_from(
+        &args.input.to_string_lossy(),
+        0,
+        &settings.image_palette,
+        &settings.terrain_costs,
+    )?;
+    let json = serde_json::to_string(&board).map_err(|e| e.to_string())?;
+    fs::write(&args.output, &json).map_err(|e| e.to_string())?;
+    let before = fs::metadata(&args.input).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "Wrote {} ({} bytes, input was {} bytes)",
+        args.output.display(),
+        json.len(),
+        before
+    );
+    Ok(())
+}
+
+/// Saved settings (image palette, terrain costs) used to load boards, or the
+/// defaults if there are none.
+fn saved_settings() -> GameSettings {
+    GameSettings::load(&GameSettings::get_default_path()).unwrap_or_default()
+}
+
+/// File name of the `index`-th of `count` exported images.
+///
+/// A single image keeps `output` as is; otherwise the index is appended to the
+/// file stem (`board.png` becomes `board_0.png`, `board_1.png`, ...).
+fn numbered_output(output: &Path, index: u32, count: u32) -> PathBuf {
+    if count <= 1 {
+        return output.to_path_buf();
+    }
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match output.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, index, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, index),
+    };
+    output.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation::{MazeAlgorithm, NoiseKind};
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_args_starts_gui() {
+        assert_eq!(parse_args(args(&[])), Ok(Command::Gui));
+    }
+
+    #[test]
+    fn test_help_command() {
+        assert_eq!(parse_args(args(&["help"])), Ok(Command::Help));
+        assert_eq!(parse_args(args(&["--help"])), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_unknown_command_is_error() {
+        assert!(parse_args(args(&["frobnicate"])).is_err());
+    }
+
+    #[test]
+    fn test_bench_defaults() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench"])) else {
+            panic!("expected bench command");
+        };
+        assert_eq!(bench.algorithms.len(), ALGORITHMS.len());
+        assert_eq!(bench.iterations, 15);
+        assert_eq!(bench.output, PathBuf::from("benchmark.csv"));
+        assert_eq!(
+            bench.configs.len(),
+            benchmarks::default_benchmark_configs().len()
+        );
+    }
+
+    #[test]
+    fn test_bench_sweep_is_cartesian_product() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--grid-sizes",
+            "32,64",
+            "--obstacles",
+            "0,10,20",
+            "--weighted",
+            "5",
+            "--weight-ranges",
+            "1,50",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert_eq!(bench.configs.len(), 12);
+        assert!(bench.configs.iter().all(|c| c.weighted_pct == 5));
+    }
+
+    #[test]
+    fn test_bench_algorithms_and_output() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--algorithms",
+            "A* search, JPSW",
+            "--iterations",
+            "3",
+            "--output",
+            "/tmp/out.csv",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert_eq!(bench.algorithms, vec!["A* search", "JPSW"]);
+        assert_eq!(bench.iterations, 3);
+        assert_eq!(bench.output, PathBuf::from("/tmp/out.csv"));
+    }
+
+    #[test]
+    fn test_bench_heuristic_sweep() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--grid-sizes",
+            "32",
+            "--obstacles",
+            "0",
+            "--weighted",
+            "0",
+            "--weight-ranges",
+            "1",
+            "--heuristics",
+            "manhattan,Octile,zero",
+            "--heuristic-weights",
+            "1,1.5",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert_eq!(bench.configs.len(), 6);
+        assert!(bench.configs.iter().any(|c| c.search.heuristic == Heuristic::Manhattan
+            && c.search.heuristic_weight == 1.5));
+        assert!(parse_args(args(&["bench", "--heuristics", "Taxicab"])).is_err());
+        assert!(parse_args(args(&["bench", "--heuristic-weights", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_bench_budget() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--time-limit",
+            "250",
+            "--max-expansions",
+            "10000",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench.configs.iter().all(|c| c.search.budget
+            == SearchBudget {
+                max_time: Some(Duration::from_millis(250)),
+                max_expansions: Some(10000),
+            }));
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench"])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench
+            .configs
+            .iter()
+            .all(|c| c.search.budget == SearchBudget::default()));
+        assert!(parse_args(args(&["bench", "--time-limit", "soon"])).is_err());
+    }
+
+    #[test]
+    fn test_cost_model_option() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench", "--cost-model", "octile"]))
+        else {
+            panic!("expected bench command");
+        };
+        assert!(bench
+            .configs
+            .iter()
+            .all(|c| c.search.cost_model == CostModel::OCTILE));
+        assert!(parse_args(args(&[
+            "scen",
+            "--map",
+            "arena.map",
+            "--scen",
+            "arena.map.scen",
+            "--cost-model",
+            "Averaged",
+        ]))
+        .is_err());
+        assert!(parse_args(args(&["bench", "--cost-model", "Manhattan"])).is_err());
+    }
+
+    #[test]
+    fn test_movement_option() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench", "--movement", "4-connected"]))
+        else {
+            panic!("expected bench command");
+        };
+        assert!(bench
+            .configs
+            .iter()
+            .all(|c| c.search.movement == MovementRule::FourConnected));
+        let Ok(Command::Export(export)) =
+            parse_args(args(&["export", "--movement", "Corner Cutting"]))
+        else {
+            panic!("expected export command");
+        };
+        assert_eq!(export.movement, Some(MovementRule::CornerCutting));
+        assert!(parse_args(args(&["scen", "--movement", "Hex"])).is_err());
+    }
+
+    #[test]
+    fn test_generation_options() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--grid-sizes",
+            "32",
+            "--obstacles",
+            "25",
+            "--weighted",
+            "0",
+            "--weight-ranges",
+            "1",
+            "--generation",
+            "City, Wilson Maze",
+            "--corridor-widths",
+            "1,3",
+            "--loop-pcts",
+            "0,50",
+        ])) else {
+            panic!("expected bench command");
+        };
+        // One city and 2 x 2 Wilson mazes
+        assert_eq!(bench.configs.len(), 5);
+        assert!(bench.configs.iter().any(|c| c.generation
+            == GenerationMode::Maze(MazeAlgorithm::Wilson)
+            && c.corridor_width == 3
+            && c.loop_pct == 50));
+        let Ok(Command::Export(export)) = parse_args(args(&[
+            "export",
+            "--generation",
+            "prim maze",
+            "--corridor-width",
+            "2",
+            "--loop-pct",
+            "25",
+        ])) else {
+            panic!("expected export command");
+        };
+        assert_eq!(
+            export.config.generation,
+            GenerationMode::Maze(MazeAlgorithm::Prim)
+        );
+        assert_eq!(export.config.corridor_width, 2);
+        assert_eq!(export.config.loop_pct, 25);
+        assert!(parse_args(args(&["bench", "--generation", "Caves"])).is_err());
+        assert!(parse_args(args(&["bench", "--corridor-widths", "0"])).is_err());
+        assert!(parse_args(args(&["export", "--loop-pct", "101"])).is_err());
+    }
+
+    #[test]
+    fn test_cave_options() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--generation",
+            "Cave",
+            "--cave-iterations",
+            "3",
+            "--cave-birth",
+            "6",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench
+            .configs
+            .iter()
+            .all(|c| c.generation == GenerationMode::Cave
+                && c.cave
+                    == CaveRules {
+                        iterations: 3,
+                        birth_limit: 6,
+                        ..CaveRules::default()
+                    }));
+        let Ok(Command::Export(export)) = parse_args(args(&["export", "--cave-death", "2"])) else {
+            panic!("expected export command");
+        };
+        assert_eq!(export.config.cave.death_limit, 2);
+        assert!(parse_args(args(&["bench", "--cave-birth", "9"])).is_err());
+        assert!(parse_args(args(&["export", "--cave-iterations", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_noise_options() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&[
+            "bench",
+            "--generation",
+            "Perlin Terrain,Value Terrain",
+            "--noise-scale",
+            "12.5",
+            "--noise-octaves",
+            "6",
+        ])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench.configs.iter().all(|c| c.noise
+            == NoiseTerrain {
+                scale: 12.5,
+                octaves: 6,
+                ..NoiseTerrain::default()
+            }));
+        assert!(bench
+            .configs
+            .iter()
+            .any(|c| c.generation == GenerationMode::Noise(NoiseKind::Value)));
+        let Ok(Command::Export(export)) =
+            parse_args(args(&["export", "--noise-persistence", "0.75"]))
+        else {
+            panic!("expected export command");
+        };
+        assert_eq!(export.config.noise.persistence, 0.75);
+        assert!(parse_args(args(&["bench", "--noise-octaves", "0"])).is_err());
+        assert!(parse_args(args(&["bench", "--noise-scale", "0.5"])).is_err());
+        assert!(parse_args(args(&["export", "--noise-persistence", "1.5"])).is_err());
+    }
+
+    #[test]
+    fn test_bench_seed() {
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench", "--seed", "1234"])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench.configs.iter().all(|c| c.seed == Some(1234)));
+        let Ok(Command::Bench(bench)) = parse_args(args(&["bench"])) else {
+            panic!("expected bench command");
+        };
+        assert!(bench.configs.iter().all(|c| c.seed.is_none()));
+        assert!(parse_args(args(&["bench", "--seed", "-1"])).is_err());
+    }
+
+    #[test]
+    fn test_bench_rejects_unknown_algorithm() {
+        assert!(parse_args(args(&["bench", "--algorithms", "Magic"])).is_err());
+    }
+
+    #[test]
+    fn test_bench_rejects_invalid_values() {
+        assert!(parse_args(args(&["bench", "--obstacles", "150"])).is_err());
+        assert!(parse_args(args(&["bench", "--grid-sizes", "0"])).is_err());
+        assert!(parse_args(args(&["bench", "--weight-ranges", "300"])).is_err());
+        assert!(parse_args(args(&["bench", "--iterations"])).is_err());
+        assert!(parse_args(args(&["bench", "--unknown", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_replan_args() {
+        let Ok(Command::Replan(replan)) = parse_args(args(&["replan"])) else {
+            panic!("expected replan command");
+        };
+        assert_eq!(replan.algorithm, "A* search");
+        assert_eq!(replan.config.search.heuristic, Heuristic::Chebyshev);
+        assert_eq!(replan.changes, 10);
+        assert_eq!(replan.output, PathBuf::from("replanning.csv"));
+
+        let Ok(Command::Replan(replan)) = parse_args(args(&[
+            "replan",
+            "--algorithm",
+            "JPSW",
+            "--changes",
+            "3",
+            "--seed",
+            "9",
+        ])) else {
+            panic!("expected replan command");
+        };
+        assert_eq!(replan.algorithm, "JPSW");
+        assert_eq!(replan.changes, 3);
+        assert_eq!(replan.config.seed, Some(9));
+        assert!(parse_args(args(&["replan", "--grid-size", "0"])).is_err());
+        assert!(parse_args(args(&["replan", "--algorithm", "Magic"])).is_err());
+    }
+
+    #[test]
+    fn test_export_defaults() {
+        let Ok(Command::Export(export)) = parse_args(args(&["export"])) else {
+            panic!("expected export command");
+        };
+        assert_eq!(export.map, None);
+        assert_eq!(export.count, 1);
+        assert_eq!(export.movement, None);
+        assert_eq!(export.scale, 4);
+        assert!(!export.trace);
+        assert_eq!(export.output, PathBuf::from("board.png"));
+    }
+
+    #[test]
+    fn test_export_args() {
+        let Ok(Command::Export(export)) = parse_args(args(&[
+            "export",
+            "--grid-size",
+            "32",
+            "--seed",
+            "7",
+            "--count",
+            "3",
+            "--algorithm",
+            "JPSW",
+            "--trace",
+            "--scale",
+            "8",
+            "--output",
+            "/tmp/maps.png",
+        ])) else {
+            panic!("expected export command");
+        };
+        assert_eq!(export.config.grid_size, 32);
+        assert_eq!(export.config.seed, Some(7));
+        assert_eq!(export.count, 3);
+        assert_eq!(export.algorithm.as_deref(), Some("JPSW"));
+        assert!(export.trace);
+        assert_eq!(export.scale, 8);
+    }
+
+    #[test]
+    fn test_export_rejects_invalid_values() {
+        assert!(parse_args(args(&["export", "--scale", "0"])).is_err());
+        assert!(parse_args(args(&["export", "--grid-size", "0"])).is_err());
+        assert!(parse_args(args(&["export", "--obstacles", "101"])).is_err());
+        assert!(parse_args(args(&["export", "--trace"])).is_err());
+        assert!(parse_args(args(&["export", "--algorithm", "Magic"])).is_err());
+    }
+
+    #[test]
+    fn test_numbered_output() {
+        let output = PathBuf::from("/tmp/board.png");
+        assert_eq!(numbered_output(&output, 0, 1), output);
+        assert_eq!(
+            numbered_output(&output, 2, 3),
+            PathBuf::from("/tmp/board_2.png")
+        );
+        assert_eq!(
+            numbered_output(Path::new("maps"), 1, 2),
+            PathBuf::from("maps_1")
+        );
+    }
+
+    #[test]
+    fn test_convert_args() {
+        let Ok(Command::Convert(convert)) = parse_args(args(&[
+            "convert", "--input", "old.json", "--output", "new.json",
+        ])) else {
+            panic!("expected convert command");
+        };
+        assert_eq!(convert.input, PathBuf::from("old.json"));
+        assert_eq!(convert.output, PathBuf::from("new.json"));
+        assert!(parse_args(args(&["convert", "--input", "old.json"])).is_err());
+    }
+
+    #[test]
+    fn test_scen_args() {
+        let Ok(Command::Scen(scen)) = parse_args(args(&[
+            "scen",
+            "--map",
+            "arena.map",
+            "--scen",
+            "arena.map.scen",
+            "--algorithm",
+            "JPSW",
+        ])) else {
+            panic!("expected scen command");
+        };
+        assert_eq!(scen.map, PathBuf::from("arena.map"));
+        assert_eq!(scen.scen, PathBuf::from("arena.map.scen"));
+        assert_eq!(scen.algorithm, "JPSW");
+        assert_eq!(scen.search.cost_model, CostModel::OCTILE);
+        assert_eq!(scen.search.movement, MovementRule::NoCornerCutting);
+        assert_eq!(scen.search.heuristic, SearchConfig::default().heuristic);
+        assert_eq!(scen.output, None);
+    }
+
+    #[test]
+    fn test_scen_requires_map_and_scen() {
+        assert!(parse_args(args(&["scen", "--map", "arena.map"])).is_err());
+        assert!(parse_args(args(&["scen", "--scen", "arena.map.scen"])).is_err());
+        assert!(parse_args(args(&[
+            "scen",
+            "--map",
+            "a",
+            "--scen",
+            "b",
+            "--algorithm",
+            "Magic"
+        ]))
+        .is_err());
+    }
+}
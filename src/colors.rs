@@ -13,6 +13,12 @@ pub const GREEN: Color = Color::RGB(0, 255, 0);
 
 pub const BLUE: Color = Color::RGB(0, 0, 255);
 
+/// Navy color - used for the straight segments of any-angle paths
+pub const SEGMENT: Color = Color::RGB(0, 0, 128);
+
+/// Light blue color - used for water tiles of MovingAI maps
+pub const WATER: Color = Color::RGB(90, 160, 230);
+
 /// Pure black color - used for obstacles and outlines
 pub const BLACK: Color = Color::RGB(0, 0, 0);
 
@@ -36,3 +42,12 @@ pub const QUATERNARY_COLOR: Color = Color::RGB(100, 100, 100);
 
 /// Hover state color (light gray) - used when mouse is over interactive elements
 pub const HOVER_COLOR: Color = Color::RGB(200, 200, 200);
+
+/// Search trace: node added to the open set (pale yellow)
+pub const TRACE_OPENED_COLOR: Color = Color::RGB(255, 241, 168);
+
+/// Search trace: node expanded (light blue)
+pub const TRACE_EXPANDED_COLOR: Color = Color::RGB(150, 200, 255);
+
+/// Search trace: jump point selected by JPSW (orange)
+pub const TRACE_JUMP_POINT_COLOR: Color = Color::RGB(255, 150, 40);
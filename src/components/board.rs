@@ -9,199 +9,118 @@
 //!
 //! ## Features
 //! - Click-to-place tile editing
-//! - Random and city-style map generation
+//! - Random, city-style, maze and cave map generation
 //! - Pathfinding execution with multi-threaded agent support
+//! - Collision-free multi-agent planning (Cooperative A* or CBS)
+//! - Any-angle paths (Theta*) drawn as straight segments over the tiles
+//! - Dynamic obstacles dropped on the paths while agents walk them, with
+//!   incremental (D* Lite), cluster-updating (HPA*) or full replanning
 //! - JSON serialization for save/load functionality
 
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{fmt, fs, thread, u8};
 
 extern crate sdl2;
 
-use rand::seq::index::sample;
-use rand::seq::IteratorRandom;
-use rand::seq::SliceRandom;
-use rand::{rng, Rng};
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+use sdl2::image::SaveSurface;
 use sdl2::mouse::MouseState;
 use sdl2::pixels::Color;
 use sdl2::pixels::PixelFormatEnum;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{Canvas, Texture, TextureCreator};
+use sdl2::surface::Surface;
 use sdl2::video::{Window, WindowContext};
-use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
-use serde::ser::SerializeStruct;
+use serde::de;
 use serde::{Deserialize, Serialize};
 use serde_json::{self, json};
 
-use crate::benchmarks::PathData;
+use crate::benchmarks::{sobel_method, PathData};
+use crate::board_file::BoardFile;
 use crate::components::Component;
-use crate::pathfinding::Agent;
-use crate::{colors::*, fileDialog, settings, util};
-
-/// Enumeration of possible tile types on the game board.
+use crate::generation::{CaveRules, MazeAlgorithm, NoiseKind, NoiseTerrain};
+use crate::grid::{self, Grid};
+use crate::history::{EditBuilder, EditHistory, GridEdit};
+use crate::multi_agent::{self, MultiAgentPlanner, MultiAgentResult};
+use crate::pathfinding::{
+    Agent, CancelToken, FailureReason, MovementRule, PathResult, SearchConfig,
+};
+use crate::playback::{Playback, PlaybackFrame};
+use crate::replanning::{obstacle_ahead, ReplanStats, Replanner};
+use crate::trace::{SearchTrace, TraceCell, TraceReplay};
+use crate::{colors::*, fileDialog, generation, settings, util};
+
+pub use crate::grid::{Tile, TileType};
+
+/// Color used to render a tile.
 ///
-/// Each type has different behavior for pathfinding and rendering.
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
-pub enum TileType {
-    /// Impassable obstacle - blocks all movement
-    Obstacle,
-    /// Standard floor tile with weight 1
-    Floor,
-    /// Player/agent starting position (rendered green)
-    Player,
-    /// Enemy/goal position (rendered red)
-    Enemy,
-    /// Floor tile with custom traversal weight (higher = slower)
-    Weighted(u8),
-    /// Path tile (rendered blue)
-    Path,
+/// Colors tiles based on type:
+/// - Obstacle: Black
+/// - Floor: White (tinted based on weight if weighted tile)
+/// - Player: Green
+/// - Enemy: Red
+/// - Path: Blue
+/// - Water: Light blue
+pub fn tile_color(tile: &Tile) -> Color {
+    match tile.tile_type() {
+        TileType::Obstacle => BLACK,
+        TileType::Enemy => RED,
+        TileType::Player => GREEN,
+        TileType::Path => BLUE,
+        TileType::Water => WATER,
+        TileType::Floor => calc_floor_color(tile.weight),
+        TileType::Weighted(weight) => calc_floor_color(weight),
+    }
 }
 
-/// Represents a single tile on the game board.
+/// Pixel offset and size of the `index`-th of `tiles` tiles along an axis
+/// `pixels` long.
 ///
-/// Tiles maintain their position, type, dimensions, and rendering state.
-/// The `dirty` flag is used for efficient redrawing - only dirty tiles are redrawn.
-#[derive(Copy, Clone)]
-pub struct Tile {
-    /// Position on the grid (tile coordinates, not pixels)
-    pub position: (i32, i32),
-    /// Current type of this tile (obstacle, floor, player, etc.)
-    tile_type: TileType,
-    /// Height in pixels when rendered
-    height: u32,
-    /// Width in pixels when rendered
-    width: u32,
-    /// Traversal weight (1 = normal, higher = slower)
-    pub weight: u8,
-    /// Whether this tile needs to be redrawn
-    dirty: bool,
-    /// Cached rectangle for efficient rendering
-    cached_rectangle: Option<Rect>,
-    cached_color: Color,
-}
-impl Serialize for Tile {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let mut state = serializer.serialize_struct("Tile", 6)?;
-        state.serialize_field("position", &self.position)?;
-        state.serialize_field("tile_type", &self.tile_type)?;
-        state.serialize_field("height", &self.height)?;
-        state.serialize_field("width", &self.width)?;
-        state.serialize_field("weight", &self.weight)?;
-        state.serialize_field("dirty", &self.dirty)?;
-        state.serialize_field("cached_color", &self.cached_color.rgb())?;
-        state.end()
+/// Tiles are at least one pixel; when there are more tiles than pixels they
+/// are placed proportionally and overlap.
+fn tile_span(index: i32, pixels: u32, tiles: u32) -> (i32, u32) {
+    match pixels / tiles.max(1) {
+        0 => ((index as i64 * pixels as i64 / tiles as i64) as i32, 1),
+        size => (index * size as i32, size),
     }
 }
 
-impl Tile {
-    /// Create a new tile with the specified properties.
-    ///
-    /// # Arguments
-    /// * `position` - Grid position (will be converted to pixel position)
-    /// * `tile_type` - Type of tile
-    /// * `height` - Tile height in pixels
-    /// * `width` - Tile width in pixels
-    /// * `weight` - Traversal weight
-    /// * `dirty` - Initial dirty state
-    pub(crate) fn new(
-        position: (i32, i32),
-        tile_type: TileType,
-        height: u32,
-        width: u32,
-        weight: u8,
-        dirty: bool,
-        color: Color,
-    ) -> Self {
-        // Convert grid position to pixel position
-        let position = (position.0 * width as i32, position.1 * height as i32);
-        Tile {
-            position,
-            tile_type,
-            height,
-            width,
-            dirty,
-            cached_rectangle: None,
-            cached_color: color,
-            weight: weight.max(1),
-        }
+/// Tile index under the pixel offset `relative` along an axis, the inverse
+/// of `tile_span`.
+fn tile_at(relative: i32, tile_size: i32, pixels: u32, tiles: u32) -> i32 {
+    match tile_size {
+        0 => (relative as i64 * tiles as i64 / pixels.max(1) as i64) as i32,
+        size => relative / size,
     }
+}
 
-    /// Calculate the screen rectangle for this tile.
-    fn get_rect(&self, board_origin: Point) -> Rect {
-        Rect::new(
-            board_origin.x() + self.position.0,
-            board_origin.y() + self.position.1,
-            self.width,
-            self.height,
-        )
+/// Get the overlay color of a search trace cell.
+pub fn trace_color(cell: TraceCell) -> Color {
+    match cell {
+        TraceCell::Opened => TRACE_OPENED_COLOR,
+        TraceCell::Expanded => TRACE_EXPANDED_COLOR,
+        TraceCell::JumpPoint => TRACE_JUMP_POINT_COLOR,
     }
+}
 
-    /// Draw this tile to the canvas.
-    ///
-    /// Colors tiles based on type:
-    /// - Obstacle: Black
-    /// - Floor: White (tinted based on weight if weighted tile)
-    /// - Player: Green
-    /// - Enemy: Red
-    fn draw(&mut self, change_layout: bool, board_origin: Point, canvas: &mut Canvas<Window>) {
-        if change_layout {
-            self.cached_rectangle = None;
-        }
-        let tile_rect = match self.cached_rectangle {
-            Some(rect) => rect,
-            None => self.get_rect(board_origin),
-        };
-        self.cached_rectangle = Some(tile_rect);
-
-        let c = self.cached_color;
-
-        canvas.set_draw_color(c);
-        canvas.fill_rect(tile_rect).unwrap();
+/// Number of frames a search trace replay should take, regardless of its size.
+const TRACE_REPLAY_FRAMES: usize = 240;
 
-        self.dirty = false;
-    }
+/// Animation frames between two dynamic obstacles.
+const DYNAMIC_OBSTACLE_INTERVAL: u32 = 4;
 
-    /// Check if this tile can be walked through.
-    ///
-    /// All non-obstacle tiles are traversable (floor, player, enemy, weighted).
-    pub fn is_traversable(&self) -> bool {
-        return self.tile_type != TileType::Obstacle;
-    }
-
-    /// Check if this tile is a standard floor tile.
-    pub fn is_floor(&self) -> bool {
-        return self.tile_type == TileType::Floor;
-    }
-
-    /// Change the tile's type and mark it as dirty for redraw.
-    fn change_tile_type(&mut self, new_type: TileType) {
-        if self.tile_type != new_type {
-            self.tile_type = new_type;
-            self.dirty = true;
-            match new_type {
-                TileType::Obstacle => self.cached_color = BLACK,
-                TileType::Enemy => self.cached_color = RED,
-                TileType::Player => self.cached_color = GREEN,
-                TileType::Floor => self.cached_color = Self::calc_floor_color(self.weight),
-                TileType::Path => self.cached_color = BLUE,
-                TileType::Weighted(weight) => self.cached_color = Self::calc_floor_color(weight),
-            }
-        }
-    }
-
-    #[inline]
-    fn calc_floor_color(weight: u8) -> Color {
-        if weight > 1 {
-            Color::RGB(255, 230 - (weight / 2) as u8, 255 - weight as u8)
-        } else {
-            WHITE
-        }
+#[inline]
+fn calc_floor_color(weight: u8) -> Color {
+    if weight > 1 {
+        Color::RGB(255, 230 - weight / 2, 255 - weight)
+    } else {
+        WHITE
     }
 }
 
@@ -234,6 +153,10 @@ pub struct Board {
     pub multiple_agents: bool,
     /// Allow multiple goal positions
     pub multiple_goals: bool,
+    /// Seed the current map was generated from, if it was generated
+    pub seed: Option<u64>,
+    /// Neighbors the agents may move to, saved with the board
+    pub movement: MovementRule,
     /// Active pathfinding agents
     pub agents: Vec<Agent>,
     pub updated_tiles: Vec<usize>,
@@ -245,11 +168,37 @@ pub struct Board {
     pub cached_texture: RefCell<Option<Texture<'static>>>,
     /// Whether the board texture needs re-rendering
     pub texture_dirty: RefCell<bool>,
+    /// Search trace being replayed over the floor tiles, if any
+    pub trace_overlay: Option<TraceReplay>,
+    /// Play/pause, speed and step-back history of the path animation
+    pub playback: Playback,
+    /// Undo/redo history of tile edits, generation and map loads
+    pub history: EditHistory,
+    /// Tile changes of the mouse stroke in progress
+    pub edit_stroke: Option<EditBuilder>,
+    /// Obstacles dropped on the paths during the animation, if enabled
+    pub dynamic_obstacles: Option<DynamicObstacles>,
+}
+
+/// Obstacles that appear on the agents' paths while they walk them.
+///
+/// Every `DYNAMIC_OBSTACLE_INTERVAL` animation frames a floor tile ahead of
+/// a random agent becomes an obstacle, and every agent whose remaining path
+/// crosses it replans from where it stands.
+pub struct DynamicObstacles {
+    rng: StdRng,
+    /// Animation frames since the last obstacle
+    moves: u32,
+    /// One replanner per agent, in agent order
+    replanners: Vec<Replanner>,
+    /// Tile indices turned into obstacles, restored by `clear_path`
+    placed: Vec<usize>,
 }
 
 /// Deserialize a Board from JSON.
 ///
-/// Reconstructs the grid from the serialized tile data.
+/// Reads the versioned `BoardFile` format and, for boards saved before it
+/// existed, the original JSON with one entry per tile (version 1).
 impl<'de> Deserialize<'de> for Board {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -265,10 +214,32 @@ impl<'de> Deserialize<'de> for Board {
             goals: Vec<usize>,
             multiple_agents: bool,
             multiple_goals: bool,
+            #[serde(default)]
+            seed: Option<u64>,
             tiles: Vec<[String; 4]>, // Array of [position, type, weight]
         }
 
-        let data = BoardData::deserialize(deserializer)?;
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StoredBoard {
+            Current(BoardFile),
+            Legacy(BoardData),
+        }
+
+        let data = match StoredBoard::deserialize(deserializer)? {
+            StoredBoard::Current(file) => {
+                let grid = file.to_grid().map_err(de::Error::custom)?;
+                let mut board = scanner::board_from_grid(grid);
+                board.width = file.width * file.tile_size[0];
+                board.height = file.height * file.tile_size[1];
+                board.multiple_agents = file.multiple_agents;
+                board.multiple_goals = file.multiple_goals;
+                board.seed = file.seed;
+                board.movement = file.movement;
+                return Ok(board);
+            }
+            StoredBoard::Legacy(data) => data,
+        };
 
         // Rebuild grid from tiles
         let mut grid = Vec::with_capacity((data.tile_amount_x * data.tile_amount_y) as usize);
@@ -276,14 +247,12 @@ impl<'de> Deserialize<'de> for Board {
         let tile_height = data.height / data.tile_amount_y;
 
         for tile_data in data.tiles {
-            //println!("Yes");
             let pos_str = &tile_data[0];
             let type_str = &tile_data[1];
             let weight_str = &tile_data[2];
-            let color_str = &tile_data[3];
-            let color: Vec<&str> = color_str.split(",").collect();
+            // tile_data[3] holds the rendered color, which is derived from type and weight
             let parts: Vec<&str> = pos_str.split(',').collect();
-            if parts.len() == 2 && color.len() == 3 {
+            if parts.len() == 2 {
                 if let (Ok(x), Ok(y)) = (parts[0].parse::<i32>(), parts[1].parse::<i32>()) {
                     let tile_type = match type_str.as_str() {
                         "Floor" => TileType::Floor,
@@ -293,26 +262,11 @@ impl<'de> Deserialize<'de> for Board {
                         "Path" => TileType::Path,
                         _ => TileType::Floor,
                     };
-                    let weight = weight_str.parse::<u8>().unwrap();
-
-                    if let (Ok(r), Ok(g), Ok(b)) = (
-                        color[0].parse::<u8>(),
-                        color[1].parse::<u8>(),
-                        color[2].parse::<u8>(),
-                    ) {
-                        // Saved positions are pixel coords; convert back to grid coords
-                        // since Tile::new() multiplies by tile dimensions
-                        let pos = (x / tile_width as i32, y / tile_height as i32);
-                        grid.push(Tile::new(
-                            pos,
-                            tile_type,
-                            tile_height,
-                            tile_width,
-                            weight,
-                            false,
-                            Color::RGB(r, g, b),
-                        ));
-                    }
+                    let weight = weight_str.parse::<u8>().map_err(de::Error::custom)?;
+
+                    // Saved positions are pixel coords; convert back to grid coords
+                    let pos = (x / tile_width as i32, y / tile_height as i32);
+                    grid.push(Tile::new(pos, tile_type, weight));
                 }
             }
         }
@@ -328,10 +282,17 @@ impl<'de> Deserialize<'de> for Board {
             active: true,
             multiple_agents: data.multiple_agents,
             multiple_goals: data.multiple_goals,
+            seed: data.seed,
+            movement: MovementRule::default(),
             cached_background: None,
             cached_grid: RefCell::new(Some(grid)),
             cached_texture: RefCell::new(None),
             texture_dirty: RefCell::new(false),
+            trace_overlay: None,
+            playback: Playback::default(),
+            history: EditHistory::default(),
+            edit_stroke: None,
+            dynamic_obstacles: None,
             agents: vec![],
             goals: data.goals,
             starts: data.starts,
@@ -340,45 +301,19 @@ impl<'de> Deserialize<'de> for Board {
     }
 }
 
-/// Serialize the Board to JSON.
-///
-/// Converts the grid to a compact format suitable for storage.
+/// Serialize the Board in the current `BoardFile` format.
 impl Serialize for Board {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("Board", 7)?;
-        state.serialize_field("height", &self.height)?;
-        state.serialize_field("width", &self.width)?;
-        state.serialize_field("tile_amount_x", &self.tile_amount_x)?;
-        state.serialize_field("tile_amount_y", &self.tile_amount_y)?;
-        state.serialize_field("starts", &self.starts)?;
-        state.serialize_field("goals", &self.goals)?;
-        state.serialize_field("multiple_agents", &self.multiple_agents)?;
-        state.serialize_field("multiple_goals", &self.multiple_goals)?;
-        self.ensure_grid();
-        let grid_borrow = self.cached_grid.borrow();
-        let tiles: Vec<(String, TileType, String, String)> = grid_borrow
-            .as_ref()
-            .unwrap()
-            .iter()
-            .map(|tile| {
-                (
-                    format!("{},{}", tile.position.0, tile.position.1),
-                    tile.tile_type,
-                    format!("{}", tile.weight),
-                    format!(
-                        "{},{},{}",
-                        tile.cached_color.rgb().0,
-                        tile.cached_color.rgb().1,
-                        tile.cached_color.rgb().2
-                    ),
-                )
-            })
-            .collect();
-        state.serialize_field("tiles", &tiles)?;
-        state.end()
+        let mut file = BoardFile::from_grid(&self.grid_model());
+        file.tile_size = [self.tile_width().max(1), self.tile_height().max(1)];
+        file.multiple_agents = self.multiple_agents;
+        file.multiple_goals = self.multiple_goals;
+        file.seed = self.seed;
+        file.movement = self.movement;
+        file.serialize(serializer)
     }
 }
 
@@ -406,8 +341,8 @@ impl Component for Board {
             return (false, None);
         }
 
-        let tile_x = relative_x / tile_width;
-        let tile_y = relative_y / tile_height;
+        let tile_x = tile_at(relative_x, tile_width, self.width, self.tile_amount_x);
+        let tile_y = tile_at(relative_y, tile_height, self.height, self.tile_amount_y);
 
         if tile_x >= self.tile_amount_x as i32 || tile_y >= self.tile_amount_y as i32 {
             return (false, None);
@@ -424,10 +359,16 @@ impl Component for Board {
             tile_clicked = true;
         }
         if tile_clicked {
+            // Every change made until the mouse is released is undone at once
+            let stroke = self
+                .edit_stroke
+                .get_or_insert_with(|| EditBuilder::new(&self.starts, &self.goals));
             if !self.multiple_agents && self.selected_piece_type == TileType::Player {
                 if !self.starts.is_empty() {
                     for start in &self.starts {
+                        let before = grid[*start];
                         grid[*start].change_tile_type(TileType::Floor);
+                        stroke.tile(*start, before, grid[*start]);
                     }
                     self.starts.clear();
                 }
@@ -436,35 +377,36 @@ impl Component for Board {
             if !self.multiple_goals && self.selected_piece_type == TileType::Enemy {
                 if !self.goals.is_empty() {
                     for goal in &self.goals {
+                        let before = grid[*goal];
                         grid[*goal].change_tile_type(TileType::Floor);
+                        stroke.tile(*goal, before, grid[*goal]);
                     }
                     self.goals.clear();
                 }
             }
 
             if let Some(tile) = grid.get_mut(pos_idx) {
+                let before = *tile;
                 match self.selected_piece_type {
-                    TileType::Obstacle => tile.change_tile_type(TileType::Obstacle),
-                    TileType::Enemy => {
-                        if tile.tile_type != TileType::Enemy {
-                            self.goals.push(pos_idx);
-                            tile.change_tile_type(TileType::Enemy);
-                        }
+                    TileType::Obstacle => {
+                        tile.change_tile_type(TileType::Obstacle);
                     }
-                    TileType::Player => {
-                        if tile.tile_type != TileType::Player {
-                            self.starts.push(pos_idx);
-                            tile.change_tile_type(TileType::Player);
-                        }
+                    TileType::Enemy if tile.tile_type() != TileType::Enemy => {
+                        self.goals.push(pos_idx);
+                        tile.change_tile_type(TileType::Enemy);
+                    }
+                    TileType::Player if tile.tile_type() != TileType::Player => {
+                        self.starts.push(pos_idx);
+                        tile.change_tile_type(TileType::Player);
                     }
                     TileType::Weighted(weight) => {
                         tile.weight = weight;
-                        tile.cached_color = Tile::calc_floor_color(weight);
-                        tile.dirty = true;
                     }
                     _ => {}
                 }
+                stroke.tile(pos_idx, before, *tile);
 
+                self.trace_overlay = None;
                 self.mark_texture_dirty();
                 return (true, Some(self.get_id()));
             }
@@ -537,22 +479,7 @@ impl Board {
         if self.cached_grid.borrow().is_some() {
             return;
         }
-        let tile_amount: usize = (self.tile_amount_x * self.tile_amount_y) as usize;
-        let mut grid = Vec::with_capacity(tile_amount);
-        let tile_width = self.tile_width();
-        let tile_height = self.tile_height();
-        for tile in 0..tile_amount {
-            let num: u8 = 1;
-            grid.push(Tile::new(
-                util::get_coordinate_from_idx(tile, self.tile_amount_x, self.tile_amount_y),
-                TileType::Floor,
-                tile_height,
-                tile_width,
-                num,
-                true,
-                WHITE,
-            ));
-        }
+        let grid = grid::floor_tiles(self.tile_amount_x, self.tile_amount_y);
         self.cached_grid.borrow_mut().replace(grid);
     }
 
@@ -572,141 +499,131 @@ impl Board {
         self.height / self.tile_amount_y
     }
 
-    fn get_random_agents(&mut self) {
-        let tile_amount = (self.tile_amount_x * self.tile_amount_y) as usize;
-        let amount = self.starts.len().max(1) * 2;
-        println!("{}", amount);
-        self.starts.clear();
-        self.goals.clear();
-        println!("{}", amount);
-        let mut rng = rand::rng();
-        let locations = sample(&mut rng, tile_amount, amount).into_vec();
-        let (starts, goals) = locations.split_at(locations.len() / 2);
-        for (i, start) in starts.iter().enumerate() {
-            self.starts.push(*start);
-            self.goals.push(goals[i]);
+    /// Move the board's tiles, starts and goals into a `Grid` model.
+    ///
+    /// Pair with `restore_grid_model` to hand the result back to the board.
+    fn take_grid_model(&mut self) -> Grid {
+        self.ensure_grid();
+        Grid {
+            width: self.tile_amount_x,
+            height: self.tile_amount_y,
+            tiles: self.cached_grid.borrow_mut().take().unwrap_or_default(),
+            starts: std::mem::take(&mut self.starts),
+            goals: std::mem::take(&mut self.goals),
+        }
+    }
+
+    /// Replace the board's tiles, starts and goals with those of `grid`.
+    fn restore_grid_model(&mut self, grid: Grid) {
+        self.tile_amount_x = grid.width;
+        self.tile_amount_y = grid.height;
+        self.starts = grid.starts;
+        self.goals = grid.goals;
+        self.cached_grid.borrow_mut().replace(grid.tiles);
+        self.trace_overlay = None;
+        self.mark_texture_dirty();
+    }
+
+    /// Copy the board's tiles, starts and goals into a `Grid` model.
+    fn grid_model(&self) -> Grid {
+        Grid {
+            width: self.tile_amount_x,
+            height: self.tile_amount_y,
+            tiles: self.grid(),
+            starts: self.starts.clone(),
+            goals: self.goals.clone(),
         }
     }
 
+    /// Finish the current mouse stroke and add it to the edit history.
+    pub fn end_edit_stroke(&mut self) {
+        if let Some(stroke) = self.edit_stroke.take() {
+            if let Some(edit) = stroke.finish(&self.starts, &self.goals) {
+                self.history.record(edit);
+            }
+        }
+    }
+
+    /// Apply a whole-grid change (generation, map load) as one undoable edit.
+    ///
+    /// # Arguments
+    /// * `change` - Modifies the board
+    pub fn record_grid_change<F: FnOnce(&mut Board)>(&mut self, change: F) {
+        self.end_edit_stroke();
+        let before = self.grid_model();
+        change(self);
+        if let Some(edit) = GridEdit::between(&before, &self.grid_model()) {
+            self.history.record(edit);
+        }
+    }
+
+    /// Replace this board with a loaded one.
+    ///
+    /// The edit history is kept, so loading a map can be undone.
+    pub fn load_board(&mut self, board: Board) {
+        self.record_grid_change(|current| {
+            let history = std::mem::take(&mut current.history);
+            *current = board;
+            current.history = history;
+        });
+    }
+
+    /// Undo the most recent edit.
+    ///
+    /// # Returns
+    /// `false` if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        self.step_history(EditHistory::undo)
+    }
+
+    /// Redo the most recently undone edit.
+    ///
+    /// # Returns
+    /// `false` if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.step_history(EditHistory::redo)
+    }
+
+    /// Apply an undo or redo step to the board's grid.
+    fn step_history(&mut self, step: fn(&mut EditHistory, &mut Grid) -> bool) -> bool {
+        self.end_edit_stroke();
+        let mut grid = self.take_grid_model();
+        let changed = step(&mut self.history, &mut grid);
+        self.restore_grid_model(grid);
+        changed
+    }
+
     /// Generate a random grid with obstacles and weighted tiles.
     ///
     /// # Arguments
     /// * `weight_range` - Maximum weight value for weighted tiles
     /// * `obstacle_percentage` - Percentage of tiles that will be obstacles
     /// * `weighted_percentage` - Percentage of tiles that will be weighted
+    /// * `seed` - Seed for the generator, random if `None`
     pub fn generate_random_grid(
         &mut self,
         weight_range: u8,
         obstacle_percentage: usize,
         weighted_percentage: usize,
         random_agents: bool,
+        seed: Option<u64>,
     ) {
-        if random_agents {
-            println!("Yes");
-            self.get_random_agents();
-        }
-        self.mark_texture_dirty();
-        println!("{:#?}", self.starts);
-        println!("{:#?}", self.goals);
-        let tile_amount = (self.tile_amount_x * self.tile_amount_y) as usize;
-        let mut grid: Vec<Tile> = Vec::with_capacity(tile_amount);
-        let tile_width = self.tile_width();
-        let tile_height = self.tile_height();
-        let mut rng = rand::rng();
-        let weighted_number = (tile_amount as f32 * (weighted_percentage as f32 / 100.0)) as usize;
-        let obstacle_number = if obstacle_percentage == 0 {
-            0
-        } else {
-            (tile_amount as u32 / (2 * self.starts.len().max(1) as u32))
-                .min((tile_amount as f32 * (obstacle_percentage as f32 / 100.0)) as u32)
-                as usize
-        };
-
-        for j in 0..self.tile_amount_y {
-            for i in 0..self.tile_amount_x {
-                let position_idx: usize = (j * self.tile_amount_x + i) as usize;
-                let position = (i as i32, j as i32);
-                let num: u8 = 1;
-                if self.starts.contains(&position_idx) {
-                    grid.push(Tile::new(
-                        position,
-                        TileType::Player,
-                        tile_height,
-                        tile_width,
-                        num,
-                        true,
-                        GREEN,
-                    ));
-                } else if self.goals.contains(&position_idx) {
-                    grid.push(Tile::new(
-                        position,
-                        TileType::Enemy,
-                        tile_height,
-                        tile_width,
-                        num,
-                        true,
-                        RED,
-                    ));
-                } else {
-                    grid.push(Tile::new(
-                        position,
-                        TileType::Floor,
-                        tile_height,
-                        tile_width,
-                        num,
-                        true,
-                        Tile::calc_floor_color(num),
-                    ));
-                }
-            }
-        }
-
-        // Collect all floor tile positions
-        let selected: Vec<usize> = grid
-            .iter()
-            .enumerate()
-            .filter(|(_, t)| t.is_floor())
-            .map(|(i, _)| i)
-            .collect();
-        let selected_length = selected.len();
-        let total_special = (obstacle_number + weighted_number).min(selected_length);
-
-        let mut selected: Vec<usize> = selected
-            .into_iter()
-            .choose_multiple(&mut rng, total_special);
-        selected.shuffle(&mut rng);
-
-        // Choose unique positions for obstacles and weighted tiles in one pass
-
-        // Assign obstacles
-        for &pos in selected.iter().take(obstacle_number) {
-            grid[pos].change_tile_type(TileType::Obstacle);
-        }
-
-        // Assign weighted tiles (skip those already made obstacles)
-        for &pos in selected.iter().skip(obstacle_number) {
-            let weight = rng.random_range(1..=weight_range.min(255));
-            grid[pos].weight = weight;
-            grid[pos].cached_color = Tile::calc_floor_color(weight);
-        }
-
-        self.cached_grid.borrow_mut().replace(grid);
+        let mut grid = self.take_grid_model();
+        self.seed = Some(generation::random_grid(
+            &mut grid,
+            weight_range,
+            obstacle_percentage,
+            weighted_percentage,
+            random_agents,
+            seed,
+        ));
+        self.restore_grid_model(grid);
     }
 
     /// Generate a city-style grid with roads and buildings.
     ///
-    /// Creates a grid with:
-    /// - Grid of roads at random intervals
-    /// - Rectangular buildings placed in non-road areas
-    /// - Roads have lower weights for faster traversal
-    ///
-    /// # Arguments
-    /// * `road_weight` - Weight value for road tiles (lower = faster)
-    /// * `road_min_spacing` - Minimum tiles between roads
-    /// * `road_max_spacing` - Maximum tiles between roads
-    /// * `building_density` - Percentage of area covered by buildings (0-100)
-    /// * `building_min_size` - Minimum building dimension
-    /// * `building_max_size` - Maximum building dimension
+    /// See `generation::organic_city` for the meaning of the parameters.
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_organic_city(
         &mut self,
         road_weight: u8,
@@ -716,123 +633,89 @@ impl Board {
         building_min_size: u32,
         building_max_size: u32,
         random_agents: bool,
+        seed: Option<u64>,
     ) {
         self.cached_background = None;
-        if random_agents {
-            self.get_random_agents();
-        }
-        self.mark_texture_dirty();
-        let tile_amount = (self.tile_amount_x * self.tile_amount_y) as usize;
-        let mut grid: Vec<Tile> = Vec::with_capacity(tile_amount);
-        let tile_width = self.tile_width();
-        let tile_height = self.tile_height();
-        let mut rng = rand::rng();
-        let mut grid_allocation: Vec<u8> = vec![0; tile_amount];
-        // road = 1
-        // floor = 0
-        // obstacle = 2
-
-        let mut x = 0;
-        while x < self.tile_amount_x {
-            for j in 0..self.tile_amount_y {
-                let idx = (j as usize) * self.tile_amount_x as usize + (x as usize);
-                if self.starts.contains(&idx) || self.goals.contains(&idx) {
-                    continue;
-                }
-                grid_allocation[idx] = 1;
-            }
-            x += rng.random_range(road_min_spacing..=road_max_spacing);
-        }
-
-        // Generate vertical roads
-        let mut y = 0;
-        while y < self.tile_amount_y {
-            for i in 0..self.tile_amount_x {
-                let idx = (i as usize) + (y as usize) * self.tile_amount_x as usize;
-                if self.starts.contains(&idx) || self.goals.contains(&idx) {
-                    continue;
-                }
-                grid_allocation[idx] = 1;
-            }
-            y += rng.random_range(road_min_spacing..=road_max_spacing);
-        }
-
-        let average_building_size = (building_min_size + building_max_size) as f32;
-
-        let num_buildings =
-            ((tile_amount as f32 / average_building_size) * (building_density / 100.0)) as u32;
-
-        for _ in 0..num_buildings {
-            let start_x = rng.random_range(0..self.tile_amount_x as i32);
-            let start_y = rng.random_range(0..self.tile_amount_y as i32);
-            let width = rng.random_range(building_min_size..=building_max_size) as i32;
-            let height = rng.random_range(building_min_size..=building_max_size) as i32;
-
-            for x in start_x..=(start_x + width).min(self.tile_amount_x as i32 - 1) {
-                for y in start_y..=(start_y + height).min(self.tile_amount_y as i32 - 1) {
-                    let idx = (x as usize) + (y as usize) * self.tile_amount_x as usize;
-                    if self.starts.contains(&idx) || self.goals.contains(&idx) {
-                        continue;
-                    }
-                    if grid_allocation[idx] == 0 {
-                        grid_allocation[idx] = 2;
-                    }
-                }
-            }
-        }
-
-        // Initialize all as floors
-        for j in 0..self.tile_amount_y {
-            for i in 0..self.tile_amount_x {
-                let position = (i as i32, j as i32);
-                let idx = (j as usize) * self.tile_amount_x as usize + (i as usize);
-                let tile_type = if self.starts.contains(&idx) {
-                    TileType::Player
-                } else if self.goals.contains(&idx) {
-                    TileType::Enemy
-                } else if grid_allocation[idx] == 2 {
-                    TileType::Obstacle
-                } else {
-                    TileType::Floor
-                };
-                if grid_allocation[idx] == 1 {
-                    grid.push(Tile::new(
-                        position,
-                        tile_type,
-                        tile_height,
-                        tile_width,
-                        road_weight,
-                        true,
-                        Tile::calc_floor_color(road_weight),
-                    ));
-                } else if grid_allocation[idx] == 2 {
-                    grid.push(Tile::new(
-                        position,
-                        tile_type,
-                        tile_height,
-                        tile_width,
-                        0,
-                        true,
-                        BLACK,
-                    ));
-                } else {
-                    grid.push(Tile::new(
-                        position,
-                        tile_type,
-                        tile_height,
-                        tile_width,
-                        255,
-                        true,
-                        Tile::calc_floor_color(255),
-                    ));
-                }
-            }
-        }
-
-        // Generate horizontal roads
-        // Place random rectangular buildings
-
-        self.cached_grid.borrow_mut().replace(grid);
+        let mut grid = self.take_grid_model();
+        self.seed = Some(generation::organic_city(
+            &mut grid,
+            road_weight,
+            road_min_spacing,
+            road_max_spacing,
+            building_density,
+            building_min_size,
+            building_max_size,
+            random_agents,
+            seed,
+        ));
+        self.restore_grid_model(grid);
+    }
+
+    /// Carve a maze into the board.
+    ///
+    /// See `generation::maze` for the meaning of the parameters.
+    pub fn generate_maze(
+        &mut self,
+        algorithm: MazeAlgorithm,
+        corridor_width: u32,
+        loop_percentage: usize,
+        random_agents: bool,
+        seed: Option<u64>,
+    ) {
+        let mut grid = self.take_grid_model();
+        self.seed = Some(generation::maze(
+            &mut grid,
+            algorithm,
+            corridor_width,
+            loop_percentage,
+            random_agents,
+            seed,
+        ));
+        self.restore_grid_model(grid);
+    }
+
+    /// Grow a cave on the board with a cellular automaton.
+    ///
+    /// See `generation::cave` for the meaning of the parameters.
+    pub fn generate_cave(
+        &mut self,
+        fill_percentage: usize,
+        rules: &CaveRules,
+        random_agents: bool,
+        seed: Option<u64>,
+    ) {
+        let mut grid = self.take_grid_model();
+        self.seed = Some(generation::cave(
+            &mut grid,
+            fill_percentage,
+            rules,
+            random_agents,
+            seed,
+        ));
+        self.restore_grid_model(grid);
+    }
+
+    /// Fill the board with noise terrain.
+    ///
+    /// See `generation::noise_terrain` for the meaning of the parameters.
+    pub fn generate_noise_terrain(
+        &mut self,
+        kind: NoiseKind,
+        weight_range: u8,
+        terrain: &NoiseTerrain,
+        random_agents: bool,
+        seed: Option<u64>,
+    ) {
+        let mut grid = self.take_grid_model();
+        self.seed = Some(generation::noise_terrain(
+            &mut grid,
+            kind,
+            weight_range,
+            terrain,
+            random_agents,
+            seed,
+        ));
+        self.restore_grid_model(grid);
     }
 
     /// Save the board to a JSON file.
@@ -850,44 +733,101 @@ impl Board {
         Ok(())
     }
 
-    /// Create pathfinding agents from start/goal positions.
+    /// Render the board into an off-screen surface.
     ///
-    /// Each start position is paired with the corresponding goal position.
-    fn create_agents(&mut self) -> Result<&'static str, &'static str> {
-        if self.goals.is_empty() || self.starts.is_empty() {
-            return Err("No goals or Agents on Board");
+    /// No window is needed. Path tiles already shown and the remaining
+    /// planned paths are drawn in the path color.
+    ///
+    /// # Arguments
+    /// * `scale` - Pixels per tile (at least 1)
+    /// * `show_trace` - Include the search trace overlay, if there is one
+    pub fn render_image(&self, scale: u32, show_trace: bool) -> Result<Surface<'static>, String> {
+        let scale = scale.max(1);
+        let mut surface = Surface::new(
+            self.tile_amount_x * scale,
+            self.tile_amount_y * scale,
+            PixelFormatEnum::RGB24,
+        )?;
+        let planned: HashSet<(i32, i32)> = self
+            .agents
+            .iter()
+            .flat_map(|agent| agent.path.iter().copied())
+            .collect();
+        for tile in self.grid() {
+            let color = match tile.tile_type() {
+                TileType::Floor | TileType::Weighted(_) if planned.contains(&tile.position) => BLUE,
+                _ if show_trace => self.display_color(&tile),
+                _ => tile_color(&tile),
+            };
+            let rect = Rect::new(
+                tile.position.0 * scale as i32,
+                tile.position.1 * scale as i32,
+                scale,
+                scale,
+            );
+            surface.fill_rect(rect, color)?;
         }
-        let goal_amount: usize = self.goals.len();
-        let start_amount: usize = self.starts.len();
-        let indexes = start_amount.max(goal_amount);
-
-        for i in 0..indexes {
-            let mut start_index = i;
-            let mut goal_index = i;
-            if goal_amount != start_amount {
-                if i > goal_amount - 1 {
-                    goal_index = i % goal_amount;
-                } else if i > start_amount - 1 {
-                    start_index = i % start_amount;
-                }
-            }
+        Ok(surface)
+    }
 
-            let start = util::get_coordinate_from_idx(
-                self.starts[start_index],
-                self.tile_amount_x,
-                self.tile_amount_y,
-            );
-            self.agents.push(Agent {
-                start: start,
-                goal: util::get_coordinate_from_idx(
-                    self.goals[goal_index],
-                    self.tile_amount_x,
-                    self.tile_amount_y,
-                ),
-                position: start,
-                path: vec![],
-            });
+    /// Save the board as a PNG image.
+    ///
+    /// See `render_image` for what is drawn.
+    pub fn export_png(&self, file: &Path, scale: u32, show_trace: bool) -> Result<(), String> {
+        self.render_image(scale, show_trace)?.save(file)
+    }
+
+    /// Plan a path for every start/goal pair without animating it.
+    ///
+    /// Used for headless exports: the paths are stored on the agents and the
+    /// search trace, if requested, is replayed to its end. The board's
+    /// movement rule replaces the one in `search`.
+    pub fn solve_paths(
+        &mut self,
+        algorithm: &str,
+        search: &SearchConfig,
+        trace: bool,
+    ) -> Result<(), &'static str> {
+        let search = &SearchConfig {
+            movement: self.movement,
+            ..*search
+        };
+        self.agents.clear();
+        self.create_agents()?;
+        let grid = self.grid();
+        let (width, height) = (self.tile_amount_x, self.tile_amount_y);
+        let mut search_trace = SearchTrace::default();
+        for agent in &mut self.agents {
+            let result = agent.get_path(algorithm, search, &grid, width, height);
+            if let Some(reason) = result.failure {
+                return Err(reason.description());
+            }
+            agent.path = result.path;
+            agent.waypoints = result.waypoints;
+            if trace {
+                search_trace.extend(agent.trace_search(algorithm, search, &grid, width, height));
+            }
+        }
+        if trace {
+            let mut replay = TraceReplay::new(search_trace);
+            replay.finish();
+            self.trace_overlay = Some(replay);
         }
+        Ok(())
+    }
+
+    /// Create pathfinding agents from start/goal positions.
+    ///
+    /// Each start position is paired with the corresponding goal position.
+    fn create_agents(&mut self) -> Result<&'static str, &'static str> {
+        let model = Grid {
+            width: self.tile_amount_x,
+            height: self.tile_amount_y,
+            tiles: vec![],
+            starts: self.starts.clone(),
+            goals: self.goals.clone(),
+        };
+        self.agents.extend(model.create_agents()?);
         return Ok("Agents Successfully created");
     }
 
@@ -895,16 +835,7 @@ impl Board {
     fn create_data_map(&self, amount: usize) -> HashMap<usize, PathData> {
         let mut data_map: HashMap<usize, PathData> = HashMap::with_capacity(amount);
         for i in 0..amount {
-            data_map.insert(
-                i,
-                PathData {
-                    wcf: vec![],
-                    memory: vec![],
-                    time: vec![],
-                    steps: vec![],
-                    path_cost: vec![],
-                },
-            );
+            data_map.insert(i, PathData::default());
         }
         return data_map;
     }
@@ -915,24 +846,42 @@ impl Board {
     /// It supports:
     /// - Multiple iterations with optional grid regeneration
     /// - Doubling experiment (doubles obstacles each iteration)
-    /// - Multi-threaded pathfinding for multiple agents
+    /// - Multi-threaded pathfinding for multiple agents, or collision-free
+    ///   joint planning when a multi-agent planner is selected
     /// - Real-time visualization of agent movement
     ///
     /// # Arguments
     /// * `canvas` - SDL2 canvas for rendering
     /// * `algorithm` - Name of the pathfinding algorithm to use
+    /// * `search` - Heuristic settings for the informed planners; the board's
+    ///   movement rule replaces the one given here
+    /// * `show_trace` - Record the final search and replay it before the paths
+    /// * `dynamic_obstacles` - Drop obstacles on the paths during playback
+    /// * `multi_agent_planner` - Planner used when several agents walk at once
     /// * `doubling` - If true, double obstacles each iteration
     /// * `dyn_gen` - If true, regenerate grid each iteration
-    /// * `obstacles` - Initial obstacle percentage
-    /// * `weighted_tiles` - Weighted tile percentage
+    /// * `obstacles` - Initial obstacle percentage (corridor width of mazes,
+    ///   fill percentage of caves, mountain percentage of noise terrain)
+    /// * `weighted_tiles` - Weighted tile percentage (loop percentage of mazes,
+    ///   lake percentage of noise terrain)
     /// * `iterations` - Number of iterations to run
     /// * `weight_range` - Maximum tile weight
-    /// * `gen_mode` - Generation mode (Random or City)
+    /// * `gen_mode` - Generation mode (Random, City, a maze, Cave or a noise terrain)
+    /// * `cave_rules` - Smoothing rules of generated caves
+    /// * `noise_terrain` - Scale, octaves and persistence of noise terrain
+    /// * `seed` - Base seed for regenerated grids, random if `None`
+    /// * `cancel_requested` - Polled while the searches run; returning `true`
+    ///   cancels them and ends the run with an error
+    #[allow(clippy::too_many_arguments)]
     pub fn run_board<'a>(
         &mut self,
         canvas: &mut Canvas<Window>,
         texture_creator: &'a TextureCreator<WindowContext>,
         algorithm: &str,
+        search: SearchConfig,
+        show_trace: bool,
+        dynamic_obstacles: bool,
+        multi_agent_planner: MultiAgentPlanner,
         doubling: bool,
         dyn_gen: bool,
         random_agents: bool,
@@ -941,7 +890,15 @@ impl Board {
         iterations: usize,
         weight_range: u8,
         gen_mode: settings::GenerationMode,
+        cave_rules: CaveRules,
+        noise_terrain: NoiseTerrain,
+        seed: Option<u64>,
+        cancel_requested: &mut dyn FnMut() -> bool,
     ) -> Result<String, &'static str> {
+        let search = SearchConfig {
+            movement: self.movement,
+            ..search
+        };
         if !random_agents {
             if self.agents.is_empty() {
                 match self.create_agents() {
@@ -953,11 +910,21 @@ impl Board {
             }
         }
         let mut data_map: HashMap<usize, PathData> = self.create_data_map(iterations);
+        // Regenerating runs replace the map; undo returns to the one before the run
+        let before_run = (doubling || dyn_gen).then(|| self.grid_model());
+        // Every regenerated grid (including retries) takes the next seed of
+        // the series, so the whole run can be repeated from the base seed
+        let base_seed = generation::resolve_seed(seed);
+        let mut generated: u64 = 0;
         let mut obstacles = obstacles as usize;
+        let mut joint_result: Option<MultiAgentResult> = None;
+        let cancel = CancelToken::default();
         for i in 0..iterations {
             let mut valid_iteration = false;
             while !valid_iteration {
                 if doubling || dyn_gen {
+                    let seed = Some(generation::derive_seed(base_seed, generated));
+                    generated += 1;
                     match gen_mode {
                         settings::GenerationMode::Random => {
                             self.generate_random_grid(
@@ -965,6 +932,7 @@ impl Board {
                                 obstacles as usize,
                                 weighted_tiles as usize,
                                 random_agents,
+                                seed,
                             );
                         }
                         settings::GenerationMode::City => {
@@ -976,15 +944,79 @@ impl Board {
                                 2,
                                 weighted_tiles,
                                 random_agents,
+                                seed,
+                            );
+                        }
+                        settings::GenerationMode::Maze(algorithm) => {
+                            self.generate_maze(
+                                algorithm,
+                                obstacles as u32,
+                                weighted_tiles as usize,
+                                random_agents,
+                                seed,
+                            );
+                        }
+                        settings::GenerationMode::Cave => {
+                            self.generate_cave(obstacles, &cave_rules, random_agents, seed);
+                        }
+                        settings::GenerationMode::Noise(kind) => {
+                            let terrain = NoiseTerrain {
+                                mountain_pct: obstacles as u32,
+                                lake_pct: weighted_tiles,
+                                ..noise_terrain
+                            };
+                            self.generate_noise_terrain(
+                                kind,
+                                weight_range,
+                                &terrain,
+                                random_agents,
+                                seed,
                             );
                         }
                     }
                 }
                 if self.agents.is_empty() {
-                    self.create_agents();
+                    self.create_agents()?;
                 }
                 let grid: Arc<Vec<Tile>> = Arc::new(self.grid());
                 valid_iteration = true;
+                if self.agents.len() > 1 && multi_agent_planner != MultiAgentPlanner::Independent {
+                    let (w, h) = (self.tile_amount_x, self.tile_amount_y);
+                    let result = multi_agent::plan_agents(
+                        multi_agent_planner,
+                        &self.agents,
+                        &search.cost_model,
+                        search.movement,
+                        &grid,
+                        w,
+                        h,
+                    );
+                    if let Some(reason) = result.failure {
+                        if !doubling && !dyn_gen {
+                            return Err(reason.description());
+                        }
+                        // Regenerate until the agents can get past each other
+                        valid_iteration = false;
+                        self.agents.clear();
+                        continue;
+                    }
+                    if let Some(iteration_data) = data_map.get_mut(&i) {
+                        iteration_data.update_all(
+                            sobel_method(&grid, w, h),
+                            result.memory,
+                            result.time,
+                            result.expansions,
+                            result.sum_of_costs,
+                        );
+                    }
+                    for (agent, path) in self.agents.iter_mut().zip(result.agent_paths()) {
+                        agent.path = path;
+                        agent.waypoints.clear();
+                    }
+                    joint_result = Some(result);
+                    self.draw(canvas, texture_creator);
+                    continue;
+                }
                 let mut agents_completed_count = 0;
                 while agents_completed_count != self.starts.len() {
                     let mut handles = vec![];
@@ -992,73 +1024,75 @@ impl Board {
                     for agent_idx in 0..self.agents.len() {
                         let algorithm_str = algorithm.to_string();
                         let grid = Arc::clone(&grid);
+                        let cancel = cancel.clone();
 
                         let mut agent_clone = self.agents[agent_idx].clone();
                         let w = self.tile_amount_x;
                         let h = self.tile_amount_y;
 
-                        let handle = thread::spawn(move || {
-                            if agent_clone.is_path_possible(&grid, w, h) {
-                                let (_, path, wcf, memory, time, steps, path_cost) =
-                                    agent_clone.get_path(&algorithm_str, &grid, w, h);
-                                if !path.is_empty() {
-                                    (
-                                        agent_idx,
-                                        Some(path),
-                                        Some(wcf),
-                                        Some(memory),
-                                        Some(time),
-                                        Some(steps),
-                                        Some(path_cost),
-                                    )
-                                } else {
-                                    (
-                                        agent_idx,
-                                        None,
-                                        Some(wcf),
-                                        Some(memory),
-                                        Some(time),
-                                        Some(steps),
-                                        None,
-                                    )
-                                }
+                        let handle = thread::spawn(move || -> (usize, PathResult) {
+                            if agent_clone.is_path_possible(&grid, w, h, search.movement) {
+                                (
+                                    agent_idx,
+                                    agent_clone.get_path_cancellable(
+                                        &algorithm_str,
+                                        &search,
+                                        &cancel,
+                                        &grid,
+                                        w,
+                                        h,
+                                    ),
+                                )
                             } else {
-                                (agent_idx, None, None, None, None, None, None)
+                                (
+                                    agent_idx,
+                                    PathResult::failed(&algorithm_str, FailureReason::Unreachable),
+                                )
                             }
                         });
                         handles.push(handle);
                     }
 
+                    // Wait without blocking input so long searches can be cancelled
+                    while !handles.iter().all(|handle| handle.is_finished()) {
+                        if !cancel.is_cancelled() && cancel_requested() {
+                            cancel.cancel();
+                        }
+                        thread::sleep(Duration::from_millis(10));
+                    }
+
                     // Collect results and update board on main thread
                     for handle in handles {
-                        if let Ok((index, path, wcf, memory, time, steps, path_cost)) =
-                            handle.join()
-                        {
-                            if path.is_none() {
-                                // If any path is not possible, regenerate
-                                if steps.is_none() {
+                        let Ok((index, mut result)) = handle.join() else {
+                            return Err("A search thread panicked");
+                        };
+                        match result.failure {
+                            Some(reason) => {
+                                if !doubling && !dyn_gen || reason == FailureReason::Cancelled {
+                                    return Err(reason.description());
+                                }
+                                // If any path is not possible (or not within the
+                                // search budget), regenerate. Other failures would
+                                // repeat on the same map, so they end the run.
+                                if matches!(
+                                    reason,
+                                    FailureReason::Unreachable
+                                        | FailureReason::Timeout
+                                        | FailureReason::ExpansionLimit
+                                ) {
                                     valid_iteration = false;
-                                    if !doubling && !dyn_gen {
-                                        return Err("No possible Path");
-                                    }
                                 } else {
-                                    if !doubling && !dyn_gen {
-                                        return Err("Path is possible but algorithm couldn't find a solution in a reasonable amount of time");
-                                    }
-                                    break;
+                                    return Err(reason.description());
                                 }
-                            } else {
-                                // Update agent
-                                self.agents[index].path = path.unwrap();
+                            }
+                            None => {
                                 if let Some(iteration_data) = data_map.get_mut(&i) {
-                                    iteration_data.update_all(
-                                        wcf.unwrap_or_default(),
-                                        memory.unwrap_or_default(),
-                                        time.unwrap_or_default(),
-                                        steps.unwrap_or_default(),
-                                        path_cost.unwrap_or_default(),
-                                    );
+                                    iteration_data.record(&result);
                                 }
+                                // Update agent
+                                self.agents[index].path = std::mem::take(&mut result.path);
+                                self.agents[index].waypoints =
+                                    std::mem::take(&mut result.waypoints);
                                 agents_completed_count += 1;
                             }
                         }
@@ -1077,7 +1111,57 @@ impl Board {
             }
             self.draw(canvas, texture_creator);
         }
+        if let Some(before) = before_run {
+            if let Some(edit) = GridEdit::between(&before, &self.grid_model()) {
+                self.history.record(edit);
+            }
+        }
+        if show_trace {
+            // Re-run the final searches with recording on, outside the timed runs
+            let grid = self.grid();
+            let mut trace = SearchTrace::default();
+            for agent in &self.agents {
+                trace.extend(agent.trace_search(
+                    algorithm,
+                    &search,
+                    &grid,
+                    self.tile_amount_x,
+                    self.tile_amount_y,
+                ));
+            }
+            self.trace_overlay = Some(TraceReplay::new(trace));
+        }
+        self.dynamic_obstacles = dynamic_obstacles.then(|| {
+            let grid = self.grid();
+            let (w, h) = (self.tile_amount_x, self.tile_amount_y);
+            let replanners = self
+                .agents
+                .iter()
+                .map(|agent| {
+                    let mut replanner = Replanner::new(algorithm, search);
+                    if replanner.is_incremental() {
+                        // Build the search state later replans repair
+                        replanner.plan(agent.start, agent.goal, &grid, w, h);
+                    }
+                    replanner
+                })
+                .collect();
+            DynamicObstacles {
+                rng: StdRng::seed_from_u64(generation::resolve_seed(seed)),
+                moves: 0,
+                replanners,
+                placed: vec![],
+            }
+        });
         let mut data_display = String::new();
+        if doubling || dyn_gen {
+            data_display += format!("Seed: {}\n", base_seed).as_str();
+        } else if let Some(seed) = self.seed {
+            data_display += format!("Seed: {}\n", seed).as_str();
+        }
+        if let Some(result) = &joint_result {
+            data_display += format!("{}\n", result).as_str();
+        }
         for (_, data) in &data_map {
             data_display += format!("{}", data).as_str();
         }
@@ -1085,73 +1169,261 @@ impl Board {
         return Ok(data_display);
     }
 
+    /// Advance the result animation for one rendered frame.
+    ///
+    /// Applies as many animation frames as the playback speed allows: a
+    /// pending search trace is replayed first, then every agent moves one
+    /// tile along its path per frame.
+    ///
+    /// # Returns
+    /// `true` once every agent has reached its goal and playback is not paused
     pub fn display_path_result(&mut self) -> bool {
+        for _ in 0..self.playback.tick() {
+            if !self.step_forward() {
+                break;
+            }
+            self.drop_dynamic_obstacle();
+        }
+        self.animation_finished() && !self.playback.is_paused()
+    }
+
+    /// Turn a tile ahead of an agent into an obstacle once enough frames
+    /// have passed, and replan every agent whose path it blocks.
+    ///
+    /// Does nothing unless dynamic obstacles are enabled and the search
+    /// trace has finished replaying.
+    fn drop_dynamic_obstacle(&mut self) {
+        if self
+            .trace_overlay
+            .as_ref()
+            .is_some_and(|replay| !replay.is_finished())
+        {
+            return;
+        }
+        let Some(dynamic) = self.dynamic_obstacles.as_mut() else {
+            return;
+        };
+        dynamic.moves += 1;
+        if dynamic.moves < DYNAMIC_OBSTACLE_INTERVAL {
+            return;
+        }
+        let (w, h) = (self.tile_amount_x, self.tile_amount_y);
         let mut grid = self.cached_grid.borrow_mut();
         let grid = grid.as_mut().unwrap();
-        let w = self.tile_amount_x as u32;
-
-        let mut all_finished = true;
 
-        for agent in &mut self.agents {
-            let path_len = agent.path.len();
-            if path_len == 0 {
+        // Remaining paths end at the agent's position, as `obstacle_ahead` expects
+        let remaining: Vec<Vec<(i32, i32)>> = self
+            .agents
+            .iter()
+            .filter(|agent| agent.path.len() > 1)
+            .map(|agent| {
+                let mut path = agent.path.clone();
+                path.push(agent.position);
+                path
+            })
+            .collect();
+        let Some(path) = remaining.choose(&mut dynamic.rng) else {
+            return;
+        };
+        let Some(blocked) = obstacle_ahead(path, grid, w, h, &mut dynamic.rng) else {
+            return;
+        };
+        let Some(idx) = util::get_idx_from_coordinate(blocked, w, h) else {
+            return;
+        };
+        dynamic.moves = 0;
+        grid[idx].change_tile_type(TileType::Obstacle);
+        dynamic.placed.push(idx);
+        self.updated_tiles.push(idx);
+
+        for (agent, replanner) in self.agents.iter_mut().zip(&mut dynamic.replanners) {
+            replanner.tiles_changed(&[blocked]);
+            if !agent.path.contains(&blocked) {
                 continue;
             }
-            if !agent.goal_reached() {
-                all_finished = false;
-            }
+            let mut path = replanner.replan(agent.position, agent.goal, grid, w, h);
+            // Drop the agent's own position; an empty path stops the agent
+            path.pop();
+            agent.path = path;
+            agent.waypoints.clear();
+        }
+    }
 
-            if let Some(pos) = agent.path.pop() {
-                if let Some(pos_idx) = util::get_idx_from_coordinate(pos, w, w) {
-                    if let Some(old_idx) = util::get_idx_from_coordinate(agent.position, w, w) {
-                        if let Some(tile) = grid.get_mut(old_idx) {
-                            if tile.tile_type != TileType::Enemy {
-                                tile.change_tile_type(TileType::Path);
-                                self.updated_tiles.push(old_idx);
-                            }
-                        }
-                    }
+    /// Summary of the dynamic obstacles dropped during the last run.
+    ///
+    /// # Returns
+    /// The number of obstacles and the combined replan measurements, or
+    /// `None` if dynamic obstacles were disabled
+    pub fn replan_summary(&self) -> Option<String> {
+        let dynamic = self.dynamic_obstacles.as_ref()?;
+        let mut stats = ReplanStats::default();
+        for replanner in &dynamic.replanners {
+            stats += replanner.stats;
+        }
+        Some(format!(
+            " Dynamic Obstacles: {}\n{}",
+            dynamic.placed.len(),
+            stats
+        ))
+    }
 
-                    if let Some(tile) = grid.get_mut(pos_idx) {
-                        if tile.tile_type != TileType::Enemy {
-                            tile.change_tile_type(TileType::Player);
-                            self.updated_tiles.push(pos_idx);
-                        }
-                    }
-                }
-                agent.position = pos;
-            } else {
-                if let Some(end_idx) = util::get_idx_from_coordinate(agent.position, w, w) {
-                    if let Some(tile) = grid.get_mut(end_idx) {
-                        if tile.tile_type != TileType::Enemy {
-                            tile.change_tile_type(TileType::Floor);
-                            self.updated_tiles.push(end_idx);
+    /// Check if the search trace and every agent's path have been shown.
+    pub fn animation_finished(&self) -> bool {
+        self.trace_overlay
+            .as_ref()
+            .is_none_or(|replay| replay.is_finished())
+            && self.agents.iter().all(|agent| agent.path.is_empty())
+    }
+
+    /// Apply the next animation frame.
+    ///
+    /// # Returns
+    /// `false` if the animation was already at its end
+    pub fn step_forward(&mut self) -> bool {
+        if self.advance_trace_overlay() {
+            return true;
+        }
+        let mut grid = self.cached_grid.borrow_mut();
+        let grid = grid.as_mut().unwrap();
+        let w = self.tile_amount_x;
+        let h = self.tile_amount_y;
+        let mut frame = PlaybackFrame::default();
+
+        for (agent_idx, agent) in self.agents.iter_mut().enumerate() {
+            let Some(pos) = agent.path.pop() else {
+                continue;
+            };
+            if let Some(pos_idx) = util::get_idx_from_coordinate(pos, w, h) {
+                if let Some(old_idx) = util::get_idx_from_coordinate(agent.position, w, h) {
+                    if let Some(tile) = grid.get_mut(old_idx) {
+                        let previous = tile.tile_type();
+                        if previous != TileType::Enemy && tile.change_tile_type(TileType::Path) {
+                            frame.tiles.push((old_idx, previous));
+                            self.updated_tiles.push(old_idx);
                         }
                     }
                 }
 
-                if let Some(goal_idx) = util::get_idx_from_coordinate(agent.goal, w, w) {
-                    if let Some(tile) = grid.get_mut(goal_idx) {
-                        tile.change_tile_type(TileType::Enemy);
-                        self.updated_tiles.push(goal_idx);
+                if let Some(tile) = grid.get_mut(pos_idx) {
+                    let previous = tile.tile_type();
+                    if previous != TileType::Enemy && tile.change_tile_type(TileType::Player) {
+                        frame.tiles.push((pos_idx, previous));
+                        self.updated_tiles.push(pos_idx);
                     }
                 }
             }
+            frame.moves.push((agent_idx, agent.position));
+            agent.position = pos;
+        }
+
+        if frame.moves.is_empty() {
+            return false;
         }
+        self.playback.record(frame);
+        true
+    }
 
-        all_finished
+    /// Undo the most recent path animation frame.
+    ///
+    /// # Returns
+    /// `false` if there was nothing to undo
+    pub fn step_back(&mut self) -> bool {
+        let Some(frame) = self.playback.undo() else {
+            return false;
+        };
+        let mut grid = self.cached_grid.borrow_mut();
+        let grid = grid.as_mut().unwrap();
+        for &(idx, tile_type) in frame.tiles.iter().rev() {
+            if let Some(tile) = grid.get_mut(idx) {
+                tile.change_tile_type(tile_type);
+                self.updated_tiles.push(idx);
+            }
+        }
+        for &(agent_idx, position) in frame.moves.iter().rev() {
+            if let Some(agent) = self.agents.get_mut(agent_idx) {
+                agent.path.push(agent.position);
+                agent.position = position;
+            }
+        }
+        true
+    }
+
+    /// Apply every remaining animation frame.
+    pub fn jump_to_end(&mut self) {
+        if let Some(replay) = self.trace_overlay.as_mut() {
+            if !replay.is_finished() {
+                replay.finish();
+                self.updated_tiles.clear();
+                self.mark_texture_dirty();
+            }
+        }
+        while self.step_forward() {}
+    }
+
+    /// Apply the next batch of search trace events.
+    ///
+    /// # Returns
+    /// `true` while the replay is still running
+    fn advance_trace_overlay(&mut self) -> bool {
+        let Some(replay) = self.trace_overlay.as_mut() else {
+            return false;
+        };
+        if replay.is_finished() {
+            return false;
+        }
+        let per_frame = (replay.len() / TRACE_REPLAY_FRAMES).max(1);
+        for pos in replay.advance(per_frame) {
+            if let Some(idx) =
+                util::get_idx_from_coordinate(pos, self.tile_amount_x, self.tile_amount_y)
+            {
+                self.updated_tiles.push(idx);
+            }
+        }
+        if replay.is_finished() {
+            // Bake the overlay into the board texture instead of redrawing
+            // every traced tile on each frame
+            self.updated_tiles.clear();
+            self.mark_texture_dirty();
+        }
+        true
+    }
+
+    /// Color a tile is drawn with, including the search trace overlay.
+    ///
+    /// The overlay only covers floor and weighted tiles so starts, goals,
+    /// obstacles and paths stay visible.
+    fn display_color(&self, tile: &Tile) -> Color {
+        match tile.tile_type() {
+            TileType::Floor | TileType::Weighted(_) => self
+                .trace_overlay
+                .as_ref()
+                .and_then(|replay| replay.cell(tile.position))
+                .map(trace_color)
+                .unwrap_or_else(|| tile_color(tile)),
+            _ => tile_color(tile),
+        }
     }
 
     pub fn clear_path(&mut self) {
         let mut grid = self.cached_grid.borrow_mut();
         let grid = grid.as_mut().unwrap();
         for &idx in &self.updated_tiles {
-            if grid[idx].tile_type == TileType::Path {
+            if grid[idx].tile_type() == TileType::Path {
                 grid[idx].change_tile_type(TileType::Floor);
             }
         }
         self.cached_texture.replace(None);
         self.updated_tiles.clear();
+        self.agents
+            .iter_mut()
+            .for_each(|agent| agent.waypoints.clear());
+        if let Some(dynamic) = self.dynamic_obstacles.take() {
+            for idx in dynamic.placed {
+                grid[idx].change_tile_type(TileType::Floor);
+            }
+        }
+        self.trace_overlay = None;
+        self.playback.clear_history();
     }
 
     pub fn reset_board(&mut self) {
@@ -1165,19 +1437,20 @@ impl Board {
 
             let current_idx = current.1 as usize * w + current.0 as usize;
             if let Some(tile) = grid.get_mut(current_idx) {
-                if tile.tile_type != TileType::Enemy {
+                if tile.tile_type() != TileType::Enemy {
                     tile.change_tile_type(TileType::Floor);
                 }
             }
 
             let start_idx = start.1 as usize * w + start.0 as usize;
             if let Some(new_tile) = grid.get_mut(start_idx) {
-                if new_tile.tile_type != TileType::Enemy {
+                if new_tile.tile_type() != TileType::Enemy {
                     new_tile.change_tile_type(TileType::Player);
                 }
             }
         });
         self.agents.clear();
+        self.playback.clear_history();
     }
 
     pub fn mark_texture_dirty(&self) {
@@ -1193,14 +1466,50 @@ impl Board {
         if let Some(grid) = borrow.as_ref() {
             for &idx in &self.updated_tiles {
                 if let Some(tile) = grid.get(idx) {
-                    let tile_rect = tile.get_rect(self.location);
-                    canvas.set_draw_color(tile.cached_color);
-                    canvas.fill_rect(tile_rect).unwrap();
+                    canvas.set_draw_color(self.display_color(tile));
+                    canvas.fill_rect(self.tile_rect(tile, self.location)).unwrap();
                 }
             }
         }
     }
 
+    /// Calculate the screen rectangle of a tile relative to `board_origin`.
+    ///
+    /// Boards with more tiles than pixels (large maps) are downscaled: each
+    /// tile is drawn one pixel wide at its proportional offset.
+    fn tile_rect(&self, tile: &Tile, board_origin: Point) -> Rect {
+        let (x, tile_width) = tile_span(tile.position.0, self.width, self.tile_amount_x);
+        let (y, tile_height) = tile_span(tile.position.1, self.height, self.tile_amount_y);
+        Rect::new(
+            board_origin.x() + x,
+            board_origin.y() + y,
+            tile_width,
+            tile_height,
+        )
+    }
+
+    /// Screen position of the center of the tile at `pos`.
+    fn tile_center(&self, pos: (i32, i32)) -> Point {
+        let (x, tile_width) = tile_span(pos.0, self.width, self.tile_amount_x);
+        let (y, tile_height) = tile_span(pos.1, self.height, self.tile_amount_y);
+        Point::new(
+            self.location.x() + x + tile_width as i32 / 2,
+            self.location.y() + y + tile_height as i32 / 2,
+        )
+    }
+
+    /// Draw the any-angle paths of the agents as lines between tile centers.
+    fn draw_waypoints(&self, canvas: &mut Canvas<Window>) {
+        canvas.set_draw_color(SEGMENT);
+        for agent in &self.agents {
+            for segment in agent.waypoints.windows(2) {
+                canvas
+                    .draw_line(self.tile_center(segment[0]), self.tile_center(segment[1]))
+                    .unwrap();
+            }
+        }
+    }
+
     /// Get the bounding rectangle of the board.
     fn get_rect(&self) -> Rect {
         if self.cached_background.is_none() {
@@ -1243,10 +1552,13 @@ impl Board {
                         .fill_rect(Rect::new(0, 0, self.width, self.height))
                         .unwrap();
 
-                    let mut borrow = self.cached_grid.borrow_mut();
-                    if let Some(grid) = borrow.as_mut() {
-                        for tile in grid.iter_mut() {
-                            tile.draw(true, Point::new(0, 0), target_canvas);
+                    let borrow = self.cached_grid.borrow();
+                    if let Some(grid) = borrow.as_ref() {
+                        for tile in grid.iter() {
+                            target_canvas.set_draw_color(self.display_color(tile));
+                            target_canvas
+                                .fill_rect(self.tile_rect(tile, Point::new(0, 0)))
+                                .unwrap();
                         }
                     }
                 })
@@ -1265,18 +1577,23 @@ impl Board {
             )
             .unwrap();
         self.draw_tiles(canvas);
+        self.draw_waypoints(canvas);
     }
 }
 
 pub use scanner::*;
 
 pub mod scanner {
+    use sdl2::image::LoadSurface;
+    use sdl2::pixels::PixelFormatEnum;
     use sdl2::rect::Point;
+    use sdl2::surface::Surface;
 
-    use crate::{
-        components::board::{Board, TileType},
-        Tile,
-    };
+    use crate::components::board::{Board, TileType};
+    use crate::grid::Grid;
+    use crate::movingai::{self, TerrainCosts};
+    use crate::palette::Palette;
+    use crate::pathfinding::MovementRule;
     pub enum file_type {
         Map,
         Image,
@@ -1292,162 +1609,55 @@ pub mod scanner {
         path::{Path, PathBuf},
     };
 
-    use crate::{colors::*, fileDialog, settings, util};
+    use crate::{fileDialog, history::EditHistory, playback::Playback};
+
+    /// File extensions `board_from` can load.
+    pub const BOARD_EXTENSIONS: [&str; 4] = ["json", "map", "png", "bmp"];
 
+    /// Load a board from a `.json`, `.map`, `.png` or `.bmp` file.
+    ///
+    /// # Arguments
+    /// * `board_size` - Largest side in pixels a `.map` board is drawn at (0 for no limit)
+    /// * `palette` - Colors used to turn image pixels into tiles
+    /// * `terrain` - Weights given to the terrain classes of `.map` files
+    ///
+    /// # Returns
+    /// The board, or an error message (with line and column for malformed maps)
     pub fn board_from(
         file: &str,
         board_size: u32,
-        tile_amount: u32,
-    ) -> Result<Board, &'static str> {
+        palette: &Palette,
+        terrain: &TerrainCosts,
+    ) -> Result<Board, String> {
         match Path::new(file).extension().and_then(OsStr::to_str) {
-            Some(ext) => match ext {
-                "map" => return board_from_map(file, board_size, tile_amount),
-                "json" => return board_from_json(file),
-                _ => return Err("Not a supported file type"),
+            Some(ext) => match ext.to_ascii_lowercase().as_str() {
+                "map" => board_from_map(file, board_size, terrain),
+                "json" => board_from_json(file).map_err(String::from),
+                "png" | "bmp" => board_from_image(file, palette).map_err(String::from),
+                _ => Err(String::from("Not a supported file type")),
             },
-            None => return Err("No file given"),
+            None => Err(String::from("No file given")),
         }
     }
 
+    /// Load a MovingAI `.map` file, one tile per map cell.
+    ///
+    /// Maps too large to draw at two pixels per tile within `board_size`
+    /// pixels are downscaled to fit, keeping their aspect ratio.
     fn board_from_map(
         file: &str,
         board_size: u32,
-        tile_amount: u32,
-    ) -> Result<Board, &'static str> {
-        let mut board_size = board_size;
-        let mut tile_amount_y = tile_amount;
-        let mut tile_amount_x = tile_amount;
-        match fileDialog::read_file(file) {
-            Ok(result) => {
-                for line in result.split("\n") {
-                    if line.contains("height") {
-                        let words: Vec<&str> = line.split_whitespace().collect();
-                        match words[1].parse::<u32>() {
-                            Ok(size) => {
-                                tile_amount_y = size.min(512);
-                            }
-                            Err(_) => {}
-                        }
-                    } else if line.contains("width") {
-                        let words: Vec<&str> = line.split_whitespace().collect();
-                        match words[1].parse::<u32>() {
-                            Ok(size) => {
-                                tile_amount_x = size.min(512);
-                            }
-                            Err(_) => {}
-                        }
-                    }
-                    if line.contains("map") {
-                        break;
-                    }
-                }
-                let mut tiles: Vec<Tile> = Vec::new();
-                let tile_height = 2;
-                let tile_width = 2;
-
-                board_size = tile_amount_x * tile_amount_y;
-
-                println!("{},{},{}", tile_amount_x, tile_amount_y, board_size);
-
-                for (i, char) in result
-                    .chars()
-                    .filter(|c| ['@', '.', 'T', 'G', 'O', 'S', 'W'].contains(c))
-                    .enumerate()
-                {
-                    match char {
-                        '.' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Floor,
-                                tile_height,
-                                tile_width,
-                                1,
-                                true,
-                                WHITE,
-                            ));
-                        }
-                        '@' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Obstacle,
-                                tile_height,
-                                tile_width,
-                                1,
-                                true,
-                                BLACK,
-                            ));
-                        }
-                        'O' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Obstacle,
-                                tile_height,
-                                tile_width,
-                                1,
-                                true,
-                                BLACK,
-                            ));
-                        }
-                        'T' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Obstacle,
-                                tile_height,
-                                tile_width,
-                                1,
-                                true,
-                                BLACK,
-                            ));
-                        }
-                        'S' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Floor,
-                                tile_height,
-                                tile_width,
-                                120,
-                                true,
-                                Tile::calc_floor_color(120),
-                            ));
-                        }
-                        'W' => {
-                            tiles.push(Tile::new(
-                                util::get_coordinate_from_idx(i, tile_amount_x, tile_amount_y),
-                                super::TileType::Floor,
-                                tile_height,
-                                tile_width,
-                                255,
-                                true,
-                                Tile::calc_floor_color(255),
-                            ));
-                        }
-                        _ => {}
-                    }
-                }
-
-                Ok(Board {
-                    location: Point::new(0, 0),
-                    height: tile_amount_y * 2,
-                    width: tile_amount_x * 2,
-                    tile_amount_x: tile_amount_x,
-                    tile_amount_y: tile_amount_y,
-                    selected_piece_type: TileType::Obstacle,
-                    id: String::from("game_board"),
-                    active: true,
-                    multiple_agents: false,
-                    multiple_goals: false,
-                    cached_background: None,
-                    cached_grid: RefCell::new(Some(tiles)),
-                    cached_texture: RefCell::new(None),
-                    texture_dirty: RefCell::new(true),
-                    agents: vec![],
-                    goals: vec![],
-                    starts: vec![],
-                    updated_tiles: vec![],
-                })
-            }
-            Err(_) => return Err("Invalid JSON"),
+        terrain: &TerrainCosts,
+    ) -> Result<Board, String> {
+        let contents = fileDialog::read_file(file).map_err(|_| "Couldn't read file")?;
+        let grid = movingai::parse_map_with_costs(&contents, terrain)?;
+        let mut board = board_from_grid(grid);
+        let longest_side = board.width.max(board.height);
+        if board_size > 0 && longest_side > board_size {
+            board.width = board.width * board_size / longest_side;
+            board.height = board.height * board_size / longest_side;
         }
+        Ok(board)
     }
 
     fn board_from_json(file: &str) -> Result<Board, &'static str> {
@@ -1460,8 +1670,60 @@ pub mod scanner {
         }
     }
 
-    fn board_from_image(file: &str) -> Result<Vec<Tile>, &'static str> {
-        todo!();
+    /// Load a PNG or BMP sketch, one tile per pixel.
+    ///
+    /// Pixels are matched to the nearest palette color; transparent pixels
+    /// are treated as floor.
+    fn board_from_image(file: &str, palette: &Palette) -> Result<Board, &'static str> {
+        let surface = Surface::from_file(file).map_err(|_| "Couldn't read image")?;
+        // RGBA32 always stores the bytes in R, G, B, A order
+        let surface = surface
+            .convert_format(PixelFormatEnum::RGBA32)
+            .map_err(|_| "Unsupported image format")?;
+        let (width, height) = (surface.width(), surface.height());
+        let pitch = surface.pitch() as usize;
+        let pixels: Vec<[u8; 3]> = surface.with_lock(|data| {
+            (0..height as usize)
+                .flat_map(|y| (0..width as usize).map(move |x| y * pitch + x * 4))
+                .map(|i| match data[i + 3] {
+                    0..128 => [255, 255, 255],
+                    _ => [data[i], data[i + 1], data[i + 2]],
+                })
+                .collect()
+        });
+        let grid = palette.grid_from_pixels(width, height, &pixels)?;
+        Ok(board_from_grid(grid))
+    }
+
+    /// Wrap an imported grid in a board drawn at two pixels per tile.
+    pub fn board_from_grid(grid: Grid) -> Board {
+        Board {
+            location: Point::new(0, 0),
+            height: grid.height * 2,
+            width: grid.width * 2,
+            tile_amount_x: grid.width,
+            tile_amount_y: grid.height,
+            selected_piece_type: TileType::Obstacle,
+            id: String::from("game_board"),
+            active: true,
+            multiple_agents: grid.starts.len() > 1,
+            multiple_goals: grid.goals.len() > 1,
+            seed: None,
+            movement: MovementRule::default(),
+            cached_background: None,
+            cached_grid: RefCell::new(Some(grid.tiles)),
+            cached_texture: RefCell::new(None),
+            texture_dirty: RefCell::new(true),
+            trace_overlay: None,
+            playback: Playback::default(),
+            history: EditHistory::default(),
+            edit_stroke: None,
+            dynamic_obstacles: None,
+            agents: vec![],
+            goals: grid.goals,
+            starts: grid.starts,
+            updated_tiles: vec![],
+        }
     }
 }
 
@@ -1485,11 +1747,18 @@ mod tests {
             active: true,
             multiple_agents: false,
             multiple_goals: false,
+            seed: None,
+            movement: MovementRule::default(),
             agents: vec![],
             cached_background: None,
             cached_grid: RefCell::new(None),
             cached_texture: RefCell::new(None),
             texture_dirty: RefCell::new(true),
+            trace_overlay: None,
+            playback: Playback::default(),
+            history: EditHistory::default(),
+            edit_stroke: None,
+            dynamic_obstacles: None,
             updated_tiles: vec![],
         }
     }
@@ -1497,80 +1766,89 @@ mod tests {
     // ------- Tile -------
 
     #[test]
-    fn test_tile_new_position_is_pixel_scaled() {
-        let tile = Tile::new((3, 4), TileType::Floor, 10, 10, 1, false, WHITE);
-        // Position is grid * width/height
-        assert_eq!(tile.position, (30, 40));
+    fn test_tile_rect_is_pixel_scaled() {
+        let board = make_test_board(10, 10);
+        let tile = Tile::new((3, 4), TileType::Floor, 1);
+        // Screen position is grid * width/height
+        let rect = board.tile_rect(&tile, Point::new(0, 0));
+        assert_eq!((rect.x(), rect.y()), (30, 40));
     }
 
     #[test]
     fn test_tile_floor_is_traversable() {
-        let tile = Tile::new((0, 0), TileType::Floor, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Floor, 1);
         assert!(tile.is_traversable());
     }
 
     #[test]
     fn test_tile_obstacle_is_not_traversable() {
-        let tile = Tile::new((0, 0), TileType::Obstacle, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Obstacle, 1);
         assert!(!tile.is_traversable());
     }
 
     #[test]
     fn test_tile_player_is_traversable() {
-        let tile = Tile::new((0, 0), TileType::Player, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Player, 1);
         assert!(tile.is_traversable());
     }
 
     #[test]
     fn test_tile_enemy_is_traversable() {
-        let tile = Tile::new((0, 0), TileType::Enemy, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Enemy, 1);
         assert!(tile.is_traversable());
     }
 
     #[test]
     fn test_tile_weighted_is_traversable() {
-        let tile = Tile::new((0, 0), TileType::Weighted(50), 10, 10, 50, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Weighted(50), 50);
         assert!(tile.is_traversable());
     }
 
     #[test]
     fn test_tile_is_floor_true() {
-        let tile = Tile::new((0, 0), TileType::Floor, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Floor, 1);
         assert!(tile.is_floor());
     }
 
     #[test]
     fn test_tile_is_floor_false_for_obstacle() {
-        let tile = Tile::new((0, 0), TileType::Obstacle, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Obstacle, 1);
         assert!(!tile.is_floor());
     }
 
     #[test]
     fn test_tile_is_floor_false_for_player() {
-        let tile = Tile::new((0, 0), TileType::Player, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Player, 1);
         assert!(!tile.is_floor());
     }
 
     #[test]
-    fn test_tile_change_tile_type_marks_dirty() {
-        let mut tile = Tile::new((0, 0), TileType::Floor, 10, 10, 1, false, WHITE);
-        assert!(!tile.dirty);
-        tile.change_tile_type(TileType::Obstacle);
-        assert!(tile.dirty);
+    fn test_tile_change_tile_type_reports_change() {
+        let mut tile = Tile::new((0, 0), TileType::Floor, 1);
+        assert!(tile.change_tile_type(TileType::Obstacle));
         assert!(!tile.is_traversable());
     }
 
     #[test]
-    fn test_tile_change_tile_type_same_type_not_dirty() {
-        let mut tile = Tile::new((0, 0), TileType::Floor, 10, 10, 1, false, WHITE);
-        tile.change_tile_type(TileType::Floor);
-        assert!(!tile.dirty); // no change
+    fn test_tile_change_tile_type_same_type_no_change() {
+        let mut tile = Tile::new((0, 0), TileType::Floor, 1);
+        assert!(!tile.change_tile_type(TileType::Floor));
+    }
+
+    #[test]
+    fn test_tile_color_by_type() {
+        assert_eq!(tile_color(&Tile::new((0, 0), TileType::Obstacle, 1)), BLACK);
+        assert_eq!(tile_color(&Tile::new((0, 0), TileType::Player, 1)), GREEN);
+        assert_eq!(tile_color(&Tile::new((0, 0), TileType::Enemy, 1)), RED);
+        assert_eq!(tile_color(&Tile::new((0, 0), TileType::Floor, 1)), WHITE);
+        assert_ne!(tile_color(&Tile::new((0, 0), TileType::Floor, 100)), WHITE);
     }
 
     #[test]
     fn test_tile_get_rect() {
-        let tile = Tile::new((2, 3), TileType::Floor, 10, 10, 1, false, WHITE);
-        let rect = tile.get_rect(Point::new(100, 200));
+        let board = make_test_board(10, 10);
+        let tile = Tile::new((2, 3), TileType::Floor, 1);
+        let rect = board.tile_rect(&tile, Point::new(100, 200));
         assert_eq!(rect.x(), 100 + 20); // board_origin.x + pixel position
         assert_eq!(rect.y(), 200 + 30);
         assert_eq!(rect.width(), 10);
@@ -1579,13 +1857,13 @@ mod tests {
 
     #[test]
     fn test_tile_weight_default() {
-        let tile = Tile::new((0, 0), TileType::Floor, 10, 10, 1, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Floor, 1);
         assert_eq!(tile.weight, 1);
     }
 
     #[test]
     fn test_tile_weight_custom() {
-        let tile = Tile::new((0, 0), TileType::Weighted(42), 10, 10, 42, false, WHITE);
+        let tile = Tile::new((0, 0), TileType::Weighted(42), 42);
         assert_eq!(tile.weight, 42);
     }
 
@@ -1737,6 +2015,42 @@ mod tests {
         assert_eq!(loaded.goals, vec![99, 88]);
     }
 
+    #[test]
+    fn test_serialize_writes_versioned_compact_format() {
+        let mut board = make_test_board(40, 40);
+        board.starts = vec![0];
+        board.goals = vec![1599];
+        let json = serde_json::to_string(&board).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], crate::board_file::BOARD_FORMAT_VERSION);
+        assert_eq!(value["layer"], "1600*1");
+        assert!(value.get("tiles").is_none());
+
+        let loaded: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!((loaded.width, loaded.height), (board.width, board.height));
+        assert_eq!(loaded.grid()[0].tile_type(), TileType::Player);
+        assert_eq!(loaded.grid()[1599].tile_type(), TileType::Enemy);
+    }
+
+    #[test]
+    fn test_deserialize_reads_legacy_json() {
+        let json = r#"{"height":20,"width":20,"tile_amount_x":2,"tile_amount_y":2,
+            "starts":[0],"goals":[3],"multiple_agents":false,"multiple_goals":false,
+            "tiles":[["0,0","Player","1","0,255,0"],["10,0","Obstacle","1","0,0,0"],
+                     ["0,10","Floor","7","255,227,248"],["10,10","Enemy","1","255,0,0"]]}"#;
+        let loaded: Board = serde_json::from_str(json).unwrap();
+        assert_eq!((loaded.tile_amount_x, loaded.tile_amount_y), (2, 2));
+        assert_eq!(loaded.starts, vec![0]);
+        assert_eq!(loaded.goals, vec![3]);
+        let grid = loaded.grid();
+        assert!(!grid[1].is_traversable());
+        assert_eq!(grid[2].weight, 7);
+
+        // Re-saving migrates the board to the current format
+        let value = serde_json::to_value(&loaded).unwrap();
+        assert_eq!(value["layer"], "1 0 7 1");
+    }
+
     // ------- Board generate_random_grid -------
 
     #[test]
@@ -1744,7 +2058,7 @@ mod tests {
         let mut board = make_test_board(10, 10);
         board.starts = vec![0];
         board.goals = vec![99];
-        board.generate_random_grid(10, 20, 10, false);
+        board.generate_random_grid(10, 20, 10, false, None);
         let grid = board.grid();
         assert_eq!(grid.len(), 100);
     }
@@ -1754,12 +2068,28 @@ mod tests {
         let mut board = make_test_board(20, 20);
         board.starts = vec![0];
         board.goals = vec![399];
-        board.generate_random_grid(5, 30, 0, false);
+        board.generate_random_grid(5, 30, 0, false, None);
         let grid = board.grid();
         let obstacle_count = grid.iter().filter(|t| !t.is_traversable()).count();
         assert!(obstacle_count > 0);
     }
 
+    #[test]
+    fn test_generate_random_grid_records_seed() {
+        let mut board = make_test_board(10, 10);
+        board.generate_random_grid(5, 30, 10, false, Some(99));
+        assert_eq!(board.seed, Some(99));
+        let json = serde_json::to_string(&board).unwrap();
+        let loaded: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.seed, Some(99));
+        assert_eq!(loaded.grid(), board.grid());
+
+        // Boards saved before seeds were recorded still load
+        let json = json.replace(",\"seed\":99", "");
+        let loaded: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.seed, None);
+    }
+
     // ------- Board create_data_map -------
 
     #[test]
@@ -1788,6 +2118,44 @@ mod tests {
         let _ = std::fs::remove_file(path);
     }
 
+    // ------- Board render_image / export_png -------
+
+    #[test]
+    fn test_render_image_scales_tiles_and_draws_paths() {
+        let mut board = make_test_board(3, 2);
+        board.starts = vec![0];
+        board.goals = vec![2];
+        {
+            let mut grid = board.cached_grid.borrow_mut();
+            let grid = grid.get_or_insert_with(|| grid::floor_tiles(3, 2));
+            grid[0].change_tile_type(TileType::Player);
+            grid[2].change_tile_type(TileType::Enemy);
+            grid[4].change_tile_type(TileType::Obstacle);
+        }
+        board
+            .solve_paths("A* search", &SearchConfig::default(), false)
+            .unwrap();
+
+        let surface = board.render_image(2, false).unwrap();
+        assert_eq!((surface.width(), surface.height()), (6, 4));
+        let pitch = surface.pitch() as usize;
+        let pixel = |x: usize, y: usize| {
+            surface.with_lock(|data| {
+                let i = y * pitch + x * 3;
+                Color::RGB(data[i], data[i + 1], data[i + 2])
+            })
+        };
+        assert_eq!(pixel(1, 1), GREEN);
+        assert_eq!(pixel(2, 0), BLUE);
+        assert_eq!(pixel(3, 3), BLACK);
+        assert_eq!(pixel(1, 3), WHITE);
+
+        let path = std::env::temp_dir().join("pathmaker_test_export.png");
+        board.export_png(&path, 1, false).unwrap();
+        assert!(path.exists());
+        let _ = std::fs::remove_file(path);
+    }
+
     // ------- Board load_board_file -------
 
     #[test]
@@ -1800,12 +2168,84 @@ mod tests {
         assert_eq!(loaded.tile_amount_y, 5);
     }
 
+    #[test]
+    fn test_board_from_image_uses_palette() {
+        use sdl2::surface::Surface;
+
+        // Row 0: start, floor, obstacle; row 1: floor, floor, goal
+        let colors: [[u8; 3]; 6] = [
+            [0, 255, 0],
+            [255, 255, 255],
+            [0, 0, 0],
+            [250, 250, 250],
+            [255, 255, 255],
+            [255, 0, 0],
+        ];
+        let mut surface = Surface::new(3, 2, PixelFormatEnum::RGB24).unwrap();
+        let pitch = surface.pitch() as usize;
+        surface.with_lock_mut(|data| {
+            for (i, color) in colors.iter().enumerate() {
+                let offset = (i / 3) * pitch + (i % 3) * 3;
+                data[offset..offset + 3].copy_from_slice(color);
+            }
+        });
+        let path = std::env::temp_dir().join("pathmaker_test_import.bmp");
+        surface.save_bmp(&path).unwrap();
+
+        let palette = crate::palette::Palette::default();
+        let board = scanner::board_from(
+            path.to_str().unwrap(),
+            0,
+            &palette,
+            &crate::movingai::TerrainCosts::default(),
+        )
+        .unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!((board.tile_amount_x, board.tile_amount_y), (3, 2));
+        assert_eq!(board.starts, vec![0]);
+        assert_eq!(board.goals, vec![5]);
+        let grid = board.grid();
+        assert!(!grid[2].is_traversable());
+        assert!(grid[3].is_floor());
+    }
+
+    #[test]
+    fn test_board_from_large_map_is_downscaled() {
+        let row = format!("W{}\n", ".".repeat(599));
+        let contents = format!(
+            "type octile\nheight 300\nwidth 600\nmap\n{}",
+            row.repeat(300)
+        );
+        let path = std::env::temp_dir().join("pathmaker_test_large.map");
+        std::fs::write(&path, contents).unwrap();
+        let board = scanner::board_from(
+            path.to_str().unwrap(),
+            400,
+            &crate::palette::Palette::default(),
+            &crate::movingai::TerrainCosts::default(),
+        )
+        .unwrap();
+        let _ = std::fs::remove_file(path);
+        assert_eq!((board.tile_amount_x, board.tile_amount_y), (600, 300));
+        assert_eq!((board.width, board.height), (400, 200));
+        assert!(board.grid()[600].is_water());
+
+        // Tiles are one pixel at their proportional offset
+        let last = Tile::new((599, 299), TileType::Floor, 1);
+        assert_eq!(
+            board.tile_rect(&last, Point::new(0, 0)),
+            Rect::new(399, 199, 1, 1)
+        );
+        assert_eq!(tile_at(399, 0, 400, 600), 598);
+        assert_eq!(tile_at(25, 5, 200, 40), 5);
+    }
+
     // ------- Board generate_organic_city -------
 
     #[test]
     fn test_generate_organic_city_creates_grid() {
         let mut board = make_test_board(10, 10);
-        board.generate_organic_city(1, 2, 4, 50.0, 2, 5, false);
+        board.generate_organic_city(1, 2, 4, 50.0, 2, 5, false, None);
         let grid = board.grid();
         assert_eq!(grid.len(), 100);
     }
@@ -1815,7 +2255,7 @@ mod tests {
         let mut board = make_test_board(10, 10);
         board.starts = vec![0];
         board.goals = vec![99];
-        board.generate_organic_city(1, 2, 4, 50.0, 2, 5, true);
+        board.generate_organic_city(1, 2, 4, 50.0, 2, 5, true, None);
         let grid = board.grid();
         assert_eq!(grid.len(), 100);
         assert!(!board.starts.is_empty() || !board.goals.is_empty());
@@ -1882,6 +2322,7 @@ mod tests {
             goal: (2, 2),
             position: (0, 0),
             path: vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+            waypoints: vec![],
         });
 
         let mut calls = 0;
@@ -1905,6 +2346,7 @@ mod tests {
             goal: (2, 0),
             position: (0, 0),
             path: vec![(0, 0), (1, 0), (2, 0)],
+            waypoints: vec![],
         });
 
         while let false = board.display_path_result() {}
@@ -1915,7 +2357,7 @@ mod tests {
             .as_ref()
             .unwrap()
             .iter()
-            .filter(|t| t.tile_type == TileType::Path)
+            .filter(|t| t.tile_type() == TileType::Path)
             .count();
         assert_eq!(path_count, 0);
     }
@@ -1930,12 +2372,14 @@ mod tests {
             goal: (1, 0),
             position: (0, 0),
             path: vec![(1, 0), (0, 0)],
+            waypoints: vec![],
         });
         board.agents.push(Agent {
             start: (2, 2),
             goal: (4, 4),
             position: (2, 2),
             path: vec![(4, 4), (3, 3), (2, 2)],
+            waypoints: vec![],
         });
 
         let mut calls = 0;
@@ -1949,4 +2393,165 @@ mod tests {
         assert_eq!(board.agents[0].position, board.agents[0].goal);
         assert_eq!(board.agents[1].position, board.agents[1].goal);
     }
+
+    // ------- Search trace overlay -------
+
+    fn make_traced_board() -> Board {
+        let mut board = make_test_board(5, 5);
+        let _ = board.grid();
+        let mut trace = SearchTrace::default();
+        trace.record((0, 0), TraceCell::Expanded);
+        trace.record((1, 0), TraceCell::Opened);
+        board.trace_overlay = Some(TraceReplay::new(trace));
+        board
+    }
+
+    #[test]
+    fn test_trace_overlay_replays_before_paths() {
+        let mut board = make_traced_board();
+        board.agents.push(Agent {
+            start: (0, 0),
+            goal: (1, 0),
+            position: (0, 0),
+            path: vec![(1, 0), (0, 0)],
+            waypoints: vec![],
+        });
+
+        // Small traces replay one event per frame
+        assert!(!board.display_path_result());
+        assert!(!board.display_path_result());
+        let replay = board.trace_overlay.as_ref().unwrap();
+        assert!(replay.is_finished());
+        // The agent has not moved while the trace was replaying
+        assert_eq!(board.agents[0].position, (0, 0));
+    }
+
+    #[test]
+    fn test_trace_overlay_colors_floor_tiles_only() {
+        let mut board = make_traced_board();
+        board.trace_overlay.as_mut().unwrap().finish();
+        let grid = board.grid();
+        let floor = Tile::new((1, 0), TileType::Floor, 1);
+        let goal = Tile::new((0, 0), TileType::Enemy, 1);
+        assert_eq!(board.display_color(&floor), TRACE_OPENED_COLOR);
+        assert_eq!(board.display_color(&goal), tile_color(&goal));
+        assert_eq!(board.display_color(&grid[2]), tile_color(&grid[2]));
+    }
+
+    #[test]
+    fn test_clear_path_removes_trace_overlay() {
+        let mut board = make_traced_board();
+        board.clear_path();
+        assert!(board.trace_overlay.is_none());
+    }
+
+    // ------- Playback -------
+
+    fn make_walking_board() -> Board {
+        let mut board = make_test_board(5, 5);
+        let _ = board.grid();
+        board.agents.push(Agent {
+            start: (0, 0),
+            goal: (2, 0),
+            position: (0, 0),
+            path: vec![(2, 0), (1, 0), (0, 0)],
+            waypoints: vec![],
+        });
+        board
+    }
+
+    #[test]
+    fn test_step_back_restores_tiles_and_agents() {
+        let mut board = make_walking_board();
+        let before = board.grid();
+        assert!(board.step_forward());
+        assert!(board.step_forward());
+        assert_eq!(board.agents[0].position, (1, 0));
+
+        assert!(board.step_back());
+        assert!(board.step_back());
+        assert!(!board.step_back());
+        assert_eq!(board.agents[0].position, (0, 0));
+        assert_eq!(board.agents[0].path, vec![(2, 0), (1, 0), (0, 0)]);
+        let after = board.grid();
+        assert!(before
+            .iter()
+            .zip(after.iter())
+            .all(|(a, b)| a.tile_type() == b.tile_type()));
+    }
+
+    #[test]
+    fn test_paused_playback_holds_the_animation() {
+        let mut board = make_walking_board();
+        board.playback.set_paused(true);
+        assert!(!board.display_path_result());
+        assert_eq!(board.playback.frame(), 0);
+
+        board.jump_to_end();
+        assert!(board.animation_finished());
+        assert_eq!(board.agents[0].position, (2, 0));
+        // The last frame stays on screen until playback resumes
+        assert!(!board.display_path_result());
+        board.playback.set_paused(false);
+        assert!(board.display_path_result());
+    }
+
+    // ------- Edit history -------
+
+    fn tile_types(board: &Board) -> Vec<TileType> {
+        board.grid().iter().map(|t| t.tile_type()).collect()
+    }
+
+    #[test]
+    fn test_undo_reverts_a_whole_stroke() {
+        let mut board = make_test_board(5, 5);
+        board.selected_piece_type = TileType::Obstacle;
+        board.on_click(Point::new(15, 5));
+        board.on_click(Point::new(25, 5));
+        board.end_edit_stroke();
+        assert_eq!(board.grid()[1].tile_type(), TileType::Obstacle);
+        assert_eq!(board.grid()[2].tile_type(), TileType::Obstacle);
+
+        // Both tiles were placed in one stroke
+        assert!(board.undo());
+        assert!(tile_types(&board).iter().all(|t| *t == TileType::Floor));
+        assert!(!board.undo());
+        assert!(board.redo());
+        assert_eq!(board.grid()[2].tile_type(), TileType::Obstacle);
+    }
+
+    #[test]
+    fn test_undo_restores_moved_start() {
+        let mut board = make_test_board(5, 5);
+        board.selected_piece_type = TileType::Player;
+        board.on_click(Point::new(5, 5));
+        board.end_edit_stroke();
+        board.on_click(Point::new(45, 45));
+        board.end_edit_stroke();
+        assert_eq!(board.starts, vec![24]);
+
+        assert!(board.undo());
+        assert_eq!(board.starts, vec![0]);
+        assert_eq!(board.grid()[0].tile_type(), TileType::Player);
+        assert_eq!(board.grid()[24].tile_type(), TileType::Floor);
+    }
+
+    #[test]
+    fn test_undo_generation_and_map_load() {
+        let mut board = make_test_board(10, 10);
+        board.starts = vec![0];
+        board.goals = vec![99];
+        let original = tile_types(&board);
+        board.record_grid_change(|b| b.generate_random_grid(10, 30, 10, false, None));
+        assert_ne!(tile_types(&board), original);
+        assert!(board.undo());
+        assert_eq!(tile_types(&board), original);
+
+        board.load_board(make_test_board(4, 4));
+        assert_eq!(board.grid().len(), 16);
+        assert!(board.undo());
+        assert_eq!((board.tile_amount_x, board.tile_amount_y), (10, 10));
+        assert_eq!(tile_types(&board), original);
+        assert_eq!(board.starts, vec![0]);
+    }
 }
@@ -1060,7 +1060,8 @@ impl Interface for CheckBox {
     }
 
     fn change_label(&mut self, new_text: String) {
-        self.label = new_text
+        self.label = new_text;
+        self.cached_texture.replace(None);
     }
 
     fn draw<'a>(
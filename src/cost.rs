@@ -0,0 +1,357 @@
+//! # Cost Model Module
+//!
+//! Defines what a move costs, in one place shared by every planner and by
+//! the path cost reported in `PathResult` and `PathData`.
+//!
+//! A `CostModel` combines:
+//! - **Step multipliers**: the base cost of an orthogonal and a diagonal step
+//! - **Weight rule**: charge the weight of the entered tile, or the average
+//!   of the tiles the step touches
+//! - **Precision**: round every step to a whole number, or keep fractions
+//!
+//! Planners with integer g-scores (Dijkstra, A*, bidirectional A*, D* Lite,
+//! HPA* and the multi-agent planners) store costs in fixed point, `COST_SCALE`
+//! units per unit of cost, so fractional models stay exact to three decimals.
+//! Any-angle planners charge straight segments with `segment_cost`.
+
+use std::f32::consts::SQRT_2;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{get_idx_from_coordinate, Tile};
+
+/// Fixed-point units per unit of cost used by the integer planners.
+pub const COST_SCALE: f32 = 1000.0;
+
+/// Which tile weights a step is charged.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeightRule {
+    /// Weight of the tile being entered
+    #[default]
+    Enter,
+    /// Average of the two tiles of an orthogonal step, or of the four tiles
+    /// around a diagonal step
+    Average,
+}
+
+/// Whether step costs are rounded to whole numbers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CostPrecision {
+    /// Every step is rounded to the nearest whole cost, at least 1
+    #[default]
+    Integer,
+    /// Steps keep their fractional cost
+    Float,
+}
+
+/// Rules for the cost of moving between neighbouring tiles.
+///
+/// Heuristics are priced with the cheapest orthogonal and diagonal steps of
+/// the model (see `Heuristic::cost`), so they stay admissible for any
+/// multipliers.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Cost of an orthogonal step over weight 1 tiles
+    pub orthogonal: f32,
+    /// Cost of a diagonal step over weight 1 tiles
+    pub diagonal: f32,
+    /// Which tile weights a step is charged
+    pub weight_rule: WeightRule,
+    /// Whether step costs are rounded to whole numbers
+    pub precision: CostPrecision,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        CostModel::UNIFORM
+    }
+}
+
+impl CostModel {
+    /// Every step costs the entered tile's weight, diagonals included.
+    pub const UNIFORM: CostModel = CostModel {
+        orthogonal: 1.0,
+        diagonal: 1.0,
+        weight_rule: WeightRule::Enter,
+        precision: CostPrecision::Integer,
+    };
+
+    /// Steps cost their length times the entered tile's weight.
+    pub const OCTILE: CostModel = CostModel {
+        orthogonal: 1.0,
+        diagonal: SQRT_2,
+        weight_rule: WeightRule::Enter,
+        precision: CostPrecision::Float,
+    };
+
+    /// Steps cost their length times the average weight of the tiles they
+    /// touch, so crossing into heavy terrain costs half on either side.
+    pub const AVERAGED: CostModel = CostModel {
+        orthogonal: 1.0,
+        diagonal: SQRT_2,
+        weight_rule: WeightRule::Average,
+        precision: CostPrecision::Float,
+    };
+
+    /// Every preset, in the order shown in the UI.
+    pub const ALL: [CostModel; 3] = [CostModel::UNIFORM, CostModel::OCTILE, CostModel::AVERAGED];
+
+    /// Display name of the model; "Custom" for models that are not a preset.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            CostModel::UNIFORM => "Uniform",
+            CostModel::OCTILE => "Octile",
+            CostModel::AVERAGED => "Averaged",
+            _ => "Custom",
+        }
+    }
+
+    /// Look up a preset by name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<CostModel> {
+        CostModel::ALL
+            .into_iter()
+            .find(|model| model.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Apply the model's precision to a cost.
+    #[inline]
+    fn finish(&self, cost: f32) -> f32 {
+        match self.precision {
+            CostPrecision::Integer => cost.round().max(1.0),
+            CostPrecision::Float => cost,
+        }
+    }
+
+    /// Cost of stepping from `from` to the neighbouring tile `to`.
+    ///
+    /// Whether the step is allowed is up to the caller (see
+    /// `get_possible_moves`); the model only prices it.
+    ///
+    /// # Returns
+    /// `None` if either tile is outside the map
+    pub fn step_cost(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> Option<f32> {
+        let w_from = tile_weight(from, map, width, height)?;
+        let w_to = tile_weight(to, map, width, height)?;
+        let diagonal = from.0 != to.0 && from.1 != to.1;
+        let weight = match self.weight_rule {
+            WeightRule::Enter => w_to,
+            WeightRule::Average if !diagonal => (w_from + w_to) * 0.5,
+            WeightRule::Average => {
+                // Side tiles count even when they are obstacles
+                let side = |pos| tile_weight(pos, map, width, height).unwrap_or(w_from);
+                let sides = side((from.0, to.1)) + side((to.0, from.1));
+                (w_from + w_to + sides) * 0.25
+            }
+        };
+        let multiplier = match diagonal {
+            true => self.diagonal,
+            false => self.orthogonal,
+        };
+        Some(self.finish(weight * multiplier))
+    }
+
+    /// `step_cost` in the fixed-point units of the integer planners.
+    #[inline]
+    pub fn step_units(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> Option<i32> {
+        self.step_cost(from, to, map, width, height).map(to_units)
+    }
+
+    /// Lowest cost of any step, which is charged on weight 1 tiles.
+    ///
+    /// Multiplying a Chebyshev distance by this gives an admissible
+    /// heuristic for every model.
+    pub fn min_step(&self) -> f32 {
+        self.finish(self.orthogonal.min(self.diagonal))
+    }
+
+    /// Lowest cost of an orthogonal step, charged on weight 1 tiles.
+    pub fn min_orthogonal(&self) -> f32 {
+        self.finish(self.orthogonal)
+    }
+
+    /// Lowest cost of a diagonal step, charged on weight 1 tiles.
+    pub fn min_diagonal(&self) -> f32 {
+        self.finish(self.diagonal)
+    }
+
+    /// Cost of moving in a straight line through `cells`, the grid cells of
+    /// the line from first to last (any-angle planners).
+    ///
+    /// The Euclidean length in orthogonal steps is charged at the mean
+    /// weight of the grid steps along the line, so single steps cost the
+    /// same as `step_cost` whenever diagonals cost √2 orthogonal steps.
+    ///
+    /// # Returns
+    /// `None` if a tile on the line is outside the map
+    pub fn segment_cost(
+        &self,
+        cells: &[(i32, i32)],
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> Option<f32> {
+        if cells.len() < 2 {
+            return Some(0.0);
+        }
+        let (from, to) = (cells[0], cells[cells.len() - 1]);
+        let weighted = CostModel {
+            orthogonal: 1.0,
+            diagonal: 1.0,
+            precision: CostPrecision::Float,
+            ..*self
+        };
+        let mut weight = 0.0;
+        for step in cells.windows(2) {
+            weight += weighted.step_cost(step[0], step[1], map, width, height)?;
+        }
+        let entered = (cells.len() - 1) as f32;
+        let length = ((to.0 - from.0) as f32).hypot((to.1 - from.1) as f32);
+        Some(self.finish(length * self.orthogonal * weight / entered))
+    }
+
+    /// Total cost of walking a goal-first path of grid steps.
+    ///
+    /// The walk starts on the last tile, which is therefore never charged.
+    pub fn path_cost(&self, path: &[(i32, i32)], map: &[Tile], width: u32, height: u32) -> f64 {
+        path.windows(2)
+            .filter_map(|step| self.step_cost(step[1], step[0], map, width, height))
+            .map(f64::from)
+            .sum()
+    }
+}
+
+/// Convert a cost to the fixed-point units of the integer planners.
+#[inline]
+pub fn to_units(cost: f32) -> i32 {
+    (cost * COST_SCALE).round() as i32
+}
+
+/// Convert fixed-point units back to a cost.
+#[inline]
+pub fn from_units(units: i64) -> f64 {
+    units as f64 / COST_SCALE as f64
+}
+
+/// Weight of the tile at `pos`, if it is on the map.
+fn tile_weight(pos: (i32, i32), map: &[Tile], width: u32, height: u32) -> Option<f32> {
+    get_idx_from_coordinate(pos, width, height)
+        .and_then(|idx| map.get(idx))
+        .map(|tile| tile.weight as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::TileType;
+
+    /// Helper: 3x3 floor with a weight 5 tile in the middle of the top row
+    fn make_map() -> Vec<Tile> {
+        let mut map = vec![];
+        for y in 0..3 {
+            for x in 0..3 {
+                map.push(Tile::new((x, y), TileType::Floor, 1));
+            }
+        }
+        map[1].weight = 5;
+        map
+    }
+
+    // ------- step_cost -------
+
+    #[test]
+    fn test_step_cost_enter_charges_the_entered_tile() {
+        let map = make_map();
+        let uniform = CostModel::UNIFORM;
+        assert_eq!(uniform.step_cost((0, 0), (1, 0), &map, 3, 3), Some(5.0));
+        assert_eq!(uniform.step_cost((1, 0), (0, 0), &map, 3, 3), Some(1.0));
+        assert_eq!(uniform.step_cost((0, 0), (1, 1), &map, 3, 3), Some(1.0));
+        assert_eq!(uniform.step_cost((2, 2), (3, 2), &map, 3, 3), None);
+
+        let octile = CostModel::OCTILE;
+        assert_eq!(octile.step_cost((0, 0), (1, 1), &map, 3, 3), Some(SQRT_2));
+        assert_eq!(octile.step_cost((0, 0), (1, 0), &map, 3, 3), Some(5.0));
+    }
+
+    #[test]
+    fn test_step_cost_average_includes_side_tiles_of_diagonals() {
+        let map = make_map();
+        let averaged = CostModel::AVERAGED;
+        assert_eq!(averaged.step_cost((0, 0), (1, 0), &map, 3, 3), Some(3.0));
+        assert_eq!(averaged.step_cost((1, 0), (0, 0), &map, 3, 3), Some(3.0));
+        // (0, 0), (1, 1) and the sides (1, 0) and (0, 1)
+        let diagonal = averaged.step_cost((0, 0), (1, 1), &map, 3, 3).unwrap();
+        assert!((diagonal - 2.0 * SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_integer_precision_rounds_every_step() {
+        let map = make_map();
+        let rounded = CostModel {
+            precision: CostPrecision::Integer,
+            ..CostModel::OCTILE
+        };
+        assert_eq!(rounded.step_cost((0, 0), (1, 1), &map, 3, 3), Some(1.0));
+        assert_eq!(rounded.step_units((0, 0), (1, 1), &map, 3, 3), Some(1000));
+        assert_eq!(
+            CostModel::OCTILE.step_units((0, 0), (1, 1), &map, 3, 3),
+            Some(1414)
+        );
+        let cheap = CostModel {
+            orthogonal: 0.2,
+            ..CostModel::UNIFORM
+        };
+        assert_eq!(cheap.min_step(), 1.0);
+    }
+
+    // ------- path_cost and segment_cost -------
+
+    #[test]
+    fn test_path_cost_skips_the_start_tile() {
+        let map = make_map();
+        let model = CostModel::UNIFORM;
+        let path = vec![(2, 0), (1, 0), (0, 0)];
+        assert_eq!(model.path_cost(&path, &map, 3, 3), 5.0 + 1.0);
+        assert_eq!(model.path_cost(&[(0, 0)], &map, 3, 3), 0.0);
+        assert_eq!(model.path_cost(&[], &map, 3, 3), 0.0);
+    }
+
+    #[test]
+    fn test_segment_cost_matches_single_octile_steps() {
+        let map = make_map();
+        for model in [CostModel::OCTILE, CostModel::AVERAGED] {
+            for to in [(1, 0), (1, 1), (0, 1)] {
+                let step = model.step_cost((0, 0), to, &map, 3, 3).unwrap();
+                let segment = model.segment_cost(&[(0, 0), to], &map, 3, 3).unwrap();
+                assert!((step - segment).abs() < 1e-5, "{} {:?}", model.name(), to);
+            }
+        }
+    }
+
+    #[test]
+    fn test_presets_round_trip_by_name() {
+        for model in CostModel::ALL {
+            assert_eq!(CostModel::from_name(model.name()), Some(model));
+        }
+        let custom = CostModel {
+            diagonal: 1.5,
+            ..CostModel::UNIFORM
+        };
+        assert_eq!(custom.name(), "Custom");
+        assert_eq!(CostModel::from_name("custom"), None);
+        assert_eq!(CostModel::default(), CostModel::UNIFORM);
+    }
+}
@@ -8,11 +8,10 @@
 //! - Benchmark data persistence
 //!
 //! ## File Format
-//! Maps are stored as JSON files containing:
+//! Maps are stored as versioned JSON files (see `board_file`) containing:
 //! - Tile dimensions and amounts
-//! - Obstacle positions
 //! - Player and enemy positions
-//! - Tile weights
+//! - A run-length encoded layer of obstacles and tile weights
 
 use std::{
     cell::RefCell,
@@ -27,6 +26,7 @@ use serde_json::{self, Value};
 use std::collections::HashMap;
 
 use crate::benchmarks::PathData;
+use crate::components::board::scanner::BOARD_EXTENSIONS;
 
 /// Represents a node in the directory tree structure.
 ///
@@ -56,7 +56,8 @@ pub fn is_directory(path: &str) -> bool {
 
 /// Build a DirectoryNode tree from a filesystem path, scanning only one level deep.
 ///
-/// Only includes JSON files and directories (excluding hidden entries).
+/// Only includes loadable boards (see `scanner::BOARD_EXTENSIONS`) and
+/// directories (excluding hidden entries).
 /// Child directories are listed but their contents are **not** recursively scanned;
 /// they are loaded on demand via `ensure_children_loaded()`.
 ///
@@ -66,7 +67,6 @@ pub fn is_directory(path: &str) -> bool {
 /// # Returns
 /// A DirectoryNode representing the path and its immediate children
 fn build_shallow(path: &Path) -> DirectoryNode {
-    let allowed_extension = (OsStr::new("json"), OsStr::new("map"));
     let name = path
         .file_name()
         .map(|s| s.to_string_lossy().into_owned())
@@ -96,8 +96,8 @@ fn build_shallow(path: &Path) -> DirectoryNode {
                             is_dir: true,
                             children: Vec::new(),
                         });
-                    } else if let Some(ext) = p.extension() {
-                        if ext == allowed_extension.0 || ext == allowed_extension.1 {
+                    } else if let Some(ext) = p.extension().and_then(OsStr::to_str) {
+                        if BOARD_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) {
                             node.children.push(DirectoryNode {
                                 name: child_name,
                                 path: p,
@@ -0,0 +1,1469 @@
+//! # Map Generation Module
+//!
+//! This module fills a `Grid` with procedurally generated content:
+//! - **Random**: uniformly scattered obstacles and weighted tiles
+//! - **City**: a lattice of cheap roads with rectangular buildings in between
+//! - **Maze**: corridors carved by a recursive backtracker, Prim's,
+//!   Kruskal's or Wilson's algorithm, optionally braided with loops
+//! - **Cave**: random noise smoothed by a cellular automaton into organic
+//!   caverns, optionally cut down to the largest connected region
+//! - **Noise terrain**: smoothly varying weights from multi-octave Perlin,
+//!   simplex or value noise, with the highest tiles as mountains and the
+//!   lowest as lakes
+//!
+//! Generators keep the grid's starts and goals (optionally re-rolling them
+//! first) and never place obstacles on top of them. Mazes re-roll them at
+//! maximal distance instead of at random.
+//!
+//! ## Seeds
+//! Every generator takes an optional seed and returns the seed it used. The
+//! same seed, parameters and grid always produce the same map; without a seed
+//! a random one is drawn so the map can still be reproduced later.
+
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::seq::IteratorRandom;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::grid::{Grid, Tile, TileType};
+
+/// Algorithm carving the spanning tree of a maze.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MazeAlgorithm {
+    /// Depth-first carving: long, winding corridors with few branches
+    #[default]
+    RecursiveBacktracker,
+    /// Randomized Prim's algorithm: many short dead ends
+    Prim,
+    /// Randomized Kruskal's algorithm: joins random walls between regions
+    Kruskal,
+    /// Loop-erased random walks: every spanning tree is equally likely
+    Wilson,
+}
+
+impl MazeAlgorithm {
+    /// Every maze algorithm, in the order shown in the UI.
+    pub const ALL: [MazeAlgorithm; 4] = [
+        MazeAlgorithm::RecursiveBacktracker,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Wilson,
+    ];
+
+    /// Display name of the algorithm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MazeAlgorithm::RecursiveBacktracker => "Recursive Backtracker",
+            MazeAlgorithm::Prim => "Prim",
+            MazeAlgorithm::Kruskal => "Kruskal",
+            MazeAlgorithm::Wilson => "Wilson",
+        }
+    }
+}
+
+/// Return `seed`, or a freshly drawn random seed if there is none.
+pub fn resolve_seed(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::rng().random())
+}
+
+/// Seed for the `index`-th map of a series started from `base`.
+///
+/// Used by repeated generation (benchmark runs, dynamic iterations) so that
+/// every map of the series differs but can be regenerated on its own.
+pub fn derive_seed(base: u64, index: u64) -> u64 {
+    base.wrapping_add(index)
+}
+
+/// Replace the grid's starts and goals with random, distinct tiles.
+///
+/// The number of agents is kept (at least one start/goal pair is placed).
+///
+/// # Returns
+/// The seed used
+pub fn random_agents(grid: &mut Grid, seed: Option<u64>) -> u64 {
+    let seed = resolve_seed(seed);
+    place_random_agents(grid, &mut StdRng::seed_from_u64(seed));
+    seed
+}
+
+/// Place random starts and goals using `rng`.
+fn place_random_agents<R: Rng>(grid: &mut Grid, rng: &mut R) {
+    let tile_amount = grid.tile_amount();
+    let amount = (grid.starts.len().max(1) * 2).min(tile_amount);
+    grid.starts.clear();
+    grid.goals.clear();
+    let locations = sample(rng, tile_amount, amount).into_vec();
+    let (starts, goals) = locations.split_at(locations.len() / 2);
+    for (i, start) in starts.iter().enumerate() {
+        grid.starts.push(*start);
+        grid.goals.push(goals[i]);
+    }
+}
+
+/// Build the tile for `idx`, marking starts and goals.
+fn base_tile(grid: &Grid, idx: usize, position: (i32, i32), weight: u8) -> Tile {
+    if grid.starts.contains(&idx) {
+        Tile::new(position, TileType::Player, weight)
+    } else if grid.goals.contains(&idx) {
+        Tile::new(position, TileType::Enemy, weight)
+    } else {
+        Tile::new(position, TileType::Floor, weight)
+    }
+}
+
+/// Generate a random grid with obstacles and weighted tiles.
+///
+/// # Arguments
+/// * `grid` - Grid to regenerate in place
+/// * `weight_range` - Maximum weight value for weighted tiles
+/// * `obstacle_percentage` - Percentage of tiles that will be obstacles
+/// * `weighted_percentage` - Percentage of tiles that will be weighted
+/// * `random_agents` - Re-roll the starts and goals before generating
+/// * `seed` - Seed for the generator, random if `None`
+///
+/// # Returns
+/// The seed used
+pub fn random_grid(
+    grid: &mut Grid,
+    weight_range: u8,
+    obstacle_percentage: usize,
+    weighted_percentage: usize,
+    random_agents: bool,
+    seed: Option<u64>,
+) -> u64 {
+    let seed = resolve_seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    if random_agents {
+        place_random_agents(grid, &mut rng);
+    }
+    let tile_amount = grid.tile_amount();
+    let mut tiles: Vec<Tile> = Vec::with_capacity(tile_amount);
+    let weighted_number = (tile_amount as f32 * (weighted_percentage as f32 / 100.0)) as usize;
+    let obstacle_number = if obstacle_percentage == 0 {
+        0
+    } else {
+        (tile_amount as u32 / (2 * grid.starts.len().max(1) as u32))
+            .min((tile_amount as f32 * (obstacle_percentage as f32 / 100.0)) as u32)
+            as usize
+    };
+
+    for j in 0..grid.height {
+        for i in 0..grid.width {
+            let position_idx: usize = (j * grid.width + i) as usize;
+            tiles.push(base_tile(grid, position_idx, (i as i32, j as i32), 1));
+        }
+    }
+
+    // Collect all floor tile positions
+    let selected: Vec<usize> = tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.is_floor())
+        .map(|(i, _)| i)
+        .collect();
+    let selected_length = selected.len();
+    let total_special = (obstacle_number + weighted_number).min(selected_length);
+
+    // Choose unique positions for obstacles and weighted tiles in one pass
+    let mut selected: Vec<usize> = selected
+        .into_iter()
+        .choose_multiple(&mut rng, total_special);
+    selected.shuffle(&mut rng);
+
+    // Assign obstacles
+    for &pos in selected.iter().take(obstacle_number) {
+        tiles[pos].change_tile_type(TileType::Obstacle);
+    }
+
+    // Assign weighted tiles (skip those already made obstacles)
+    for &pos in selected.iter().skip(obstacle_number) {
+        tiles[pos].weight = rng.random_range(1..=weight_range.max(1));
+    }
+
+    grid.tiles = tiles;
+    seed
+}
+
+/// Generate a city-style grid with roads and buildings.
+///
+/// Creates a grid with:
+/// - Grid of roads at random intervals
+/// - Rectangular buildings placed in non-road areas
+/// - Roads have lower weights for faster traversal
+///
+/// # Arguments
+/// * `grid` - Grid to regenerate in place
+/// * `road_weight` - Weight value for road tiles (lower = faster)
+/// * `road_min_spacing` - Minimum tiles between roads
+/// * `road_max_spacing` - Maximum tiles between roads (at least `road_min_spacing`)
+/// * `building_density` - Percentage of area covered by buildings (0-100)
+/// * `building_min_size` - Minimum building dimension
+/// * `building_max_size` - Maximum building dimension (at least `building_min_size`)
+/// * `random_agents` - Re-roll the starts and goals before generating
+/// * `seed` - Seed for the generator, random if `None`
+///
+/// # Returns
+/// The seed used
+#[allow(clippy::too_many_arguments)]
+pub fn organic_city(
+    grid: &mut Grid,
+    road_weight: u8,
+    road_min_spacing: u32,
+    road_max_spacing: u32,
+    building_density: f32,
+    building_min_size: u32,
+    building_max_size: u32,
+    random_agents: bool,
+    seed: Option<u64>,
+) -> u64 {
+    let seed = resolve_seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    if random_agents {
+        place_random_agents(grid, &mut rng);
+    }
+    let width = grid.width;
+    let height = grid.height;
+    let tile_amount = grid.tile_amount();
+    let road_max_spacing = road_max_spacing.max(road_min_spacing);
+    let building_max_size = building_max_size.max(building_min_size);
+    let mut grid_allocation: Vec<u8> = vec![0; tile_amount];
+    // road = 1
+    // floor = 0
+    // obstacle = 2
+
+    // Generate horizontal roads
+    let mut x = 0;
+    while x < width {
+        for j in 0..height {
+            let idx = (j as usize) * width as usize + (x as usize);
+            if grid.starts.contains(&idx) || grid.goals.contains(&idx) {
+                continue;
+            }
+            grid_allocation[idx] = 1;
+        }
+        x += rng.random_range(road_min_spacing..=road_max_spacing);
+    }
+
+    // Generate vertical roads
+    let mut y = 0;
+    while y < height {
+        for i in 0..width {
+            let idx = (i as usize) + (y as usize) * width as usize;
+            if grid.starts.contains(&idx) || grid.goals.contains(&idx) {
+                continue;
+            }
+            grid_allocation[idx] = 1;
+        }
+        y += rng.random_range(road_min_spacing..=road_max_spacing);
+    }
+
+    // Place random rectangular buildings
+    let average_building_size = (building_min_size + building_max_size) as f32;
+
+    let num_buildings =
+        ((tile_amount as f32 / average_building_size) * (building_density / 100.0)) as u32;
+
+    for _ in 0..num_buildings {
+        let start_x = rng.random_range(0..width as i32);
+        let start_y = rng.random_range(0..height as i32);
+        let building_width = rng.random_range(building_min_size..=building_max_size) as i32;
+        let building_height = rng.random_range(building_min_size..=building_max_size) as i32;
+
+        for x in start_x..=(start_x + building_width).min(width as i32 - 1) {
+            for y in start_y..=(start_y + building_height).min(height as i32 - 1) {
+                let idx = (x as usize) + (y as usize) * width as usize;
+                if grid.starts.contains(&idx) || grid.goals.contains(&idx) {
+                    continue;
+                }
+                if grid_allocation[idx] == 0 {
+                    grid_allocation[idx] = 2;
+                }
+            }
+        }
+    }
+
+    let mut tiles: Vec<Tile> = Vec::with_capacity(tile_amount);
+    for j in 0..height {
+        for i in 0..width {
+            let position = (i as i32, j as i32);
+            let idx = (j as usize) * width as usize + (i as usize);
+            let tile = match grid_allocation[idx] {
+                1 => base_tile(grid, idx, position, road_weight),
+                2 => Tile::new(position, TileType::Obstacle, 1),
+                _ => base_tile(grid, idx, position, 255),
+            };
+            tiles.push(tile);
+        }
+    }
+
+    grid.tiles = tiles;
+    seed
+}
+
+/// Cellular-automaton rules smoothing a cave.
+///
+/// Each iteration counts the walls among a tile's 8 neighbors (tiles past
+/// the map edge count as walls): a floor tile turns into a wall when at
+/// least `birth_limit` are walls, a wall stays a wall while at least
+/// `death_limit` are.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaveRules {
+    /// Number of smoothing passes
+    pub iterations: u32,
+    /// Wall neighbors that turn a floor tile into a wall (0-8)
+    pub birth_limit: u8,
+    /// Wall neighbors that keep a wall standing (0-8)
+    pub death_limit: u8,
+    /// Fill every region but the largest, so all floor tiles are connected
+    pub keep_largest_region: bool,
+}
+
+impl Default for CaveRules {
+    fn default() -> Self {
+        CaveRules {
+            iterations: 5,
+            birth_limit: 5,
+            death_limit: 4,
+            keep_largest_region: true,
+        }
+    }
+}
+
+impl fmt::Display for CaveRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} iterations, birth {}, death {}{}",
+            self.iterations,
+            self.birth_limit,
+            self.death_limit,
+            if self.keep_largest_region {
+                ", largest region"
+            } else {
+                ""
+            }
+        )
+    }
+}
+
+/// Lattice noise summed over the octaves of a noise terrain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseKind {
+    /// Gradient noise on a square lattice
+    #[default]
+    Perlin,
+    /// Gradient noise on a triangular lattice, with fewer axis-aligned artifacts
+    Simplex,
+    /// Interpolated random values: blobbier than gradient noise
+    Value,
+}
+
+impl NoiseKind {
+    /// Every noise kind, in the order shown in the UI.
+    pub const ALL: [NoiseKind; 3] = [NoiseKind::Perlin, NoiseKind::Simplex, NoiseKind::Value];
+
+    /// Display name of the noise.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseKind::Perlin => "Perlin",
+            NoiseKind::Simplex => "Simplex",
+            NoiseKind::Value => "Value",
+        }
+    }
+}
+
+/// Shape of a noise terrain.
+///
+/// Octave `n` samples the noise at `2^n / scale` cycles per tile and counts
+/// `persistence^n` as much as the first one.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseTerrain {
+    /// Size of the largest features in tiles
+    pub scale: f32,
+    /// Number of noise layers added together
+    pub octaves: u32,
+    /// Amplitude of each octave relative to the previous one (0-1)
+    pub persistence: f32,
+    /// Percentage of tiles, the highest, turned into obstacles
+    pub mountain_pct: u32,
+    /// Percentage of tiles, the lowest, turned into water
+    pub lake_pct: u32,
+}
+
+impl Default for NoiseTerrain {
+    fn default() -> Self {
+        NoiseTerrain {
+            scale: 32.0,
+            octaves: 4,
+            persistence: 0.5,
+            mountain_pct: 0,
+            lake_pct: 0,
+        }
+    }
+}
+
+impl fmt::Display for NoiseTerrain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "scale {}, {} octaves, persistence {}, {}% mountains, {}% lakes",
+            self.scale, self.octaves, self.persistence, self.mountain_pct, self.lake_pct
+        )
+    }
+}
+
+/// Directions of the lattice gradients.
+const NOISE_GRADIENTS: [(f64, f64); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (1.0, 1.0),
+    (-1.0, 1.0),
+    (1.0, -1.0),
+    (-1.0, -1.0),
+];
+
+/// Seeded lattice noise, sampled in roughly [-1, 1].
+struct LatticeNoise {
+    permutation: Vec<usize>,
+}
+
+impl LatticeNoise {
+    fn new<R: Rng>(rng: &mut R) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        permutation.shuffle(rng);
+        permutation.extend_from_within(..);
+        LatticeNoise { permutation }
+    }
+
+    /// Pseudo-random byte of a lattice point.
+    fn hash(&self, x: i64, y: i64) -> usize {
+        self.permutation[self.permutation[(x & 255) as usize] + (y & 255) as usize]
+    }
+
+    fn sample(&self, kind: NoiseKind, x: f64, y: f64) -> f64 {
+        match kind {
+            NoiseKind::Perlin => self.square(x, y, |cx, cy, dx, dy| {
+                let (gx, gy) = NOISE_GRADIENTS[self.hash(cx, cy) & 7];
+                gx * dx + gy * dy
+            }),
+            NoiseKind::Value => {
+                self.square(x, y, |cx, cy, _, _| self.hash(cx, cy) as f64 / 127.5 - 1.0)
+            }
+            NoiseKind::Simplex => self.simplex(x, y),
+        }
+    }
+
+    /// Blend the values of the four lattice corners around (x, y).
+    fn square(&self, x: f64, y: f64, corner: impl Fn(i64, i64, f64, f64) -> f64) -> f64 {
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x0, y - y0);
+        let (cx, cy) = (x0 as i64, y0 as i64);
+        let (u, v) = (fade(dx), fade(dy));
+        lerp(
+            lerp(corner(cx, cy, dx, dy), corner(cx + 1, cy, dx - 1.0, dy), u),
+            lerp(
+                corner(cx, cy + 1, dx, dy - 1.0),
+                corner(cx + 1, cy + 1, dx - 1.0, dy - 1.0),
+                u,
+            ),
+            v,
+        )
+    }
+
+    /// 2D simplex noise: sum the falloff of the three triangle corners.
+    fn simplex(&self, x: f64, y: f64) -> f64 {
+        let skew = 0.5 * (3.0f64.sqrt() - 1.0);
+        let unskew = (3.0 - 3.0f64.sqrt()) / 6.0;
+        let s = (x + y) * skew;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * unskew;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f64 + unskew, y0 - j1 as f64 + unskew),
+            (1, 1, x0 - 1.0 + 2.0 * unskew, y0 - 1.0 + 2.0 * unskew),
+        ];
+        let (i, j) = (i as i64, j as i64);
+        70.0 * corners
+            .iter()
+            .map(|&(ci, cj, dx, dy)| {
+                let falloff = 0.5 - dx * dx - dy * dy;
+                if falloff <= 0.0 {
+                    return 0.0;
+                }
+                let (gx, gy) = NOISE_GRADIENTS[self.hash(i + ci, j + cj) & 7];
+                falloff.powi(4) * (gx * dx + gy * dy)
+            })
+            .sum::<f64>()
+    }
+}
+
+/// Generate terrain whose weights follow multi-octave noise.
+///
+/// The summed noise is stretched over the map so the lowest land tile
+/// weighs 1 and the highest `weight_range`. The highest `mountain_pct`
+/// percent of tiles become obstacles and the lowest `lake_pct` percent
+/// water; starts and goals always stay land.
+///
+/// # Arguments
+/// * `grid` - Grid to regenerate in place
+/// * `kind` - Noise summed over the octaves
+/// * `weight_range` - Weight of the highest land tiles
+/// * `terrain` - Scale, octaves, persistence and thresholds of the terrain
+/// * `random_agents` - Re-roll the starts and goals before generating
+/// * `seed` - Seed for the generator, random if `None`
+///
+/// # Returns
+/// The seed used
+pub fn noise_terrain(
+    grid: &mut Grid,
+    kind: NoiseKind,
+    weight_range: u8,
+    terrain: &NoiseTerrain,
+    random_agents: bool,
+    seed: Option<u64>,
+) -> u64 {
+    let seed = resolve_seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    if random_agents {
+        place_random_agents(grid, &mut rng);
+    }
+    let noise = LatticeNoise::new(&mut rng);
+    let width = grid.width as usize;
+    let height = grid.height as usize;
+    let base_frequency = 1.0 / terrain.scale.max(1.0) as f64;
+    let heights: Vec<f64> = (0..grid.tile_amount())
+        .map(|idx| {
+            let (x, y) = ((idx % width) as f64, (idx / width) as f64);
+            let mut frequency = base_frequency;
+            let mut amplitude = 1.0;
+            let mut height = 0.0;
+            for _ in 0..terrain.octaves.max(1) {
+                height += amplitude * noise.sample(kind, x * frequency, y * frequency);
+                frequency *= 2.0;
+                amplitude *= terrain.persistence.clamp(0.0, 1.0) as f64;
+            }
+            height
+        })
+        .collect();
+
+    // Rank the tiles from lowest to highest; agents never become lakes or mountains
+    let mut ranked: Vec<usize> = (0..heights.len())
+        .filter(|idx| !grid.starts.contains(idx) && !grid.goals.contains(idx))
+        .collect();
+    ranked.sort_by(|&a, &b| heights[a].total_cmp(&heights[b]));
+    let lakes = heights.len() * terrain.lake_pct.min(100) as usize / 100;
+    let mountains = heights.len() * terrain.mountain_pct.min(100) as usize / 100;
+    let lakes = lakes.min(ranked.len());
+    let mountains = mountains.min(ranked.len() - lakes);
+    let mut tile_types = vec![TileType::Floor; heights.len()];
+    for &idx in &ranked[..lakes] {
+        tile_types[idx] = TileType::Water;
+    }
+    for &idx in &ranked[ranked.len() - mountains..] {
+        tile_types[idx] = TileType::Obstacle;
+    }
+
+    let land = (0..heights.len()).filter(|&idx| tile_types[idx] == TileType::Floor);
+    let (low, high) = land.fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), idx| {
+        (low.min(heights[idx]), high.max(heights[idx]))
+    });
+    let span = (high - low).max(f64::EPSILON);
+    let top = weight_range.max(1) as f64 - 1.0;
+
+    let mut tiles: Vec<Tile> = Vec::with_capacity(heights.len());
+    for j in 0..height {
+        for i in 0..width {
+            let idx = j * width + i;
+            let position = (i as i32, j as i32);
+            let tile = match tile_types[idx] {
+                TileType::Floor => {
+                    let weight = 1.0 + ((heights[idx] - low) / span * top).round();
+                    base_tile(grid, idx, position, weight as u8)
+                }
+                tile_type => Tile::new(position, tile_type, 1),
+            };
+            tiles.push(tile);
+        }
+    }
+
+    grid.tiles = tiles;
+    seed
+}
+
+/// Carve a maze into the grid.
+///
+/// The grid is split into square cells `corridor_width` tiles wide with
+/// walls one tile thick between them. `algorithm` joins the cells into a
+/// perfect maze (exactly one route between any two cells), then
+/// `loop_percentage` of its dead ends are opened to a neighboring cell:
+/// 0 keeps the maze perfect, 100 braids it until no dead end is left.
+/// Tiles past the last full cell stay walls.
+///
+/// # Arguments
+/// * `grid` - Grid to regenerate in place
+/// * `algorithm` - Algorithm carving the maze
+/// * `corridor_width` - Width of the corridors in tiles, shrunk to fit small grids
+/// * `loop_percentage` - Percentage of dead ends opened into loops
+/// * `random_agents` - Re-roll the starts and goals at maximal distance:
+///   the first pair at both ends of the longest route through the maze,
+///   every other start in a random cell with its goal at the cell farthest
+///   from it
+/// * `seed` - Seed for the generator, random if `None`
+///
+/// # Returns
+/// The seed used
+pub fn maze(
+    grid: &mut Grid,
+    algorithm: MazeAlgorithm,
+    corridor_width: u32,
+    loop_percentage: usize,
+    random_agents: bool,
+    seed: Option<u64>,
+) -> u64 {
+    let seed = resolve_seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let width = grid.width;
+    let height = grid.height;
+    let corridor = corridor_width.clamp(1, width.min(height).saturating_sub(2).max(1));
+    let pitch = corridor + 1;
+    let columns = (width.saturating_sub(1) / pitch) as usize;
+    let rows = (height.saturating_sub(1) / pitch) as usize;
+    let cells = columns * rows;
+    // Top left tile of a cell
+    let origin = |cell: usize| {
+        (
+            1 + (cell % columns) as u32 * pitch,
+            1 + (cell / columns) as u32 * pitch,
+        )
+    };
+
+    let mut open = vec![cells == 0; grid.tile_amount()];
+    if cells > 0 {
+        let mut passages = match algorithm {
+            MazeAlgorithm::RecursiveBacktracker => backtracker_maze(columns, rows, &mut rng),
+            MazeAlgorithm::Prim => prim_maze(columns, rows, &mut rng),
+            MazeAlgorithm::Kruskal => kruskal_maze(columns, rows, &mut rng),
+            MazeAlgorithm::Wilson => wilson_maze(columns, rows, &mut rng),
+        };
+        let links = braid_maze(&mut passages, columns, rows, loop_percentage, &mut rng);
+
+        let mut carve = |x: u32, y: u32, w: u32, h: u32| {
+            for j in y..y + h {
+                for i in x..x + w {
+                    open[(j * width + i) as usize] = true;
+                }
+            }
+        };
+        for cell in 0..cells {
+            let (x, y) = origin(cell);
+            carve(x, y, corridor, corridor);
+        }
+        for (a, b) in passages {
+            let (x, y) = origin(a.min(b));
+            if a.abs_diff(b) == 1 {
+                carve(x + corridor, y, 1, corridor);
+            } else {
+                carve(x, y + corridor, corridor, 1);
+            }
+        }
+
+        let pairs = grid.starts.len().max(1).min(cells / 2);
+        if random_agents && pairs > 0 {
+            let tile = |cell: usize| {
+                let (x, y) = origin(cell);
+                (y * width + x) as usize
+            };
+            let mut used = vec![false; cells];
+            let mut starts = Vec::with_capacity(pairs);
+            let mut goals = Vec::with_capacity(pairs);
+            for pair in 0..pairs {
+                // The cell farthest from any cell is one end of the longest route
+                let from = (0..cells).filter(|&c| !used[c]).choose(&mut rng).unwrap();
+                let from = match pair {
+                    0 => *bfs_order(&links, from).last().unwrap(),
+                    _ => from,
+                };
+                used[from] = true;
+                let Some(to) = bfs_order(&links, from)
+                    .into_iter()
+                    .rev()
+                    .find(|&c| !used[c])
+                else {
+                    break;
+                };
+                used[to] = true;
+                starts.push(tile(from));
+                goals.push(tile(to));
+            }
+            grid.starts = starts;
+            grid.goals = goals;
+        }
+    }
+
+    let mut tiles: Vec<Tile> = Vec::with_capacity(open.len());
+    for j in 0..height {
+        for i in 0..width {
+            let idx = (j * width + i) as usize;
+            let position = (i as i32, j as i32);
+            if open[idx] || grid.starts.contains(&idx) || grid.goals.contains(&idx) {
+                tiles.push(base_tile(grid, idx, position, 1));
+            } else {
+                tiles.push(Tile::new(position, TileType::Obstacle, 1));
+            }
+        }
+    }
+
+    grid.tiles = tiles;
+    seed
+}
+
+/// Generate a cave with a cellular automaton.
+///
+/// Every tile starts as a wall with `fill_percentage` chance, then `rules`
+/// smooth the noise into caverns. With `keep_largest_region` every floor
+/// tile outside the largest 4-connected region is filled, and starts or
+/// goals left outside it move to random tiles inside, so every agent can
+/// reach its goal under any movement rule.
+///
+/// # Arguments
+/// * `grid` - Grid to regenerate in place
+/// * `fill_percentage` - Percentage of tiles that start as walls
+/// * `rules` - Smoothing rules of the automaton
+/// * `random_agents` - Re-roll the starts and goals on random floor tiles
+/// * `seed` - Seed for the generator, random if `None`
+///
+/// # Returns
+/// The seed used
+pub fn cave(
+    grid: &mut Grid,
+    fill_percentage: usize,
+    rules: &CaveRules,
+    random_agents: bool,
+    seed: Option<u64>,
+) -> u64 {
+    let seed = resolve_seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let width = grid.width as usize;
+    let height = grid.height as usize;
+    let fill = fill_percentage.min(100) as f64 / 100.0;
+    let mut walls: Vec<bool> = (0..grid.tile_amount())
+        .map(|_| rng.random_bool(fill))
+        .collect();
+
+    for _ in 0..rules.iterations {
+        let smoothed: Vec<bool> = (0..walls.len())
+            .map(|idx| {
+                let (x, y) = ((idx % width) as i64, (idx / width) as i64);
+                let mut neighbors = 0;
+                for (dx, dy) in [
+                    (-1, -1),
+                    (0, -1),
+                    (1, -1),
+                    (-1, 0),
+                    (1, 0),
+                    (-1, 1),
+                    (0, 1),
+                    (1, 1),
+                ] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    let outside = nx < 0 || ny < 0 || nx >= width as i64 || ny >= height as i64;
+                    if outside || walls[ny as usize * width + nx as usize] {
+                        neighbors += 1;
+                    }
+                }
+                if walls[idx] {
+                    neighbors >= rules.death_limit
+                } else {
+                    neighbors >= rules.birth_limit
+                }
+            })
+            .collect();
+        walls = smoothed;
+    }
+
+    let mut floor: Vec<usize> = (0..walls.len()).filter(|&idx| !walls[idx]).collect();
+    if rules.keep_largest_region {
+        let region = largest_region(&walls, width);
+        if !region.is_empty() {
+            let mut kept = vec![false; walls.len()];
+            for &idx in &region {
+                kept[idx] = true;
+            }
+            walls = kept.iter().map(|keep| !keep).collect();
+            floor = region;
+        }
+    }
+
+    let pairs = grid.starts.len().max(1).min(floor.len() / 2);
+    if random_agents && pairs == 0 {
+        // Too few floor tiles left, the agents clear their own
+        place_random_agents(grid, &mut rng);
+    } else if random_agents {
+        let tiles: Vec<usize> = sample(&mut rng, floor.len(), pairs * 2)
+            .into_iter()
+            .map(|i| floor[i])
+            .collect();
+        let (starts, goals) = tiles.split_at(pairs);
+        grid.starts = starts.to_vec();
+        grid.goals = goals.to_vec();
+    } else if rules.keep_largest_region && floor.len() >= grid.starts.len() + grid.goals.len() {
+        // Move agents the filled regions cut off
+        let mut free: Vec<usize> = floor
+            .iter()
+            .copied()
+            .filter(|idx| !grid.starts.contains(idx) && !grid.goals.contains(idx))
+            .collect();
+        free.shuffle(&mut rng);
+        for agent in grid.starts.iter_mut().chain(grid.goals.iter_mut()) {
+            if walls[*agent] {
+                *agent = free.pop().unwrap();
+            }
+        }
+    }
+
+    let mut tiles: Vec<Tile> = Vec::with_capacity(walls.len());
+    for j in 0..height {
+        for i in 0..width {
+            let idx = j * width + i;
+            let position = (i as i32, j as i32);
+            if walls[idx] && !grid.starts.contains(&idx) && !grid.goals.contains(&idx) {
+                tiles.push(Tile::new(position, TileType::Obstacle, 1));
+            } else {
+                tiles.push(base_tile(grid, idx, position, 1));
+            }
+        }
+    }
+
+    grid.tiles = tiles;
+    seed
+}
+
+/// Tiles of the largest 4-connected region of floor tiles.
+fn largest_region(walls: &[bool], width: usize) -> Vec<usize> {
+    let mut seen = walls.to_vec();
+    let mut largest = Vec::new();
+    for start in 0..walls.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut region = vec![start];
+        let mut next = 0;
+        while next < region.len() {
+            let idx = region[next];
+            next += 1;
+            let (x, y) = (idx % width, idx / width);
+            let neighbors = [
+                (x > 0).then(|| idx - 1),
+                (x + 1 < width).then(|| idx + 1),
+                (y > 0).then(|| idx - width),
+                (idx + width < walls.len()).then(|| idx + width),
+            ];
+            for neighbor in neighbors.into_iter().flatten() {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    region.push(neighbor);
+                }
+            }
+        }
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+    largest
+}
+
+/// Cells sharing a wall with `cell` in a `columns` x `rows` maze.
+fn cell_neighbors(cell: usize, columns: usize, rows: usize) -> impl Iterator<Item = usize> {
+    let (column, row) = (cell % columns, cell / columns);
+    [
+        (column > 0).then(|| cell - 1),
+        (column + 1 < columns).then(|| cell + 1),
+        (row > 0).then(|| cell - columns),
+        (row + 1 < rows).then(|| cell + columns),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Carve a perfect maze with a randomized depth-first search.
+///
+/// # Returns
+/// The pairs of cells joined by a passage
+fn backtracker_maze<R: Rng>(columns: usize, rows: usize, rng: &mut R) -> Vec<(usize, usize)> {
+    let mut visited = vec![false; columns * rows];
+    let mut passages = Vec::with_capacity(visited.len());
+    let start = rng.random_range(0..visited.len());
+    visited[start] = true;
+    let mut stack = vec![start];
+    while let Some(&cell) = stack.last() {
+        match cell_neighbors(cell, columns, rows)
+            .filter(|&neighbor| !visited[neighbor])
+            .choose(rng)
+        {
+            Some(next) => {
+                visited[next] = true;
+                passages.push((cell, next));
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    passages
+}
+
+/// Carve a perfect maze with randomized Prim's algorithm.
+///
+/// # Returns
+/// The pairs of cells joined by a passage
+fn prim_maze<R: Rng>(columns: usize, rows: usize, rng: &mut R) -> Vec<(usize, usize)> {
+    let mut visited = vec![false; columns * rows];
+    let mut passages = Vec::with_capacity(visited.len());
+    let start = rng.random_range(0..visited.len());
+    visited[start] = true;
+    let mut frontier: Vec<(usize, usize)> = cell_neighbors(start, columns, rows)
+        .map(|neighbor| (start, neighbor))
+        .collect();
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+        if visited[to] {
+            continue;
+        }
+        visited[to] = true;
+        passages.push((from, to));
+        frontier.extend(
+            cell_neighbors(to, columns, rows)
+                .filter(|&neighbor| !visited[neighbor])
+                .map(|neighbor| (to, neighbor)),
+        );
+    }
+    passages
+}
+
+/// Carve a perfect maze with randomized Kruskal's algorithm.
+///
+/// # Returns
+/// The pairs of cells joined by a passage
+fn kruskal_maze<R: Rng>(columns: usize, rows: usize, rng: &mut R) -> Vec<(usize, usize)> {
+    fn find(parent: &mut [usize], mut cell: usize) -> usize {
+        while parent[cell] != cell {
+            parent[cell] = parent[parent[cell]];
+            cell = parent[cell];
+        }
+        cell
+    }
+
+    let cells = columns * rows;
+    let mut walls: Vec<(usize, usize)> = (0..cells)
+        .flat_map(|cell| {
+            cell_neighbors(cell, columns, rows)
+                .filter(move |&neighbor| neighbor > cell)
+                .map(move |neighbor| (cell, neighbor))
+        })
+        .collect();
+    walls.shuffle(rng);
+    let mut parent: Vec<usize> = (0..cells).collect();
+    let mut passages = Vec::with_capacity(cells);
+    for (a, b) in walls {
+        let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+        if root_a != root_b {
+            parent[root_a] = root_b;
+            passages.push((a, b));
+        }
+    }
+    passages
+}
+
+/// Carve a perfect maze with Wilson's algorithm.
+///
+/// # Returns
+/// The pairs of cells joined by a passage
+fn wilson_maze<R: Rng>(columns: usize, rows: usize, rng: &mut R) -> Vec<(usize, usize)> {
+    let cells = columns * rows;
+    let mut in_maze = vec![false; cells];
+    in_maze[rng.random_range(0..cells)] = true;
+    let mut next = vec![0; cells];
+    let mut passages = Vec::with_capacity(cells);
+    let mut order: Vec<usize> = (0..cells).collect();
+    order.shuffle(rng);
+    for start in order {
+        // Walk until the maze is hit; revisiting a cell overwrites its exit,
+        // which erases the loop
+        let mut cell = start;
+        while !in_maze[cell] {
+            next[cell] = cell_neighbors(cell, columns, rows).choose(rng).unwrap();
+            cell = next[cell];
+        }
+        let mut cell = start;
+        while !in_maze[cell] {
+            in_maze[cell] = true;
+            passages.push((cell, next[cell]));
+            cell = next[cell];
+        }
+    }
+    passages
+}
+
+/// Open `loop_percentage` of the maze's dead ends into a neighboring cell,
+/// preferring cells that are dead ends themselves.
+///
+/// # Returns
+/// The cells linked to each cell after braiding
+fn braid_maze<R: Rng>(
+    passages: &mut Vec<(usize, usize)>,
+    columns: usize,
+    rows: usize,
+    loop_percentage: usize,
+    rng: &mut R,
+) -> Vec<Vec<usize>> {
+    let mut links = vec![Vec::new(); columns * rows];
+    for &(a, b) in passages.iter() {
+        links[a].push(b);
+        links[b].push(a);
+    }
+    let mut dead_ends: Vec<usize> = (0..links.len()).filter(|&c| links[c].len() == 1).collect();
+    dead_ends.shuffle(rng);
+    let amount = dead_ends.len() * loop_percentage.min(100) / 100;
+    for cell in dead_ends.into_iter().take(amount) {
+        // An earlier loop may already have opened this dead end
+        if links[cell].len() != 1 {
+            continue;
+        }
+        let walls: Vec<usize> = cell_neighbors(cell, columns, rows)
+            .filter(|neighbor| !links[cell].contains(neighbor))
+            .collect();
+        let target = walls
+            .iter()
+            .copied()
+            .filter(|&neighbor| links[neighbor].len() == 1)
+            .choose(rng)
+            .or_else(|| walls.iter().copied().choose(rng));
+        if let Some(neighbor) = target {
+            links[cell].push(neighbor);
+            links[neighbor].push(cell);
+            passages.push((cell, neighbor));
+        }
+    }
+    links
+}
+
+/// Cells reachable from `from`, in breadth-first order (nearest first).
+fn bfs_order(links: &[Vec<usize>], from: usize) -> Vec<usize> {
+    let mut seen = vec![false; links.len()];
+    seen[from] = true;
+    let mut order = vec![from];
+    let mut queue = VecDeque::from([from]);
+    while let Some(cell) = queue.pop_front() {
+        for &neighbor in &links[cell] {
+            if !seen[neighbor] {
+                seen[neighbor] = true;
+                order.push(neighbor);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ------- random_agents -------
+
+    #[test]
+    fn test_random_agents_places_distinct_pair() {
+        let mut grid = Grid::new(10, 10);
+        random_agents(&mut grid, None);
+        assert_eq!(grid.starts.len(), 1);
+        assert_eq!(grid.goals.len(), 1);
+        assert_ne!(grid.starts[0], grid.goals[0]);
+    }
+
+    #[test]
+    fn test_random_agents_keeps_agent_count() {
+        let mut grid = Grid::new(10, 10);
+        grid.starts = vec![0, 1, 2];
+        grid.goals = vec![99];
+        random_agents(&mut grid, None);
+        assert_eq!(grid.starts.len(), 3);
+        assert_eq!(grid.goals.len(), 3);
+    }
+
+    // ------- random_grid -------
+
+    #[test]
+    fn test_random_grid_has_obstacles() {
+        let mut grid = Grid::new(20, 20);
+        grid.starts = vec![0];
+        grid.goals = vec![399];
+        random_grid(&mut grid, 5, 30, 0, false, None);
+        assert_eq!(grid.tiles.len(), 400);
+        assert!(grid.tiles.iter().any(|t| !t.is_traversable()));
+    }
+
+    #[test]
+    fn test_random_grid_keeps_starts_and_goals() {
+        let mut grid = Grid::new(20, 20);
+        grid.starts = vec![0];
+        grid.goals = vec![399];
+        random_grid(&mut grid, 5, 50, 50, false, None);
+        assert_eq!(grid.tiles[0].tile_type(), TileType::Player);
+        assert_eq!(grid.tiles[399].tile_type(), TileType::Enemy);
+    }
+
+    #[test]
+    fn test_random_grid_weights_within_range() {
+        let mut grid = Grid::new(20, 20);
+        random_grid(&mut grid, 7, 0, 100, false, None);
+        assert!(grid.tiles.iter().all(|t| (1..=7).contains(&t.weight)));
+        assert!(grid.tiles.iter().any(|t| t.weight > 1));
+    }
+
+    // ------- organic_city -------
+
+    #[test]
+    fn test_organic_city_creates_grid() {
+        let mut grid = Grid::new(10, 10);
+        organic_city(&mut grid, 1, 2, 4, 50.0, 2, 5, false, None);
+        assert_eq!(grid.tiles.len(), 100);
+    }
+
+    #[test]
+    fn test_organic_city_roads_use_road_weight() {
+        let mut grid = Grid::new(10, 10);
+        organic_city(&mut grid, 3, 2, 4, 0.0, 2, 5, false, None);
+        // The first column and row are always roads
+        assert_eq!(grid.tiles[0].weight, 3);
+        assert!(grid.tiles.iter().all(|t| t.is_traversable()));
+    }
+
+    #[test]
+    fn test_organic_city_with_random_agents() {
+        let mut grid = Grid::new(10, 10);
+        organic_city(&mut grid, 1, 2, 4, 50.0, 2, 5, true, None);
+        assert_eq!(grid.starts.len(), 1);
+        assert!(grid.tiles[grid.starts[0]].is_traversable());
+        assert!(grid.tiles[grid.goals[0]].is_traversable());
+    }
+
+    // ------- maze -------
+
+    /// Breadth-first distances over traversable tiles, `None` if unreachable.
+    fn tile_distances(grid: &Grid, from: usize) -> Vec<Option<u32>> {
+        let width = grid.width as usize;
+        let mut distances = vec![None; grid.tiles.len()];
+        distances[from] = Some(0);
+        let mut queue = VecDeque::from([from]);
+        while let Some(idx) = queue.pop_front() {
+            let (x, y) = (idx % width, idx / width);
+            let neighbors = [
+                (x > 0).then(|| idx - 1),
+                (x + 1 < width).then(|| idx + 1),
+                (y > 0).then(|| idx - width),
+                (idx + width < grid.tiles.len()).then(|| idx + width),
+            ];
+            for next in neighbors.into_iter().flatten() {
+                if distances[next].is_none() && grid.tiles[next].is_traversable() {
+                    distances[next] = distances[idx].map(|d| d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    #[test]
+    fn test_perfect_maze_is_a_spanning_tree() {
+        for algorithm in MazeAlgorithm::ALL {
+            // 10x10 cells, so a tree has 100 cell tiles and 99 passage tiles
+            let mut grid = Grid::new(21, 21);
+            maze(&mut grid, algorithm, 1, 0, true, Some(3));
+            let open = grid.tiles.iter().filter(|t| t.is_traversable()).count();
+            assert_eq!(open, 199, "{}", algorithm.name());
+            let distances = tile_distances(&grid, grid.starts[0]);
+            assert!(grid
+                .tiles
+                .iter()
+                .zip(&distances)
+                .all(|(tile, d)| !tile.is_traversable() || d.is_some()));
+        }
+    }
+
+    #[test]
+    fn test_fully_braided_maze_has_no_dead_ends() {
+        for algorithm in MazeAlgorithm::ALL {
+            let mut grid = Grid::new(21, 21);
+            maze(&mut grid, algorithm, 1, 100, false, Some(5));
+            assert!(grid.tiles.iter().filter(|t| t.is_traversable()).count() > 199);
+            for y in (1..21).step_by(2) {
+                for x in (1..21).step_by(2) {
+                    let exits = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                        .iter()
+                        .filter(|(i, j)| grid.tiles[j * 21 + i].is_traversable())
+                        .count();
+                    assert!(exits >= 2, "{} dead end at {:?}", algorithm.name(), (x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_maze_corridor_width() {
+        let mut grid = Grid::new(33, 33);
+        maze(&mut grid, MazeAlgorithm::Prim, 3, 0, false, Some(1));
+        // 8x8 cells of 3x3 tiles, joined by 63 passages 3 tiles wide
+        let open = grid.tiles.iter().filter(|t| t.is_traversable()).count();
+        assert_eq!(open, 64 * 9 + 63 * 3);
+        assert!((1..4).all(|i| (1..4).all(|j| grid.tiles[j * 33 + i].is_traversable())));
+        assert!(!grid.tiles[0].is_traversable());
+    }
+
+    #[test]
+    fn test_maze_places_agents_at_maximal_distance() {
+        for algorithm in MazeAlgorithm::ALL {
+            let mut grid = Grid::new(31, 31);
+            maze(&mut grid, algorithm, 1, 0, true, Some(9));
+            assert_eq!(grid.tiles[grid.starts[0]].tile_type(), TileType::Player);
+            assert_eq!(grid.tiles[grid.goals[0]].tile_type(), TileType::Enemy);
+            let distances = tile_distances(&grid, grid.starts[0]);
+            let farthest = distances.iter().flatten().max().copied();
+            assert_eq!(distances[grid.goals[0]], farthest, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn test_maze_keeps_agent_count_and_tiny_grids_stay_open() {
+        let mut grid = Grid::new(21, 21);
+        grid.starts = vec![22, 24, 26];
+        grid.goals = vec![400];
+        maze(&mut grid, MazeAlgorithm::Wilson, 1, 0, true, Some(2));
+        assert_eq!(grid.starts.len(), 3);
+        assert_eq!(grid.goals.len(), 3);
+
+        let mut tiny = Grid::new(2, 2);
+        maze(&mut tiny, MazeAlgorithm::Kruskal, 4, 0, false, None);
+        assert!(tiny.tiles.iter().all(|t| t.is_traversable()));
+    }
+
+    // ------- cave -------
+
+    #[test]
+    fn test_cave_smoothing_fills_corners_of_an_empty_map() {
+        // Corners have 5 neighbors past the edge, every other tile at most 4
+        let mut grid = Grid::new(12, 12);
+        cave(&mut grid, 0, &CaveRules::default(), false, Some(1));
+        let walls: Vec<usize> = (0..144)
+            .filter(|&i| !grid.tiles[i].is_traversable())
+            .collect();
+        assert_eq!(walls, vec![0, 11, 132, 143]);
+
+        cave(&mut grid, 100, &CaveRules::default(), false, Some(1));
+        assert!(grid.tiles.iter().all(|t| !t.is_traversable()));
+    }
+
+    #[test]
+    fn test_cave_without_smoothing_keeps_the_fill_ratio() {
+        let rules = CaveRules {
+            iterations: 0,
+            keep_largest_region: false,
+            ..CaveRules::default()
+        };
+        let mut grid = Grid::new(40, 40);
+        cave(&mut grid, 30, &rules, false, Some(8));
+        let walls = grid.tiles.iter().filter(|t| !t.is_traversable()).count();
+        assert!((320..640).contains(&walls), "{} walls", walls);
+    }
+
+    #[test]
+    fn test_cave_keeps_only_the_largest_region() {
+        for seed in 0..5 {
+            let mut grid = Grid::new(40, 40);
+            cave(&mut grid, 45, &CaveRules::default(), true, Some(seed));
+            let distances = tile_distances(&grid, grid.starts[0]);
+            assert!(distances[grid.goals[0]].is_some());
+            assert!(grid
+                .tiles
+                .iter()
+                .zip(&distances)
+                .all(|(tile, d)| !tile.is_traversable() || d.is_some()));
+        }
+    }
+
+    #[test]
+    fn test_cave_moves_agents_out_of_filled_tiles() {
+        let mut grid = Grid::new(12, 12);
+        grid.starts = vec![0];
+        grid.goals = vec![70];
+        cave(&mut grid, 0, &CaveRules::default(), false, Some(2));
+        assert_ne!(grid.starts[0], 0);
+        assert_eq!(grid.goals[0], 70);
+        assert_eq!(grid.tiles[grid.starts[0]].tile_type(), TileType::Player);
+        assert!(!grid.tiles[0].is_traversable());
+    }
+
+    // ------- noise_terrain -------
+
+    /// Mean absolute weight difference between horizontal neighbors.
+    fn roughness(grid: &Grid) -> f64 {
+        let width = grid.width as usize;
+        let steps: Vec<f64> = (0..grid.tiles.len())
+            .filter(|idx| (idx + 1) % width != 0)
+            .map(|idx| (grid.tiles[idx].weight as f64 - grid.tiles[idx + 1].weight as f64).abs())
+            .collect();
+        steps.iter().sum::<f64>() / steps.len() as f64
+    }
+
+    #[test]
+    fn test_noise_terrain_is_smoother_than_random_weights() {
+        let mut random = Grid::new(64, 64);
+        random_grid(&mut random, 9, 0, 100, false, Some(3));
+        for kind in NoiseKind::ALL {
+            let mut grid = Grid::new(64, 64);
+            noise_terrain(&mut grid, kind, 9, &NoiseTerrain::default(), false, Some(3));
+            assert!(grid.tiles.iter().all(|t| (1..=9).contains(&t.weight)));
+            assert!(grid.tiles.iter().any(|t| t.weight == 1));
+            assert!(grid.tiles.iter().any(|t| t.weight == 9));
+            assert!(
+                roughness(&grid) * 3.0 < roughness(&random),
+                "{} noise is too rough",
+                kind.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_noise_terrain_thresholds_mountains_and_lakes() {
+        let terrain = NoiseTerrain {
+            mountain_pct: 20,
+            lake_pct: 10,
+            ..NoiseTerrain::default()
+        };
+        let mut grid = Grid::new(40, 40);
+        noise_terrain(&mut grid, NoiseKind::Perlin, 5, &terrain, true, Some(4));
+        let count = |grid: &Grid, tile_type| {
+            grid.tiles
+                .iter()
+                .filter(|t| t.tile_type() == tile_type)
+                .count()
+        };
+        assert_eq!(count(&grid, TileType::Obstacle), 320);
+        assert_eq!(count(&grid, TileType::Water), 160);
+        assert_eq!(grid.tiles[grid.starts[0]].tile_type(), TileType::Player);
+        assert_eq!(grid.tiles[grid.goals[0]].tile_type(), TileType::Enemy);
+
+        // Thresholds past 100% are capped so the agents keep their land
+        let flooded = NoiseTerrain {
+            mountain_pct: 80,
+            lake_pct: 80,
+            ..terrain
+        };
+        noise_terrain(&mut grid, NoiseKind::Value, 5, &flooded, false, Some(4));
+        assert_eq!(count(&grid, TileType::Water), 1280);
+        assert_eq!(count(&grid, TileType::Obstacle), 318);
+        assert!(grid.tiles[grid.starts[0]].is_traversable());
+    }
+
+    // ------- Seeds -------
+
+    #[test]
+    fn test_same_seed_reproduces_random_grid() {
+        let mut first = Grid::new(20, 20);
+        let mut second = Grid::new(20, 20);
+        assert_eq!(random_grid(&mut first, 9, 30, 30, true, Some(42)), 42);
+        random_grid(&mut second, 9, 30, 30, true, Some(42));
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.starts, second.starts);
+        assert_eq!(first.goals, second.goals);
+    }
+
+    #[test]
+    fn test_organic_city_clamps_empty_ranges() {
+        // Maximums below the minimums used to panic on an empty range
+        let mut grid = Grid::new(20, 20);
+        organic_city(&mut grid, 1, 2, 1, 50.0, 2, 0, true, Some(7));
+        assert_eq!(grid.tiles.len(), 400);
+        assert!(grid.tiles.iter().any(|t| !t.is_traversable()));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_organic_city() {
+        let mut first = Grid::new(20, 20);
+        let mut second = Grid::new(20, 20);
+        organic_city(&mut first, 1, 2, 4, 50.0, 2, 5, true, Some(7));
+        organic_city(&mut second, 1, 2, 4, 50.0, 2, 5, true, Some(7));
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.starts, second.starts);
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_maze() {
+        for algorithm in MazeAlgorithm::ALL {
+            let mut first = Grid::new(25, 25);
+            let mut second = Grid::new(25, 25);
+            maze(&mut first, algorithm, 2, 30, true, Some(11));
+            maze(&mut second, algorithm, 2, 30, true, Some(11));
+            assert_eq!(first.tiles, second.tiles);
+            assert_eq!(first.starts, second.starts);
+            assert_eq!(first.goals, second.goals);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_cave() {
+        let mut first = Grid::new(30, 30);
+        let mut second = Grid::new(30, 30);
+        cave(&mut first, 45, &CaveRules::default(), true, Some(13));
+        cave(&mut second, 45, &CaveRules::default(), true, Some(13));
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.starts, second.starts);
+        assert_eq!(first.goals, second.goals);
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_noise_terrain() {
+        let terrain = NoiseTerrain {
+            mountain_pct: 10,
+            lake_pct: 10,
+            ..NoiseTerrain::default()
+        };
+        for kind in NoiseKind::ALL {
+            let mut first = Grid::new(30, 30);
+            let mut second = Grid::new(30, 30);
+            noise_terrain(&mut first, kind, 9, &terrain, true, Some(17));
+            noise_terrain(&mut second, kind, 9, &terrain, true, Some(17));
+            assert_eq!(first.tiles, second.tiles);
+            assert_eq!(first.starts, second.starts);
+        }
+    }
+
+    #[test]
+    fn test_unseeded_generation_reports_a_reusable_seed() {
+        let mut first = Grid::new(20, 20);
+        let seed = random_grid(&mut first, 9, 30, 30, true, None);
+        let mut second = Grid::new(20, 20);
+        random_grid(&mut second, 9, 30, 30, true, Some(seed));
+        assert_eq!(first.tiles, second.tiles);
+    }
+
+    #[test]
+    fn test_derive_seed_differs_per_index() {
+        assert_eq!(derive_seed(10, 0), 10);
+        assert_ne!(derive_seed(10, 1), derive_seed(10, 2));
+        assert_eq!(derive_seed(u64::MAX, 1), 0);
+    }
+}
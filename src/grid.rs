@@ -0,0 +1,360 @@
+//! # Grid Model Module
+//!
+//! This module contains the display-free grid model shared by the planners,
+//! generators and benchmarks:
+//! - `TileType`: what occupies a cell (floor, obstacle, start, goal, ...)
+//! - `Tile`: a single cell with its grid position, type and traversal weight
+//! - `Grid`: a row-major tile map together with its start and goal cells
+//!
+//! Nothing in here depends on SDL; the UI renders tiles by mapping them to
+//! screen rectangles and colors on its own side.
+
+use serde::{Deserialize, Serialize};
+
+use crate::pathfinding::Agent;
+
+/// Enumeration of possible tile types on the game board.
+///
+/// Each type has different behavior for pathfinding and rendering.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TileType {
+    /// Impassable obstacle - blocks all movement
+    Obstacle,
+    /// Standard floor tile with weight 1
+    Floor,
+    /// Player/agent starting position (rendered green)
+    Player,
+    /// Enemy/goal position (rendered red)
+    Enemy,
+    /// Floor tile with custom traversal weight (higher = slower)
+    Weighted(u8),
+    /// Path tile (rendered blue)
+    Path,
+    /// Water (MovingAI `W`) - only reachable from other water tiles
+    Water,
+}
+
+/// Represents a single tile of the grid.
+///
+/// Tiles only carry the data the planners need: where they are, what they
+/// are and how expensive they are to traverse.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Tile {
+    /// Position on the grid (tile coordinates, not pixels)
+    pub position: (i32, i32),
+    /// Current type of this tile (obstacle, floor, player, etc.)
+    tile_type: TileType,
+    /// Traversal weight (1 = normal, higher = slower)
+    pub weight: u8,
+}
+
+impl Tile {
+    /// Create a new tile with the specified properties.
+    ///
+    /// # Arguments
+    /// * `position` - Grid position of the tile
+    /// * `tile_type` - Type of tile
+    /// * `weight` - Traversal weight (clamped to at least 1)
+    pub fn new(position: (i32, i32), tile_type: TileType, weight: u8) -> Self {
+        Tile {
+            position,
+            tile_type,
+            weight: weight.max(1),
+        }
+    }
+
+    /// Get the current type of this tile.
+    pub fn tile_type(&self) -> TileType {
+        self.tile_type
+    }
+
+    /// Check if this tile can be walked through.
+    ///
+    /// All non-obstacle tiles are traversable (floor, player, enemy, weighted)
+    /// except water, which land agents cannot enter (see `can_enter_from`).
+    pub fn is_traversable(&self) -> bool {
+        self.tile_type != TileType::Obstacle && self.tile_type != TileType::Water
+    }
+
+    /// Check if this tile is water.
+    pub fn is_water(&self) -> bool {
+        self.tile_type == TileType::Water
+    }
+
+    /// Check if a move from `from` onto this tile is allowed.
+    ///
+    /// Water tiles can only be entered from other water tiles, and land can
+    /// only be entered from land.
+    pub fn can_enter_from(&self, from: &Tile) -> bool {
+        if from.is_water() {
+            return self.is_water();
+        }
+        self.is_traversable()
+    }
+
+    /// Check if this tile is a standard floor tile.
+    pub fn is_floor(&self) -> bool {
+        self.tile_type == TileType::Floor
+    }
+
+    /// Change the tile's type.
+    ///
+    /// # Returns
+    /// `true` if the type actually changed (the tile needs to be redrawn)
+    pub fn change_tile_type(&mut self, new_type: TileType) -> bool {
+        if self.tile_type == new_type {
+            return false;
+        }
+        self.tile_type = new_type;
+        true
+    }
+}
+
+/// A rectangular, row-major tile map with its start and goal cells.
+///
+/// This is the model the generators write into and the planners read from.
+/// Starts and goals are stored as tile indices, matching the board format.
+#[derive(Clone, Debug)]
+pub struct Grid {
+    /// Number of tiles horizontally
+    pub width: u32,
+    /// Number of tiles vertically
+    pub height: u32,
+    /// Tiles in row-major order (`y * width + x`)
+    pub tiles: Vec<Tile>,
+    /// Tile indices of the agent starting positions
+    pub starts: Vec<usize>,
+    /// Tile indices of the goal positions
+    pub goals: Vec<usize>,
+}
+
+impl Grid {
+    /// Create an open grid of weight 1 floor tiles with no starts or goals.
+    pub fn new(width: u32, height: u32) -> Self {
+        Grid {
+            width,
+            height,
+            tiles: floor_tiles(width, height),
+            starts: vec![],
+            goals: vec![],
+        }
+    }
+
+    /// Total number of tiles in the grid.
+    pub fn tile_amount(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    /// Get the tile at a grid position, if it lies inside the grid.
+    pub fn tile(&self, pos: (i32, i32)) -> Option<&Tile> {
+        get_idx_from_coordinate(pos, self.width, self.height).and_then(|idx| self.tiles.get(idx))
+    }
+
+    /// Pair every start with a goal and build the corresponding agents.
+    ///
+    /// When there are more starts than goals (or vice versa) the shorter
+    /// list is cycled.
+    ///
+    /// # Returns
+    /// The agents, or an error if the grid has no starts or no goals
+    pub fn create_agents(&self) -> Result<Vec<Agent>, &'static str> {
+        if self.goals.is_empty() || self.starts.is_empty() {
+            return Err("No goals or Agents on Board");
+        }
+        let goal_amount: usize = self.goals.len();
+        let start_amount: usize = self.starts.len();
+        let indexes = start_amount.max(goal_amount);
+        let mut agents = Vec::with_capacity(indexes);
+
+        for i in 0..indexes {
+            let start = get_coordinate_from_idx(
+                self.starts[i % start_amount],
+                self.width,
+                self.height,
+            );
+            agents.push(Agent {
+                start,
+                goal: get_coordinate_from_idx(self.goals[i % goal_amount], self.width, self.height),
+                position: start,
+                path: vec![],
+                waypoints: vec![],
+            });
+        }
+        Ok(agents)
+    }
+}
+
+/// Build a row-major vector of weight 1 floor tiles.
+pub fn floor_tiles(width: u32, height: u32) -> Vec<Tile> {
+    let tile_amount = (width * height) as usize;
+    (0..tile_amount)
+        .map(|idx| {
+            Tile::new(
+                get_coordinate_from_idx(idx, width, height),
+                TileType::Floor,
+                1,
+            )
+        })
+        .collect()
+}
+
+/// Convert a row-major tile index into grid coordinates.
+///
+/// Rows are `x_size` tiles long, so only the width is needed; `_y_size` is
+/// kept for symmetry with `get_idx_from_coordinate`.
+#[inline]
+pub fn get_coordinate_from_idx(idx: usize, x_size: u32, _y_size: u32) -> (i32, i32) {
+    let x = (idx % x_size as usize) as i32;
+    let y = (idx / x_size as usize) as i32;
+    (x, y)
+}
+
+/// Convert grid coordinates into a row-major tile index.
+///
+/// # Returns
+/// The index, or `None` if the position lies outside the grid
+#[inline]
+pub fn get_idx_from_coordinate(pos: (i32, i32), width: u32, height: u32) -> Option<usize> {
+    if pos.0 < 0 || pos.1 < 0 || pos.0 >= width as i32 || pos.1 >= height as i32 {
+        return None;
+    }
+    Some(pos.1 as usize * width as usize + pos.0 as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ------- Tile -------
+
+    #[test]
+    fn test_tile_new_keeps_grid_position() {
+        let tile = Tile::new((3, 4), TileType::Floor, 1);
+        assert_eq!(tile.position, (3, 4));
+    }
+
+    #[test]
+    fn test_tile_weight_is_at_least_one() {
+        let tile = Tile::new((0, 0), TileType::Floor, 0);
+        assert_eq!(tile.weight, 1);
+    }
+
+    #[test]
+    fn test_tile_change_tile_type_reports_change() {
+        let mut tile = Tile::new((0, 0), TileType::Floor, 1);
+        assert!(tile.change_tile_type(TileType::Obstacle));
+        assert!(!tile.is_traversable());
+        assert!(!tile.change_tile_type(TileType::Obstacle));
+    }
+
+    #[test]
+    fn test_tile_type_getter() {
+        let tile = Tile::new((0, 0), TileType::Enemy, 1);
+        assert_eq!(tile.tile_type(), TileType::Enemy);
+    }
+
+    #[test]
+    fn test_water_is_only_entered_from_water() {
+        let water = Tile::new((0, 0), TileType::Water, 1);
+        let floor = Tile::new((1, 0), TileType::Floor, 1);
+        assert!(!water.is_traversable());
+        assert!(water.can_enter_from(&water));
+        assert!(!water.can_enter_from(&floor));
+        assert!(!floor.can_enter_from(&water));
+        assert!(floor.can_enter_from(&floor));
+    }
+
+    // ------- Grid -------
+
+    #[test]
+    fn test_grid_new_is_open_floor() {
+        let grid = Grid::new(4, 3);
+        assert_eq!(grid.tiles.len(), 12);
+        assert_eq!(grid.tile_amount(), 12);
+        assert!(grid.tiles.iter().all(|t| t.is_floor() && t.weight == 1));
+    }
+
+    #[test]
+    fn test_grid_tile_lookup() {
+        let grid = Grid::new(5, 5);
+        assert_eq!(grid.tile((2, 3)).map(|t| t.position), Some((2, 3)));
+        assert!(grid.tile((5, 0)).is_none());
+        assert!(grid.tile((-1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_grid_create_agents_pairs_starts_and_goals() {
+        let mut grid = Grid::new(10, 10);
+        grid.starts = vec![0, 11];
+        grid.goals = vec![99];
+        let agents = grid.create_agents().unwrap();
+        assert_eq!(agents.len(), 2);
+        assert_eq!(agents[0].start, (0, 0));
+        assert_eq!(agents[1].start, (1, 1));
+        assert!(agents.iter().all(|a| a.goal == (9, 9)));
+    }
+
+    #[test]
+    fn test_grid_create_agents_requires_starts_and_goals() {
+        let grid = Grid::new(10, 10);
+        assert!(grid.create_agents().is_err());
+    }
+
+    // ------- get_coordinate_from_idx -------
+
+    #[test]
+    fn test_get_coordinate_from_idx_basic() {
+        assert_eq!(get_coordinate_from_idx(0, 10, 10), (0, 0));
+    }
+
+    #[test]
+    fn test_get_coordinate_from_idx_second_row() {
+        assert_eq!(get_coordinate_from_idx(10, 10, 10), (0, 1));
+    }
+
+    #[test]
+    fn test_get_coordinate_from_idx_middle() {
+        assert_eq!(get_coordinate_from_idx(15, 10, 10), (5, 1));
+    }
+
+    #[test]
+    fn test_get_coordinate_from_idx_last() {
+        assert_eq!(get_coordinate_from_idx(99, 10, 10), (9, 9));
+    }
+
+    #[test]
+    fn test_get_coordinate_from_idx_non_square() {
+        // 8 wide, 3 tall: index 10 is the third tile of the second row
+        assert_eq!(get_coordinate_from_idx(10, 8, 3), (2, 1));
+        assert_eq!(get_idx_from_coordinate((2, 1), 8, 3), Some(10));
+    }
+
+    // ------- get_idx_from_coordinate -------
+
+    #[test]
+    fn test_get_idx_from_coordinate_basic() {
+        assert_eq!(get_idx_from_coordinate((0, 0), 10, 10), Some(0));
+    }
+
+    #[test]
+    fn test_get_idx_from_coordinate_second_row() {
+        assert_eq!(get_idx_from_coordinate((0, 1), 10, 10), Some(10));
+    }
+
+    #[test]
+    fn test_get_idx_from_coordinate_middle() {
+        assert_eq!(get_idx_from_coordinate((5, 1), 10, 10), Some(15));
+    }
+
+    #[test]
+    fn test_get_idx_from_coordinate_out_of_bounds_negative() {
+        assert_eq!(get_idx_from_coordinate((-1, 0), 10, 10), None);
+        assert_eq!(get_idx_from_coordinate((0, -1), 10, 10), None);
+    }
+
+    #[test]
+    fn test_get_idx_from_coordinate_out_of_bounds_too_large() {
+        assert_eq!(get_idx_from_coordinate((10, 0), 10, 10), None);
+        assert_eq!(get_idx_from_coordinate((0, 10), 10, 10), None);
+    }
+}
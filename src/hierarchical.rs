@@ -0,0 +1,908 @@
+//! # Hierarchical Pathfinding Module
+//!
+//! This module implements HPA* (Hierarchical Path-Finding A*) for large grids:
+//! - `HpaGraph`: the grid split into square clusters, the entrances between
+//!   neighbouring clusters and the cheapest routes between the entrances of
+//!   each cluster
+//! - `HpaStar`: the `PathfindingAlgorithm` building a graph and querying it
+//!
+//! ## Queries
+//! 1. The start and the goal are connected to the entrances of their clusters
+//! 2. A* searches the abstract graph of entrances
+//! 3. Every abstract edge inside a cluster is refined into grid steps by a
+//!    search that never leaves that cluster
+//!
+//! Building the graph is preprocessing: it is done once per map and timed
+//! separately from the queries. After tiles change, `update_tiles` rebuilds
+//! only the clusters around them. Paths only cross cluster borders at
+//! entrances, so they are close to optimal but not guaranteed optimal.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::cost::{to_units, CostModel};
+use crate::grid::{get_idx_from_coordinate, Tile};
+use crate::pathfinding::{get_possible_moves, MovementRule, PathfindingAlgorithm, SearchContext};
+use crate::trace::TraceCell;
+
+/// Name of the hierarchical planner.
+pub const HPA_ALGORITHM: &str = "HPA*";
+
+/// Width and height of a cluster, in tiles, unless configured otherwise.
+pub const DEFAULT_CLUSTER_SIZE: u32 = 16;
+
+/// Entrances at least this wide get transitions at both ends and every
+/// `ENTRANCE_SPACING` tiles between them instead of a single one in the
+/// middle.
+const WIDE_ENTRANCE: usize = 6;
+
+/// Distance between the transitions of a wide entrance.
+const ENTRANCE_SPACING: usize = 4;
+
+/// Abstract edges: the nodes reachable from each node and their costs.
+type Edges = HashMap<(i32, i32), Vec<((i32, i32), i32)>>;
+
+/// A step across a cluster border: (tile inside, tile in the neighbour).
+type Transition = ((i32, i32), (i32, i32));
+
+/// Abstract graph of a grid split into clusters.
+///
+/// Nodes are the tiles on either side of a transition, a step between two
+/// neighbouring clusters (cardinal, or diagonal when the movement rule lets
+/// diagonals pass blocked sides). Each maximal open run of a border
+/// (an entrance) gets one transition in its middle, or several spread along
+/// it when it is wide. Nodes of the same cluster are joined by the cost of the
+/// cheapest route inside it.
+///
+/// ## Usage
+/// 1. `build` once per map (preprocessing)
+/// 2. `find_path` for every query
+/// 3. `update_tiles` after editing tiles, before the next query
+pub struct HpaGraph {
+    cluster_size: u32,
+    width: u32,
+    height: u32,
+    /// Cost of moves, in the fixed-point units of the edge costs
+    cost_model: CostModel,
+    /// Neighbors a route may move to
+    movement: MovementRule,
+    /// Number of clusters horizontally
+    clusters_x: u32,
+    /// Transitions across the east border of each cluster
+    east: Vec<Vec<Transition>>,
+    /// Transitions across the south border of each cluster
+    south: Vec<Vec<Transition>>,
+    /// Cheapest routes between the nodes of each cluster
+    intra: Vec<Edges>,
+    /// Steps across cluster borders, in both directions
+    inter: Edges,
+    /// Time taken by the last `build` or `update_tiles`
+    pub preprocessing: Duration,
+}
+
+/// Result of a search that never leaves one cluster.
+struct ClusterSearch {
+    /// Tile the search started from
+    origin: (i32, i32),
+    /// Cluster bounds as (min_x, min_y, max_x, max_y), max exclusive
+    bounds: (i32, i32, i32, i32),
+    /// Cost and parent of every tile of the cluster (`i32::MAX` if unreached)
+    best: Vec<(i32, (i32, i32))>,
+    /// Number of tiles expanded
+    steps: u32,
+}
+
+impl ClusterSearch {
+    /// Index of `pos` in `best`, if it lies inside the cluster.
+    fn local(&self, pos: (i32, i32)) -> Option<usize> {
+        let (min_x, min_y, max_x, max_y) = self.bounds;
+        if pos.0 < min_x || pos.1 < min_y || pos.0 >= max_x || pos.1 >= max_y {
+            return None;
+        }
+        Some(((pos.1 - min_y) * (max_x - min_x) + pos.0 - min_x) as usize)
+    }
+
+    /// Cost of the cheapest route from the origin to `pos`, if it was reached.
+    fn cost(&self, pos: (i32, i32)) -> Option<i32> {
+        self.local(pos)
+            .map(|local| self.best[local].0)
+            .filter(|&cost| cost != i32::MAX)
+    }
+
+    /// Steps from the origin (excluded) to `to`, if it was reached.
+    fn path_to(&self, to: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        self.cost(to)?;
+        let mut path = vec![];
+        let mut current = to;
+        while current != self.origin {
+            path.push(current);
+            current = self.best[self.local(current)?].1;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl HpaGraph {
+    /// Split the grid into clusters and build the abstract graph.
+    ///
+    /// # Arguments
+    /// * `map` - Reference to the tile map
+    /// * `cluster_size` - Width and height of a cluster in tiles (at least 2)
+    /// * `cost_model` - Cost of moves
+    /// * `movement` - Neighbors a route may move to
+    ///
+    /// # Returns
+    /// The graph, with the time it took in `preprocessing`
+    pub fn build(
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        cluster_size: u32,
+        cost_model: CostModel,
+        movement: MovementRule,
+    ) -> Self {
+        let now = Instant::now();
+        let cluster_size = cluster_size.max(2);
+        let clusters_x = width.div_ceil(cluster_size);
+        let cluster_amount = (clusters_x * height.div_ceil(cluster_size)) as usize;
+        let mut graph = HpaGraph {
+            cluster_size,
+            width,
+            height,
+            cost_model,
+            movement,
+            clusters_x,
+            east: vec![vec![]; cluster_amount],
+            south: vec![vec![]; cluster_amount],
+            intra: vec![Edges::new(); cluster_amount],
+            inter: Edges::new(),
+            preprocessing: Duration::ZERO,
+        };
+        for cluster in 0..cluster_amount {
+            graph.east[cluster] = graph.find_transitions(cluster, true, map);
+            graph.south[cluster] = graph.find_transitions(cluster, false, map);
+        }
+        for cluster in 0..cluster_amount {
+            graph.connect_cluster(cluster, map);
+        }
+        graph.connect_borders(map);
+        graph.preprocessing = now.elapsed();
+        graph
+    }
+
+    /// Number of nodes in the abstract graph.
+    pub fn node_count(&self) -> usize {
+        self.intra.iter().map(|edges| edges.len()).sum()
+    }
+
+    /// Cost of the step from `from` to `to` in fixed-point units.
+    #[inline]
+    fn step(&self, from: (i32, i32), to: (i32, i32), map: &[Tile]) -> Option<i32> {
+        self.cost_model
+            .step_units(from, to, map, self.width, self.height)
+    }
+
+    /// Index of the cluster containing `pos`.
+    #[inline]
+    fn cluster_of(&self, pos: (i32, i32)) -> usize {
+        let size = self.cluster_size as i32;
+        (pos.1 / size) as usize * self.clusters_x as usize + (pos.0 / size) as usize
+    }
+
+    /// Tile bounds of a cluster as (min_x, min_y, max_x, max_y), max exclusive.
+    fn bounds(&self, cluster: usize) -> (i32, i32, i32, i32) {
+        let size = self.cluster_size as i32;
+        let x = (cluster % self.clusters_x as usize) as i32 * size;
+        let y = (cluster / self.clusters_x as usize) as i32 * size;
+        (
+            x,
+            y,
+            (x + size).min(self.width as i32),
+            (y + size).min(self.height as i32),
+        )
+    }
+
+    /// Transitions across the east (or south) border of a cluster.
+    ///
+    /// When diagonals may pass blocked sides, a diagonal step can be the only
+    /// way across a border, so diagonal crossings form entrances of their
+    /// own. A diagonal crossing of a vertical border belongs to the east
+    /// border of the cluster on its left, corners included; the south border
+    /// only keeps diagonals that stay in the same column of clusters. Under
+    /// the other rules every diagonal crossing has a cardinal one beside it.
+    fn find_transitions(&self, cluster: usize, east: bool, map: &[Tile]) -> Vec<Transition> {
+        let (min_x, min_y, max_x, max_y) = self.bounds(cluster);
+        let offsets: &[i32] = match self.movement.passes_blocked_sides() {
+            true => &[0, -1, 1],
+            false => &[0],
+        };
+
+        let mut transitions = vec![];
+        for &offset in offsets {
+            let crossings: Vec<Transition> = match east {
+                true if max_x < self.width as i32 => (min_y..max_y)
+                    .map(|y| ((max_x - 1, y), (max_x, y + offset)))
+                    .collect(),
+                false if max_y < self.height as i32 => (min_x..max_x)
+                    .filter(|x| (min_x..max_x).contains(&(x + offset)))
+                    .map(|x| ((x, max_y - 1), (x + offset, max_y)))
+                    .collect(),
+                _ => return vec![],
+            };
+
+            // An entrance is a run of open crossings into the same cluster
+            let mut entrance: Vec<Transition> = vec![];
+            for crossing in crossings.into_iter().map(Some).chain([None]) {
+                let open = crossing.filter(|&(inside, outside)| {
+                    get_possible_moves(inside, map, self.width, self.height, self.movement)
+                        .contains(&outside)
+                        && get_possible_moves(outside, map, self.width, self.height, self.movement)
+                            .contains(&inside)
+                });
+                let continues = match (open, entrance.last()) {
+                    (Some((_, outside)), Some(&(_, last))) => {
+                        self.cluster_of(outside) == self.cluster_of(last)
+                    }
+                    _ => true,
+                };
+                if open.is_none() || !continues {
+                    Self::place_transitions(&entrance, &mut transitions);
+                    entrance.clear();
+                }
+                entrance.extend(open);
+            }
+        }
+        transitions
+    }
+
+    /// Transitions of one entrance: its middle, or both ends and every
+    /// `ENTRANCE_SPACING` tiles between them when it is wide.
+    fn place_transitions(entrance: &[Transition], transitions: &mut Vec<Transition>) {
+        if entrance.len() >= WIDE_ENTRANCE {
+            transitions.extend(entrance.iter().step_by(ENTRANCE_SPACING));
+            if !(entrance.len() - 1).is_multiple_of(ENTRANCE_SPACING) {
+                transitions.push(entrance[entrance.len() - 1]);
+            }
+        } else if !entrance.is_empty() {
+            transitions.push(entrance[entrance.len() / 2]);
+        }
+    }
+
+    /// Cluster `dx` columns and `dy` rows away from `cluster`, if there is one.
+    fn neighbour(&self, cluster: usize, dx: i32, dy: i32) -> Option<usize> {
+        let clusters_x = self.clusters_x as i32;
+        let clusters_y = (self.intra.len() / self.clusters_x as usize) as i32;
+        let x = cluster as i32 % clusters_x + dx;
+        let y = cluster as i32 / clusters_x + dy;
+        ((0..clusters_x).contains(&x) && (0..clusters_y).contains(&y))
+            .then_some((y * clusters_x + x) as usize)
+    }
+
+    /// Nodes of a cluster: the inner side of every transition on its borders.
+    fn entrances(&self, cluster: usize) -> Vec<(i32, i32)> {
+        let mut entrances: Vec<(i32, i32)> = self.east[cluster]
+            .iter()
+            .chain(&self.south[cluster])
+            .map(|&(inside, _)| inside)
+            .collect();
+        // East borders on the left may also cross diagonally into this cluster
+        for dy in -1..=1 {
+            if let Some(left) = self.neighbour(cluster, -1, dy) {
+                entrances.extend(
+                    self.east[left]
+                        .iter()
+                        .map(|&(_, outside)| outside)
+                        .filter(|&outside| self.cluster_of(outside) == cluster),
+                );
+            }
+        }
+        if let Some(above) = self.neighbour(cluster, 0, -1) {
+            entrances.extend(self.south[above].iter().map(|&(_, outside)| outside));
+        }
+        entrances.sort();
+        entrances.dedup();
+        entrances
+    }
+
+    /// Cheapest routes from `from` to the other tiles of its cluster.
+    ///
+    /// Dijkstra with the graph's cost model, stopping early once `target` is
+    /// expanded.
+    fn cluster_search(
+        &self,
+        from: (i32, i32),
+        target: Option<(i32, i32)>,
+        map: &[Tile],
+        ctx: &mut SearchContext,
+    ) -> ClusterSearch {
+        let (min_x, min_y, max_x, max_y) = self.bounds(self.cluster_of(from));
+        let mut search = ClusterSearch {
+            origin: from,
+            bounds: (min_x, min_y, max_x, max_y),
+            best: vec![(i32::MAX, from); ((max_x - min_x) * (max_y - min_y)) as usize],
+            steps: 0,
+        };
+        let mut open = BinaryHeap::new();
+        if let Some(local) = search.local(from) {
+            search.best[local] = (0, from);
+            open.push(Reverse((0, from)));
+        }
+
+        while let Some(Reverse((cost, current))) = open.pop() {
+            if search.cost(current).is_some_and(|known| cost > known) {
+                continue; // outdated entry
+            }
+            search.steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if Some(current) == target {
+                break;
+            }
+            for next in get_possible_moves(current, map, self.width, self.height, self.movement) {
+                let Some(local) = search.local(next) else {
+                    continue;
+                };
+                let Some(step) = self.step(current, next, map) else {
+                    continue;
+                };
+                let next_cost = cost + step;
+                if next_cost < search.best[local].0 {
+                    search.best[local] = (next_cost, current);
+                    open.push(Reverse((next_cost, next)));
+                    ctx.record(next, TraceCell::Opened);
+                }
+            }
+        }
+        search
+    }
+
+    /// Recompute the routes between the nodes of a cluster.
+    fn connect_cluster(&mut self, cluster: usize, map: &[Tile]) {
+        let entrances = self.entrances(cluster);
+        let mut edges = Edges::new();
+        for &from in &entrances {
+            let search = self.cluster_search(from, None, map, &mut SearchContext::default());
+            let routes = entrances
+                .iter()
+                .filter(|&&to| to != from)
+                .filter_map(|&to| search.cost(to).map(|cost| (to, cost)))
+                .collect();
+            edges.insert(from, routes);
+        }
+        self.intra[cluster] = edges;
+    }
+
+    /// Recompute the steps across cluster borders from the transitions.
+    fn connect_borders(&mut self, map: &[Tile]) {
+        let mut inter = Edges::new();
+        for &(inside, outside) in self.east.iter().chain(&self.south).flatten() {
+            let (Some(forward), Some(backward)) = (
+                self.step(inside, outside, map),
+                self.step(outside, inside, map),
+            ) else {
+                continue;
+            };
+            inter.entry(inside).or_default().push((outside, forward));
+            inter.entry(outside).or_default().push((inside, backward));
+        }
+        self.inter = inter;
+    }
+
+    /// Tell the graph that the tiles at `changed` were edited in `map`.
+    ///
+    /// Only the clusters around the changed tiles find their entrances
+    /// again, and only they and their neighbours recompute their routes.
+    /// The time taken replaces `preprocessing`.
+    pub fn update_tiles(&mut self, changed: &[(i32, i32)], map: &[Tile]) {
+        let now = Instant::now();
+        let mut affected: Vec<usize> = vec![];
+        for &pos in changed {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let neighbor = (pos.0 + dx, pos.1 + dy);
+                    if get_idx_from_coordinate(neighbor, self.width, self.height).is_some() {
+                        affected.push(self.cluster_of(neighbor));
+                    }
+                }
+            }
+        }
+        affected.sort();
+        affected.dedup();
+        if affected.is_empty() {
+            return;
+        }
+
+        // Borders touching a cluster belong to it, the clusters on its left
+        // (diagonal crossings included) and the cluster above it; their
+        // transitions lead into the clusters right of and below them
+        let mut owners: Vec<usize> = vec![];
+        for &cluster in &affected {
+            for (dx, dy) in [(0, 0), (-1, -1), (-1, 0), (-1, 1), (0, -1)] {
+                owners.extend(self.neighbour(cluster, dx, dy));
+            }
+        }
+        owners.sort();
+        owners.dedup();
+        let mut reconnect = vec![];
+        for &owner in &owners {
+            self.east[owner] = self.find_transitions(owner, true, map);
+            self.south[owner] = self.find_transitions(owner, false, map);
+            for (dx, dy) in [(0, 0), (1, -1), (1, 0), (1, 1), (0, 1)] {
+                reconnect.extend(self.neighbour(owner, dx, dy));
+            }
+        }
+        reconnect.sort();
+        reconnect.dedup();
+        for cluster in reconnect {
+            self.connect_cluster(cluster, map);
+        }
+        self.connect_borders(map);
+        self.preprocessing = now.elapsed();
+    }
+
+    /// Find a path with the abstract graph.
+    ///
+    /// # Returns
+    /// The full path goal first, ending at `start`, or an empty path if no
+    /// route was found, together with the number of nodes expanded by the
+    /// abstract search and the cluster searches
+    pub fn find_path(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        let (Some(_), Some(_)) = (
+            get_idx_from_coordinate(start, self.width, self.height),
+            get_idx_from_coordinate(goal, self.width, self.height),
+        ) else {
+            return (vec![], 0);
+        };
+        if start == goal {
+            return (vec![start], 1);
+        }
+        let (start_cluster, goal_cluster) = (self.cluster_of(start), self.cluster_of(goal));
+
+        // Connect the start to its cluster's nodes
+        let start_search = self.cluster_search(start, None, map, ctx);
+        let mut steps = start_search.steps;
+        if start_cluster == goal_cluster {
+            if let Some(mut path) = start_search.path_to(goal) {
+                path.insert(0, start);
+                path.reverse();
+                return (path, steps);
+            }
+        }
+        let start_edges: Vec<((i32, i32), i32)> = self
+            .entrances(start_cluster)
+            .into_iter()
+            .filter(|&node| node != start)
+            .filter_map(|node| start_search.cost(node).map(|cost| (node, cost)))
+            .collect();
+
+        // Connect the goal's cluster nodes to it. Moves are symmetric, so the
+        // routes found from the goal are walked back; the cost model may
+        // charge the way back differently, so it is priced step by step.
+        let goal_search = self.cluster_search(goal, None, map, ctx);
+        steps += goal_search.steps;
+        let goal_edges: HashMap<(i32, i32), i32> = self
+            .entrances(goal_cluster)
+            .into_iter()
+            .filter(|&node| node != goal)
+            .filter_map(|node| {
+                let mut route = goal_search.path_to(node)?;
+                route.insert(0, goal);
+                let cost = route
+                    .windows(2)
+                    .map(|pair| self.step(pair[1], pair[0], map))
+                    .sum::<Option<i32>>()?;
+                Some((node, cost))
+            })
+            .collect();
+
+        // A* over the abstract graph (Chebyshev is admissible because every
+        // move costs at least the model's cheapest step)
+        let min_step = to_units(self.cost_model.min_step());
+        let heuristic =
+            |pos: (i32, i32)| (pos.0 - goal.0).abs().max((pos.1 - goal.1).abs()) * min_step;
+        let mut g_score: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        g_score.insert(start, 0);
+        open.push(Reverse((heuristic(start), start)));
+        while let Some(Reverse((f, current))) = open.pop() {
+            let cost = g_score[&current];
+            if f > cost + heuristic(current) {
+                continue; // outdated entry
+            }
+            steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
+            if current == goal {
+                break;
+            }
+            let mut edges = match current == start {
+                true => start_edges.clone(),
+                false => vec![],
+            };
+            if let Some(routes) = self.intra[self.cluster_of(current)].get(&current) {
+                edges.extend(routes);
+            }
+            if let Some(steps) = self.inter.get(&current) {
+                edges.extend(steps);
+            }
+            if let Some(&cost) = goal_edges.get(&current) {
+                edges.push((goal, cost));
+            }
+            for (next, step) in edges {
+                let next_cost = cost + step;
+                if g_score.get(&next).is_none_or(|&known| next_cost < known) {
+                    g_score.insert(next, next_cost);
+                    parent.insert(next, current);
+                    open.push(Reverse((next_cost + heuristic(next), next)));
+                    ctx.record(next, TraceCell::Opened);
+                }
+            }
+        }
+        if !g_score.contains_key(&goal) {
+            return (vec![], steps);
+        }
+
+        let mut nodes = vec![goal];
+        let mut node = goal;
+        while let Some(&prev) = parent.get(&node) {
+            nodes.push(prev);
+            node = prev;
+        }
+        nodes.reverse();
+
+        // Refine every abstract edge into grid steps
+        let mut path = vec![start];
+        for pair in nodes.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if self.cluster_of(from) != self.cluster_of(to) {
+                path.push(to);
+                continue;
+            }
+            let search = match from {
+                _ if from == start => start_search.path_to(to),
+                _ => {
+                    let search = self.cluster_search(from, Some(to), map, ctx);
+                    steps += search.steps;
+                    if ctx.out_of_budget(steps) {
+                        return (vec![], steps);
+                    }
+                    search.path_to(to)
+                }
+            };
+            match search {
+                Some(route) => path.extend(route),
+                None => return (vec![], steps),
+            }
+        }
+        path.reverse();
+        (path, steps)
+    }
+}
+
+/// HPA* planning on a freshly built graph.
+///
+/// Builds an `HpaGraph` for every query so it can be used and benchmarked
+/// like the other planners; the build is reported as preprocessing time
+/// through the `SearchContext`. Keep an `HpaGraph` between queries to
+/// reuse it (see `replanning::Replanner`).
+pub struct HpaStar {
+    /// Width and height of a cluster in tiles
+    pub cluster_size: u32,
+    /// Cost of moves
+    pub cost_model: CostModel,
+    /// Neighbors a route may move to
+    pub movement: MovementRule,
+}
+
+impl Default for HpaStar {
+    fn default() -> Self {
+        HpaStar {
+            cluster_size: DEFAULT_CLUSTER_SIZE,
+            cost_model: CostModel::default(),
+            movement: MovementRule::default(),
+        }
+    }
+}
+
+impl PathfindingAlgorithm for HpaStar {
+    fn search(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        let graph = HpaGraph::build(
+            map,
+            width,
+            height,
+            self.cluster_size,
+            self.cost_model,
+            self.movement,
+        );
+        ctx.preprocessing += graph.preprocessing;
+        graph.find_path(start, goal, map, ctx)
+    }
+
+    fn returns_full_path(&self) -> bool {
+        true
+    }
+
+    fn reconstruct_path(&self, path: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        path
+    }
+
+    fn name(&self) -> &str {
+        HPA_ALGORITHM
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation;
+    use crate::grid::{Grid, TileType};
+    use crate::pathfinding::{
+        AStarSearch, Agent, DijkstraSearch, FailureReason, Heuristic, SearchConfig,
+    };
+
+    /// Helper: random grid with weighted tiles, its corners kept open
+    fn random_grid(size: u32, obstacles: usize, seed: u64) -> Grid {
+        let mut grid = Grid::new(size, size);
+        generation::random_grid(&mut grid, 20, obstacles, 30, false, Some(seed));
+        let last = grid.tile_amount() - 1;
+        grid.tiles[0] = Tile::new((0, 0), TileType::Floor, 1);
+        grid.tiles[last] = Tile::new(grid.tiles[last].position, TileType::Floor, 1);
+        grid
+    }
+
+    /// Helper: cost of a goal-first path, charging each entered tile's weight
+    fn path_cost(path: &[(i32, i32)], grid: &Grid) -> i32 {
+        path.iter()
+            .rev()
+            .skip(1)
+            .map(|&pos| grid.tile(pos).map_or(0, |tile| tile.weight as i32))
+            .sum()
+    }
+
+    fn assert_valid_path(
+        path: &[(i32, i32)],
+        start: (i32, i32),
+        goal: (i32, i32),
+        grid: &Grid,
+        movement: MovementRule,
+    ) {
+        assert_eq!(path.first(), Some(&goal));
+        assert_eq!(path.last(), Some(&start));
+        for step in path.windows(2) {
+            let moves = get_possible_moves(step[1], &grid.tiles, grid.width, grid.height, movement);
+            assert!(moves.contains(&step[0]), "{:?} -> {:?}", step[1], step[0]);
+        }
+    }
+
+    // ------- HpaGraph -------
+
+    #[test]
+    fn test_open_grid_transitions_are_spaced() {
+        // 4x4 clusters of 8 tiles: 12 east and 12 south borders, each a
+        // wide entrance with transitions at offsets 0, 4 and 7
+        let grid = Grid::new(32, 32);
+        let graph = HpaGraph::build(
+            &grid.tiles,
+            32,
+            32,
+            8,
+            CostModel::default(),
+            MovementRule::default(),
+        );
+        let transitions: usize = graph.east.iter().chain(&graph.south).map(Vec::len).sum();
+        assert_eq!(transitions, 24 * 3);
+        assert_eq!(
+            graph.east[0],
+            vec![((7, 0), (8, 0)), ((7, 4), (8, 4)), ((7, 7), (8, 7))]
+        );
+        assert!(graph.node_count() > 0);
+    }
+
+    #[test]
+    fn test_paths_are_valid_and_close_to_optimal() {
+        let astar = AStarSearch {
+            config: SearchConfig {
+                heuristic: Heuristic::Chebyshev,
+                heuristic_weight: 1.0,
+                ..SearchConfig::default()
+            },
+        };
+        for seed in 0..10 {
+            let grid = random_grid(64, 20, seed);
+            let graph = HpaGraph::build(
+                &grid.tiles,
+                64,
+                64,
+                16,
+                CostModel::default(),
+                MovementRule::default(),
+            );
+            for (start, goal) in [((0, 0), (63, 63)), ((5, 40), (50, 3)), ((2, 2), (9, 12))] {
+                let (path, _) =
+                    graph.find_path(start, goal, &grid.tiles, &mut SearchContext::default());
+                let (optimal, _) = astar.find_path(start, goal, &grid.tiles, 64, 64);
+                assert_eq!(path.is_empty(), optimal.is_empty(), "seed {}", seed);
+                if path.is_empty() {
+                    continue;
+                }
+                assert_valid_path(&path, start, goal, &grid, MovementRule::default());
+                let (cost, best) = (path_cost(&path, &grid), path_cost(&optimal, &grid));
+                assert!(cost >= best);
+                assert!(cost <= best * 3 / 2, "seed {}: {} vs {}", seed, cost, best);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_movement_rule_matches_dijkstra_reachability() {
+        // Small maps of several 4x4 clusters: HPA* finds a path exactly when
+        // Dijkstra does, and it is never cheaper than Dijkstra's
+        for movement in MovementRule::ALL {
+            for cost_model in CostModel::ALL {
+                let dijkstra = DijkstraSearch {
+                    cost_model,
+                    movement,
+                };
+                for seed in 0..40 {
+                    let size = 4 + (seed % 21) as u32;
+                    let grid = random_grid(size, 30, seed);
+                    let graph = HpaGraph::build(&grid.tiles, size, size, 4, cost_model, movement);
+                    let (start, goal) = ((0, 0), (size as i32 - 1, size as i32 - 1));
+                    let (path, _) =
+                        graph.find_path(start, goal, &grid.tiles, &mut SearchContext::default());
+                    let (optimal, _) = dijkstra.find_path(start, goal, &grid.tiles, size, size);
+                    let context =
+                        format!("{} {} seed {}", movement.name(), cost_model.name(), seed);
+                    assert_eq!(path.is_empty(), optimal.is_empty(), "{}", context);
+                    if path.is_empty() {
+                        continue;
+                    }
+                    assert_valid_path(&path, start, goal, &grid, movement);
+                    let cost = cost_model.path_cost(&path, &grid.tiles, size, size);
+                    let best = cost_model.path_cost(&optimal, &grid.tiles, size, size);
+                    assert!(cost >= best - 1e-3, "{}: {} vs {}", context, cost, best);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_path_through_wall() {
+        let mut grid = Grid::new(32, 32);
+        for y in 0..32 {
+            grid.tiles[y * 32 + 20].change_tile_type(TileType::Obstacle);
+        }
+        let graph = HpaGraph::build(
+            &grid.tiles,
+            32,
+            32,
+            8,
+            CostModel::default(),
+            MovementRule::default(),
+        );
+        let (path, _) =
+            graph.find_path((0, 0), (31, 31), &grid.tiles, &mut SearchContext::default());
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_corner_cutting_crosses_borders_diagonally() {
+        // 2x2 clusters of 4 tiles. Walls leave a single diagonal squeeze
+        // across the vertical border, or across the corner of all four
+        // clusters; both pass two blocked sides.
+        let mut border = Grid::new(8, 8);
+        let mut corner = Grid::new(8, 8);
+        for i in 0..8 {
+            border.tiles[i * 8 + 3].change_tile_type(TileType::Obstacle);
+            border.tiles[i * 8 + 4].change_tile_type(TileType::Obstacle);
+            corner.tiles[i * 8 + 4].change_tile_type(TileType::Obstacle);
+            corner.tiles[4 * 8 + i].change_tile_type(TileType::Obstacle);
+        }
+        border.tiles[8 + 3].change_tile_type(TileType::Floor);
+        border.tiles[2 * 8 + 4].change_tile_type(TileType::Floor);
+        corner.tiles[4 * 8 + 4].change_tile_type(TileType::Floor);
+
+        for grid in [border, corner] {
+            for movement in [MovementRule::CornerCutting, MovementRule::NoCornerCutting] {
+                let graph = HpaGraph::build(&grid.tiles, 8, 8, 4, CostModel::default(), movement);
+                let (path, _) =
+                    graph.find_path((0, 0), (7, 7), &grid.tiles, &mut SearchContext::default());
+                match movement {
+                    MovementRule::CornerCutting => {
+                        assert_valid_path(&path, (0, 0), (7, 7), &grid, movement)
+                    }
+                    _ => assert!(path.is_empty()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_tiles_matches_rebuilt_graph() {
+        for movement in [MovementRule::default(), MovementRule::CornerCutting] {
+            let mut grid = random_grid(48, 20, 7);
+            let mut graph =
+                HpaGraph::build(&grid.tiles, 48, 48, 16, CostModel::default(), movement);
+            // A border tile, a cluster corner and an inner tile
+            let changed = [(15, 20), (31, 32), (40, 8)];
+            for &pos in &changed {
+                let idx = (pos.1 * 48 + pos.0) as usize;
+                let tile_type = match grid.tiles[idx].is_traversable() {
+                    true => TileType::Obstacle,
+                    false => TileType::Floor,
+                };
+                grid.tiles[idx].change_tile_type(tile_type);
+            }
+            grid.tiles[5 * 48 + 5].weight = 200;
+            graph.update_tiles(&changed, &grid.tiles);
+            graph.update_tiles(&[(5, 5)], &grid.tiles);
+
+            let rebuilt = HpaGraph::build(&grid.tiles, 48, 48, 16, CostModel::default(), movement);
+            assert_eq!(graph.east, rebuilt.east);
+            assert_eq!(graph.south, rebuilt.south);
+            assert_eq!(graph.intra, rebuilt.intra);
+            assert_eq!(graph.inter, rebuilt.inter);
+        }
+    }
+
+    // ------- HpaStar -------
+
+    #[test]
+    fn test_expands_fewer_nodes_than_astar_on_large_grid() {
+        let grid = random_grid(256, 20, 3);
+        let mut agent = Agent {
+            start: (0, 0),
+            goal: (255, 255),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let config = SearchConfig::default();
+        let astar = agent.get_path("A* search", &config, &grid.tiles, 256, 256);
+        let hpa = agent.get_path(HPA_ALGORITHM, &config, &grid.tiles, 256, 256);
+        assert!(astar.is_success() && hpa.is_success());
+        assert!(
+            hpa.expansions < astar.expansions,
+            "{} >= {}",
+            hpa.expansions,
+            astar.expansions
+        );
+        assert!(hpa.preprocessing > Duration::ZERO);
+        assert_eq!(astar.preprocessing, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_get_path_reports_unreachable() {
+        let mut grid = Grid::new(16, 16);
+        for x in 0..16 {
+            grid.tiles[8 * 16 + x].change_tile_type(TileType::Obstacle);
+        }
+        let mut agent = Agent {
+            start: (0, 0),
+            goal: (15, 15),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let result = agent.get_path(HPA_ALGORITHM, &SearchConfig::default(), &grid.tiles, 16, 16);
+        assert_eq!(result.failure, Some(FailureReason::Unreachable));
+    }
+}
@@ -0,0 +1,434 @@
+//! # Edit History Module
+//!
+//! This module provides undo/redo for board editing:
+//! - `GridEdit`: one undoable change to a `Grid`
+//! - `EditBuilder`: merges the tile changes of one mouse stroke into a single edit
+//! - `EditHistory`: undo and redo stacks with a bounded memory footprint
+//!
+//! In-place changes (clicks, generation, loading a map of the same size) are
+//! stored as per-tile differences, so their cost is proportional to the number
+//! of tiles that changed. Only a change of dimensions keeps both full grids.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::mem::size_of;
+
+use crate::grid::{Grid, Tile};
+
+/// Default memory budget of an `EditHistory` (16 MiB).
+pub const DEFAULT_HISTORY_BYTES: usize = 16 * 1024 * 1024;
+
+/// A single tile before and after an edit.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TileChange {
+    /// Index of the tile in the grid
+    pub idx: usize,
+    /// The tile before the edit
+    pub before: Tile,
+    /// The tile after the edit
+    pub after: Tile,
+}
+
+/// One undoable change to a grid.
+#[derive(Clone, Debug)]
+pub enum GridEdit {
+    /// Tiles, starts and goals changed on a grid of unchanged dimensions
+    Tiles {
+        /// Every tile that differs between the two states
+        changes: Vec<TileChange>,
+        /// Starts before and after the edit
+        starts: (Vec<usize>, Vec<usize>),
+        /// Goals before and after the edit
+        goals: (Vec<usize>, Vec<usize>),
+    },
+    /// The grid was replaced by one of different dimensions
+    Resize {
+        /// The grid before the edit
+        before: Grid,
+        /// The grid after the edit
+        after: Grid,
+    },
+}
+
+impl GridEdit {
+    /// Describe the change between two grid states.
+    ///
+    /// # Returns
+    /// The edit, or `None` if both states are identical
+    pub fn between(before: &Grid, after: &Grid) -> Option<GridEdit> {
+        if before.width != after.width || before.height != after.height {
+            return Some(GridEdit::Resize {
+                before: before.clone(),
+                after: after.clone(),
+            });
+        }
+        let changes: Vec<TileChange> = before
+            .tiles
+            .iter()
+            .zip(after.tiles.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(idx, (a, b))| TileChange {
+                idx,
+                before: *a,
+                after: *b,
+            })
+            .collect();
+        if changes.is_empty() && before.starts == after.starts && before.goals == after.goals {
+            return None;
+        }
+        Some(GridEdit::Tiles {
+            changes,
+            starts: (before.starts.clone(), after.starts.clone()),
+            goals: (before.goals.clone(), after.goals.clone()),
+        })
+    }
+
+    /// Revert the edit on `grid`.
+    pub fn undo(&self, grid: &mut Grid) {
+        match self {
+            GridEdit::Tiles {
+                changes,
+                starts,
+                goals,
+            } => {
+                for change in changes {
+                    if let Some(tile) = grid.tiles.get_mut(change.idx) {
+                        *tile = change.before;
+                    }
+                }
+                grid.starts = starts.0.clone();
+                grid.goals = goals.0.clone();
+            }
+            GridEdit::Resize { before, .. } => *grid = before.clone(),
+        }
+    }
+
+    /// Apply the edit to `grid` again.
+    pub fn redo(&self, grid: &mut Grid) {
+        match self {
+            GridEdit::Tiles {
+                changes,
+                starts,
+                goals,
+            } => {
+                for change in changes {
+                    if let Some(tile) = grid.tiles.get_mut(change.idx) {
+                        *tile = change.after;
+                    }
+                }
+                grid.starts = starts.1.clone();
+                grid.goals = goals.1.clone();
+            }
+            GridEdit::Resize { after, .. } => *grid = after.clone(),
+        }
+    }
+
+    /// Approximate heap memory held by the edit, in bytes.
+    pub fn size_bytes(&self) -> usize {
+        let indices =
+            |list: &(Vec<usize>, Vec<usize>)| (list.0.len() + list.1.len()) * size_of::<usize>();
+        let grid = |grid: &Grid| {
+            grid.tiles.len() * size_of::<Tile>()
+                + (grid.starts.len() + grid.goals.len()) * size_of::<usize>()
+        };
+        size_of::<GridEdit>()
+            + match self {
+                GridEdit::Tiles {
+                    changes,
+                    starts,
+                    goals,
+                } => changes.len() * size_of::<TileChange>() + indices(starts) + indices(goals),
+                GridEdit::Resize { before, after } => grid(before) + grid(after),
+            }
+    }
+}
+
+/// Collects the tile changes of one stroke (mouse press to release).
+#[derive(Clone, Debug)]
+pub struct EditBuilder {
+    /// First `before` and latest `after` of every touched tile
+    tiles: BTreeMap<usize, (Tile, Tile)>,
+    /// Starts when the stroke began
+    starts: Vec<usize>,
+    /// Goals when the stroke began
+    goals: Vec<usize>,
+}
+
+impl EditBuilder {
+    /// Start a stroke on a grid with the given starts and goals.
+    pub fn new(starts: &[usize], goals: &[usize]) -> Self {
+        EditBuilder {
+            tiles: BTreeMap::new(),
+            starts: starts.to_vec(),
+            goals: goals.to_vec(),
+        }
+    }
+
+    /// Record that the tile at `idx` changed from `before` to `after`.
+    ///
+    /// Repeated changes of the same tile are merged.
+    pub fn tile(&mut self, idx: usize, before: Tile, after: Tile) {
+        self.tiles
+            .entry(idx)
+            .and_modify(|change| change.1 = after)
+            .or_insert((before, after));
+    }
+
+    /// Finish the stroke.
+    ///
+    /// # Arguments
+    /// * `starts` - Starts after the stroke
+    /// * `goals` - Goals after the stroke
+    ///
+    /// # Returns
+    /// The edit, or `None` if the stroke left the grid unchanged
+    pub fn finish(self, starts: &[usize], goals: &[usize]) -> Option<GridEdit> {
+        let changes: Vec<TileChange> = self
+            .tiles
+            .into_iter()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(idx, (before, after))| TileChange { idx, before, after })
+            .collect();
+        if changes.is_empty() && self.starts == starts && self.goals == goals {
+            return None;
+        }
+        Some(GridEdit::Tiles {
+            changes,
+            starts: (self.starts, starts.to_vec()),
+            goals: (self.goals, goals.to_vec()),
+        })
+    }
+}
+
+/// Undo and redo stacks limited to a memory budget.
+///
+/// When the budget is exceeded the oldest edits are forgotten; the most
+/// recent edit is always kept so it can be undone.
+#[derive(Clone, Debug)]
+pub struct EditHistory {
+    /// Edits that can be undone, oldest first
+    undo: VecDeque<GridEdit>,
+    /// Edits that can be redone, most recently undone last
+    redo: Vec<GridEdit>,
+    /// Memory held by both stacks
+    used_bytes: usize,
+    /// Memory budget
+    max_bytes: usize,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        EditHistory::with_limit(DEFAULT_HISTORY_BYTES)
+    }
+}
+
+impl EditHistory {
+    /// Create an empty history with the given memory budget in bytes.
+    pub fn with_limit(max_bytes: usize) -> Self {
+        EditHistory {
+            undo: VecDeque::new(),
+            redo: vec![],
+            used_bytes: 0,
+            max_bytes,
+        }
+    }
+
+    /// Add a new edit. Anything that could be redone is discarded.
+    pub fn record(&mut self, edit: GridEdit) {
+        for dropped in self.redo.drain(..) {
+            self.used_bytes -= dropped.size_bytes();
+        }
+        self.used_bytes += edit.size_bytes();
+        self.undo.push_back(edit);
+        while self.used_bytes > self.max_bytes && self.undo.len() > 1 {
+            if let Some(oldest) = self.undo.pop_front() {
+                self.used_bytes -= oldest.size_bytes();
+            }
+        }
+    }
+
+    /// Revert the most recent edit on `grid`.
+    ///
+    /// # Returns
+    /// `false` if there was nothing to undo
+    pub fn undo(&mut self, grid: &mut Grid) -> bool {
+        let Some(edit) = self.undo.pop_back() else {
+            return false;
+        };
+        edit.undo(grid);
+        self.redo.push(edit);
+        true
+    }
+
+    /// Re-apply the most recently undone edit on `grid`.
+    ///
+    /// # Returns
+    /// `false` if there was nothing to redo
+    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+        edit.redo(grid);
+        self.undo.push_back(edit);
+        true
+    }
+
+    /// Number of edits that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Number of edits that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    /// Memory currently held by the history, in bytes.
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    /// Forget every edit.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.used_bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::TileType;
+
+    fn with_obstacle(mut grid: Grid, idx: usize) -> Grid {
+        grid.tiles[idx].change_tile_type(TileType::Obstacle);
+        grid
+    }
+
+    fn tile_types(grid: &Grid) -> Vec<TileType> {
+        grid.tiles.iter().map(|t| t.tile_type()).collect()
+    }
+
+    // ------- GridEdit -------
+
+    #[test]
+    fn test_between_identical_grids_is_none() {
+        let grid = Grid::new(4, 4);
+        assert!(GridEdit::between(&grid, &grid.clone()).is_none());
+    }
+
+    #[test]
+    fn test_between_stores_only_changed_tiles() {
+        let before = Grid::new(4, 4);
+        let mut after = with_obstacle(before.clone(), 5);
+        after.starts = vec![0];
+        let edit = GridEdit::between(&before, &after).unwrap();
+        match &edit {
+            GridEdit::Tiles {
+                changes, starts, ..
+            } => {
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].idx, 5);
+                assert_eq!(starts, &(vec![], vec![0]));
+            }
+            GridEdit::Resize { .. } => panic!("same dimensions must not store full grids"),
+        }
+
+        let mut grid = after.clone();
+        edit.undo(&mut grid);
+        assert_eq!(tile_types(&grid), tile_types(&before));
+        assert!(grid.starts.is_empty());
+        edit.redo(&mut grid);
+        assert_eq!(tile_types(&grid), tile_types(&after));
+        assert_eq!(grid.starts, vec![0]);
+    }
+
+    #[test]
+    fn test_resize_restores_dimensions() {
+        let before = Grid::new(4, 4);
+        let after = Grid::new(8, 2);
+        let edit = GridEdit::between(&before, &after).unwrap();
+        let mut grid = after.clone();
+        edit.undo(&mut grid);
+        assert_eq!((grid.width, grid.height, grid.tiles.len()), (4, 4, 16));
+        edit.redo(&mut grid);
+        assert_eq!((grid.width, grid.height, grid.tiles.len()), (8, 2, 16));
+    }
+
+    // ------- EditBuilder -------
+
+    #[test]
+    fn test_builder_merges_repeated_changes() {
+        let floor = Tile::new((1, 0), TileType::Floor, 1);
+        let obstacle = Tile::new((1, 0), TileType::Obstacle, 1);
+        let mut builder = EditBuilder::new(&[], &[]);
+        builder.tile(1, floor, obstacle);
+        builder.tile(1, obstacle, floor);
+        // The tile ended where it started
+        assert!(builder.finish(&[], &[]).is_none());
+
+        let mut builder = EditBuilder::new(&[], &[]);
+        builder.tile(1, floor, obstacle);
+        builder.tile(2, floor, obstacle);
+        match builder.finish(&[], &[3]).unwrap() {
+            GridEdit::Tiles { changes, goals, .. } => {
+                assert_eq!(changes.len(), 2);
+                assert_eq!(goals, (vec![], vec![3]));
+            }
+            GridEdit::Resize { .. } => panic!("strokes never resize"),
+        }
+    }
+
+    // ------- EditHistory -------
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut history = EditHistory::default();
+        let original = Grid::new(3, 3);
+        let first = with_obstacle(original.clone(), 0);
+        let second = with_obstacle(first.clone(), 1);
+        history.record(GridEdit::between(&original, &first).unwrap());
+        history.record(GridEdit::between(&first, &second).unwrap());
+
+        let mut grid = second.clone();
+        assert!(history.undo(&mut grid));
+        assert!(history.undo(&mut grid));
+        assert!(!history.undo(&mut grid));
+        assert_eq!(tile_types(&grid), tile_types(&original));
+
+        assert!(history.redo(&mut grid));
+        assert_eq!(tile_types(&grid), tile_types(&first));
+        assert_eq!((history.undo_len(), history.redo_len()), (1, 1));
+    }
+
+    #[test]
+    fn test_record_discards_redo() {
+        let mut history = EditHistory::default();
+        let original = Grid::new(3, 3);
+        let edited = with_obstacle(original.clone(), 0);
+        history.record(GridEdit::between(&original, &edited).unwrap());
+        let mut grid = edited.clone();
+        history.undo(&mut grid);
+        history.record(GridEdit::between(&original, &with_obstacle(original.clone(), 4)).unwrap());
+        assert_eq!(history.redo_len(), 0);
+        assert!(!history.redo(&mut grid));
+    }
+
+    #[test]
+    fn test_history_stays_within_budget() {
+        let original = Grid::new(10, 10);
+        let edit = GridEdit::between(&original, &with_obstacle(original.clone(), 0)).unwrap();
+        let budget = edit.size_bytes() * 3;
+        let mut history = EditHistory::with_limit(budget);
+        for _ in 0..10 {
+            history.record(edit.clone());
+        }
+        assert_eq!(history.undo_len(), 3);
+        assert!(history.used_bytes() <= budget);
+
+        history.clear();
+        assert_eq!(history.used_bytes(), 0);
+        assert_eq!(history.undo_len(), 0);
+    }
+}
@@ -0,0 +1,67 @@
+//! # PathMaker Core Library
+//!
+//! The display-free core of PathMaker. Everything needed to generate grids,
+//! run the planners and collect benchmark metrics lives here, so it can be
+//! embedded in other programs and tested on machines without SDL. The
+//! `pathmaker` binary is a thin SDL front-end on top of this crate.
+//!
+//! ## Modules
+//! - `board_file`: compact, versioned board file format
+//! - `cost`: the movement-cost model shared by planners and metrics
+//! - `grid`: tiles, weights, starts/goals and coordinate helpers
+//! - `hierarchical`: HPA* clustering and abstract graph for large grids
+//! - `history`: bounded undo/redo history of grid edits
+//! - `pathfinding`: the `PathfindingAlgorithm` trait, planners and `Agent`
+//! - `generation`: random and city-style map generators
+//! - `benchmarks`: metric collection and the benchmark runner
+//! - `metrics`: allocation tracking used for the memory metric
+//! - `movingai`: MovingAI `.map`/`.scen` loading and optimality validation
+//! - `multi_agent`: collision-free planning of several agents (Cooperative A*, CBS)
+//! - `palette`: color palette turning level images into grids
+//! - `playback`: play/pause, speed and step-back history of the path animation
+//! - `replanning`: incremental (D* Lite) versus full replanning as tiles change
+//! - `settings`: persisted application settings
+//! - `trace`: search trace recording and replay for visualisation
+//!
+//! ## Example
+//! ```
+//! use game_ex::grid::Grid;
+//! use game_ex::pathfinding::get_algorithm;
+//!
+//! let grid = Grid::new(10, 10);
+//! let (path, _) = get_algorithm("A* search").find_path((0, 0), (9, 9), &grid.tiles, 10, 10);
+//! assert_eq!(path[0], (9, 9));
+//! ```
+
+/// Benchmarking utilities for measuring pathfinding performance
+pub mod benchmarks;
+/// Versioned board file format
+pub mod board_file;
+/// Movement-cost model
+pub mod cost;
+/// Procedural map generators
+pub mod generation;
+/// Display-free grid model
+pub mod grid;
+/// Hierarchical pathfinding (HPA*)
+pub mod hierarchical;
+/// Undo/redo history of grid edits
+pub mod history;
+/// Allocation tracking for the memory metric
+pub mod metrics;
+/// MovingAI benchmark maps and scenarios
+pub mod movingai;
+/// Collision-free multi-agent planning
+pub mod multi_agent;
+/// Image color palette for board imports
+pub mod palette;
+/// Pathfinding algorithms implementation (Greedy, BFS, A*, JPSW)
+pub mod pathfinding;
+/// Path animation playback controls
+pub mod playback;
+/// Replanning after tile changes
+pub mod replanning;
+/// Application settings and configuration persistence
+pub mod settings;
+/// Search trace recording and replay
+pub mod trace;
@@ -0,0 +1,76 @@
+//! # Metrics Module
+//!
+//! Allocation tracking used to report how many bytes a search allocates.
+//!
+//! With the default `global-allocator` feature the library installs jemalloc
+//! (Unix) or a counting mimalloc wrapper (Windows) as the global allocator so
+//! allocations can be measured. Programs that embed the planners and bring
+//! their own allocator can disable the feature; memory is then reported as 0.
+
+#[cfg(not(target_os = "windows"))]
+use jemalloc_ctl::{epoch, thread};
+
+/// Global allocator using jemalloc for improved memory allocation performance
+/// and accurate memory usage tracking during pathfinding benchmarks.
+#[cfg(all(feature = "global-allocator", not(target_os = "windows")))]
+#[global_allocator]
+static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+#[cfg(all(feature = "global-allocator", target_os = "windows"))]
+#[global_allocator]
+static ALLOC: cap::Cap<mimalloc::MiMalloc> = cap::Cap::new(mimalloc::MiMalloc, usize::MAX);
+
+/// Reads the allocation counter of the current thread (Unix) or process (Windows).
+pub struct AllocationCounter {
+    #[cfg(not(target_os = "windows"))]
+    allocated: Option<thread::allocatedp_mib>,
+}
+
+impl Default for AllocationCounter {
+    fn default() -> Self {
+        AllocationCounter {
+            #[cfg(not(target_os = "windows"))]
+            allocated: thread::allocatedp::mib().ok(),
+        }
+    }
+}
+
+impl AllocationCounter {
+    /// Snapshot the number of bytes allocated so far.
+    ///
+    /// Only differences between two snapshots are meaningful.
+    pub fn allocated(&self) -> u64 {
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = epoch::advance();
+            self.allocated
+                .as_ref()
+                .and_then(|mib| mib.read().ok())
+                .map_or(0, |allocated| allocated.get())
+        }
+        #[cfg(all(feature = "global-allocator", target_os = "windows"))]
+        {
+            ALLOC.allocated() as u64
+        }
+        #[cfg(all(not(feature = "global-allocator"), target_os = "windows"))]
+        {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocation_counter_sees_allocations() {
+        let counter = AllocationCounter::default();
+        let before = counter.allocated();
+        let data: Vec<u64> = vec![7; 1 << 16];
+        let after = counter.allocated();
+        assert_eq!(data.len(), 1 << 16);
+        if cfg!(feature = "global-allocator") {
+            assert!(after >= before + (1 << 16) * 8);
+        }
+    }
+}
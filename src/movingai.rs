@@ -0,0 +1,689 @@
+//! # MovingAI Benchmark Module
+//!
+//! This module reads the grid maps (`.map`) and scenario files (`.scen`)
+//! published with the MovingAI pathfinding benchmark sets and checks a
+//! planner against the reference optimal lengths listed in the scenarios.
+//!
+//! ## Scenario Format
+//! ```text
+//! version 1
+//! <bucket> <map> <width> <height> <start x> <start y> <goal x> <goal y> <optimal length>
+//! ```
+//!
+//! Reference lengths are octile distances: orthogonal steps cost 1, diagonal
+//! steps cost √2 and corners may not be cut. Planners only match them under
+//! `CostModel::OCTILE` and `MovementRule::NoCornerCutting`, so scenario runs
+//! always use those. Path lengths are measured geometrically with
+//! `pathfinding::path_length`, independent of tile weights.
+//!
+//! ## Map Format
+//! ```text
+//! type octile
+//! height <rows>
+//! width <columns>
+//! map
+//! <rows lines of exactly <columns> terrain symbols>
+//! ```
+//!
+//! ## Terrain Classes
+//! | Symbol  | Terrain       | Tile                             | Weight                 |
+//! |---------|---------------|----------------------------------|------------------------|
+//! | `.` `G` | ground        | `Floor`                          | `TerrainCosts::ground` |
+//! | `S`     | swamp         | `Floor`                          | `TerrainCosts::swamp`  |
+//! | `W`     | water         | `Water`, entered only from water | `TerrainCosts::water`  |
+//! | `@` `O` | out of bounds | `Obstacle`                       | -                      |
+//! | `T`     | trees         | `Obstacle`                       | -                      |
+//!
+//! The default costs are all 1 so the grid matches the reference lengths;
+//! raise them to make swamps and water more expensive. Planners that prune
+//! neighbors on their own (JPSW) treat water as an obstacle.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{get_coordinate_from_idx, Grid, Tile, TileType};
+use crate::pathfinding::{path_length, PathfindingAlgorithm, SearchBudget, SearchContext};
+
+/// Relative tolerance used when comparing lengths to the reference.
+///
+/// Scenario files round optimal lengths to a few decimals.
+const LENGTH_TOLERANCE: f64 = 1e-6;
+
+/// Traversal weights of the passable MovingAI terrain classes.
+///
+/// See the module documentation for the full terrain table.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TerrainCosts {
+    /// Weight of ground tiles (`.` and `G`)
+    pub ground: u8,
+    /// Weight of swamp tiles (`S`)
+    pub swamp: u8,
+    /// Weight of water tiles (`W`)
+    pub water: u8,
+}
+
+/// Unit costs for every class, matching the scenario reference lengths.
+impl Default for TerrainCosts {
+    fn default() -> Self {
+        TerrainCosts {
+            ground: 1,
+            swamp: 1,
+            water: 1,
+        }
+    }
+}
+
+impl TerrainCosts {
+    /// Tile type and weight of a terrain symbol.
+    ///
+    /// # Returns
+    /// `None` if the symbol is not a MovingAI terrain class
+    pub fn tile_for(&self, symbol: char) -> Option<(TileType, u8)> {
+        match symbol {
+            '.' | 'G' => Some((TileType::Floor, self.ground)),
+            'S' => Some((TileType::Floor, self.swamp)),
+            'W' => Some((TileType::Water, self.water)),
+            '@' | 'O' | 'T' => Some((TileType::Obstacle, 1)),
+            _ => None,
+        }
+    }
+}
+
+/// A single start/goal query from a `.scen` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    /// Difficulty bucket (scenarios are grouped by optimal length)
+    pub bucket: u32,
+    /// Map file the scenario was generated for
+    pub map: String,
+    /// Width of the map in tiles
+    pub map_width: u32,
+    /// Height of the map in tiles
+    pub map_height: u32,
+    /// Start position (grid coordinates)
+    pub start: (i32, i32),
+    /// Goal position (grid coordinates)
+    pub goal: (i32, i32),
+    /// Reference optimal octile length
+    pub optimal_length: f64,
+}
+
+/// Outcome of running one scenario through a planner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioResult {
+    /// Bucket of the scenario
+    pub bucket: u32,
+    /// Whether the planner returned a path
+    pub found: bool,
+    /// Octile length of the returned path (0 if none was found)
+    pub length: f64,
+    /// Reference optimal length from the scenario file
+    pub optimal_length: f64,
+    /// Number of nodes expanded by the planner
+    pub expansions: u32,
+    /// Time taken by the planner
+    pub time: Duration,
+}
+
+impl ScenarioResult {
+    /// Ratio of the returned length to the optimal length.
+    ///
+    /// # Returns
+    /// `None` if no path was found, otherwise a value of at least 1.0 for a
+    /// correct planner (1.0 means the path is optimal)
+    pub fn suboptimality(&self) -> Option<f64> {
+        if !self.found {
+            return None;
+        }
+        if self.optimal_length <= 0.0 {
+            return Some(1.0);
+        }
+        Some(self.length / self.optimal_length)
+    }
+
+    /// Check if the returned path is shorter than the reference allows.
+    ///
+    /// This indicates a planner that cuts corners or moves through
+    /// obstacles, or a map that does not match the scenario file.
+    pub fn is_shorter_than_optimal(&self) -> bool {
+        self.found && self.length < self.optimal_length * (1.0 - LENGTH_TOLERANCE)
+    }
+}
+
+/// Aggregated results of all scenarios in one bucket.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BucketReport {
+    /// Bucket number
+    pub bucket: u32,
+    /// Number of scenarios in the bucket
+    pub scenarios: usize,
+    /// Number of scenarios the planner found a path for
+    pub solved: usize,
+    /// Number of solved scenarios whose path was optimal
+    pub optimal: usize,
+    /// Mean suboptimality ratio over solved scenarios
+    pub mean_suboptimality: f64,
+    /// Worst suboptimality ratio in the bucket
+    pub max_suboptimality: f64,
+    /// Total nodes expanded over all scenarios
+    pub expansions: u64,
+    /// Total planner time over all scenarios
+    pub time: Duration,
+}
+
+/// Parse the contents of a `.scen` file.
+///
+/// # Returns
+/// The scenarios in file order, or a message naming the malformed line
+pub fn parse_scenarios(contents: &str) -> Result<Vec<Scenario>, String> {
+    let mut lines = contents.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim_start().starts_with("version") => {}
+        _ => return Err(String::from("Line 1: expected 'version' header")),
+    }
+
+    let mut scenarios = Vec::new();
+    for (i, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = i + 1;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 9 {
+            return Err(format!(
+                "Line {}: expected 9 fields, found {}",
+                line_number,
+                fields.len()
+            ));
+        }
+        let number = |idx: usize| -> Result<i32, String> {
+            fields[idx]
+                .parse::<i32>()
+                .map_err(|_| format!("Line {}: invalid number '{}'", line_number, fields[idx]))
+        };
+        let bucket = fields[0]
+            .parse::<u32>()
+            .map_err(|_| format!("Line {}: invalid bucket '{}'", line_number, fields[0]))?;
+        let map_width = fields[2]
+            .parse::<u32>()
+            .map_err(|_| format!("Line {}: invalid width '{}'", line_number, fields[2]))?;
+        let map_height = fields[3]
+            .parse::<u32>()
+            .map_err(|_| format!("Line {}: invalid height '{}'", line_number, fields[3]))?;
+        let optimal_length = fields[8]
+            .parse::<f64>()
+            .map_err(|_| format!("Line {}: invalid length '{}'", line_number, fields[8]))?;
+
+        scenarios.push(Scenario {
+            bucket,
+            map: fields[1].to_string(),
+            map_width,
+            map_height,
+            start: (number(4)?, number(5)?),
+            goal: (number(6)?, number(7)?),
+            optimal_length,
+        });
+    }
+    Ok(scenarios)
+}
+
+/// Load and parse a `.scen` file.
+pub fn load_scenarios(path: &Path) -> Result<Vec<Scenario>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_scenarios(&contents)
+}
+
+/// Parse the contents of a `.map` file into a grid with unit terrain costs.
+///
+/// The grid has no starts or goals.
+pub fn parse_map(contents: &str) -> Result<Grid, String> {
+    parse_map_with_costs(contents, &TerrainCosts::default())
+}
+
+/// Parse the contents of a `.map` file into a grid.
+///
+/// # Arguments
+/// * `contents` - Text of the map file
+/// * `costs` - Weights given to the passable terrain classes
+///
+/// # Returns
+/// The grid without starts or goals, or a message naming the line (and
+/// column, for bad tiles) of the malformed input
+pub fn parse_map_with_costs(contents: &str, costs: &TerrainCosts) -> Result<Grid, String> {
+    let mut width: Option<u32> = None;
+    let mut height: Option<u32> = None;
+    let mut lines = contents.lines().enumerate();
+
+    let header_value = |line_number: usize, line: &str, value: &str| -> Result<u32, String> {
+        match value.parse::<u32>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(format!(
+                "Line {}, column {}: invalid size '{}'",
+                line_number,
+                line.find(value).unwrap_or(0) + 1,
+                value
+            )),
+        }
+    };
+    let mut found_map = false;
+    for (i, line) in lines.by_ref() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["type", _] | [] => {}
+            ["height", value] => height = Some(header_value(i + 1, line, value)?),
+            ["width", value] => width = Some(header_value(i + 1, line, value)?),
+            ["map"] => {
+                found_map = true;
+                break;
+            }
+            _ => return Err(format!("Line {}: unexpected header '{}'", i + 1, line)),
+        }
+    }
+
+    if !found_map {
+        return Err(String::from("Missing 'map' line after the header"));
+    }
+    let (Some(width), Some(height)) = (width, height) else {
+        return Err(String::from("Missing width or height in map header"));
+    };
+    let mut grid = Grid::new(width, height);
+    let mut rows = 0;
+    for (i, line) in lines.by_ref().take(height as usize) {
+        let row = line.trim_end_matches(['\r', '\n']);
+        let mut columns = 0;
+        for (column, symbol) in row.chars().enumerate() {
+            if column as u32 >= width {
+                return Err(format!(
+                    "Line {}, column {}: row is longer than the width {}",
+                    i + 1,
+                    column + 1,
+                    width
+                ));
+            }
+            let Some((tile_type, weight)) = costs.tile_for(symbol) else {
+                return Err(format!(
+                    "Line {}, column {}: unknown tile '{}'",
+                    i + 1,
+                    column + 1,
+                    symbol
+                ));
+            };
+            let idx = rows * width as usize + column;
+            grid.tiles[idx] = Tile::new(
+                get_coordinate_from_idx(idx, width, height),
+                tile_type,
+                weight,
+            );
+            columns += 1;
+        }
+        if columns != width {
+            return Err(format!(
+                "Line {}, column {}: expected {} tiles, found {}",
+                i + 1,
+                columns + 1,
+                width,
+                columns
+            ));
+        }
+        rows += 1;
+    }
+    if rows != height as usize {
+        return Err(format!("Expected {} rows, found {}", height, rows));
+    }
+    if let Some((i, _)) = lines.find(|(_, line)| !line.trim().is_empty()) {
+        return Err(format!(
+            "Line {}: unexpected data after the last row",
+            i + 1
+        ));
+    }
+    Ok(grid)
+}
+
+/// Load and parse a `.map` file with unit terrain costs.
+pub fn load_map(path: &Path) -> Result<Grid, String> {
+    load_map_with_costs(path, &TerrainCosts::default())
+}
+
+/// Load and parse a `.map` file with the given terrain costs.
+pub fn load_map_with_costs(path: &Path, costs: &TerrainCosts) -> Result<Grid, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_map_with_costs(&contents, costs)
+}
+
+/// Run every scenario on `grid` through `algorithm`.
+///
+/// # Arguments
+/// * `grid` - Map the scenarios were generated for
+/// * `scenarios` - Queries to run
+/// * `algorithm` - Planner to evaluate
+/// * `budget` - Limits of every query; a query that runs out counts as unsolved
+///
+/// # Returns
+/// One result per scenario, or an error if a scenario does not fit the map
+pub fn run_scenarios(
+    grid: &Grid,
+    scenarios: &[Scenario],
+    algorithm: &dyn PathfindingAlgorithm,
+    budget: &SearchBudget,
+) -> Result<Vec<ScenarioResult>, String> {
+    let mut results = Vec::with_capacity(scenarios.len());
+    for (i, scenario) in scenarios.iter().enumerate() {
+        if scenario.map_width != grid.width || scenario.map_height != grid.height {
+            return Err(format!(
+                "Scenario {} is for a {}x{} map but the grid is {}x{}",
+                i, scenario.map_width, scenario.map_height, grid.width, grid.height
+            ));
+        }
+        if grid.tile(scenario.start).is_none() || grid.tile(scenario.goal).is_none() {
+            return Err(format!("Scenario {} lies outside the map", i));
+        }
+
+        let mut ctx = SearchContext::budgeted(budget, None);
+        let now = Instant::now();
+        let (mut path, expansions) = algorithm.search(
+            scenario.start,
+            scenario.goal,
+            &grid.tiles,
+            grid.width,
+            grid.height,
+            &mut ctx,
+        );
+        let time = now.elapsed();
+        // Any-angle planners are measured along their segments, not the
+        // staircase of cells the segments are drawn through
+        let waypoints = match algorithm.is_any_angle() {
+            true => path.clone(),
+            false => vec![],
+        };
+        if !path.is_empty() && !algorithm.returns_full_path() {
+            path = algorithm.reconstruct_path(path);
+            path.push(scenario.start);
+        }
+
+        results.push(ScenarioResult {
+            bucket: scenario.bucket,
+            found: !path.is_empty(),
+            length: match waypoints.is_empty() {
+                true => path_length(&path),
+                false => path_length(&waypoints),
+            },
+            optimal_length: scenario.optimal_length,
+            expansions,
+            time,
+        });
+    }
+    Ok(results)
+}
+
+/// Group scenario results by bucket and compute suboptimality statistics.
+///
+/// # Returns
+/// One report per bucket, ordered by bucket number
+pub fn bucket_reports(results: &[ScenarioResult]) -> Vec<BucketReport> {
+    let mut buckets: BTreeMap<u32, BucketReport> = BTreeMap::new();
+    for result in results {
+        let report = buckets
+            .entry(result.bucket)
+            .or_insert_with(|| BucketReport {
+                bucket: result.bucket,
+                ..Default::default()
+            });
+        report.scenarios += 1;
+        report.expansions += result.expansions as u64;
+        report.time += result.time;
+        if let Some(ratio) = result.suboptimality() {
+            report.solved += 1;
+            if ratio <= 1.0 + LENGTH_TOLERANCE {
+                report.optimal += 1;
+            }
+            // Accumulate the sum here, divided below
+            report.mean_suboptimality += ratio;
+            report.max_suboptimality = report.max_suboptimality.max(ratio);
+        }
+    }
+    buckets
+        .into_values()
+        .map(|mut report| {
+            if report.solved > 0 {
+                report.mean_suboptimality /= report.solved as f64;
+            }
+            report
+        })
+        .collect()
+}
+
+/// Write bucket reports to a CSV file.
+pub fn write_bucket_reports(
+    reports: &[BucketReport],
+    algorithm: &str,
+    output_path: &Path,
+) -> Result<(), String> {
+    let mut wtr = csv::Writer::from_path(output_path)
+        .map_err(|e| format!("Failed to create {}: {}", output_path.display(), e))?;
+    wtr.write_record([
+        "algorithm",
+        "bucket",
+        "scenarios",
+        "solved",
+        "optimal",
+        "mean_suboptimality",
+        "max_suboptimality",
+        "expansions",
+        "time_ms",
+    ])
+    .map_err(|e| e.to_string())?;
+    for report in reports {
+        wtr.write_record(&[
+            algorithm.to_string(),
+            report.bucket.to_string(),
+            report.scenarios.to_string(),
+            report.solved.to_string(),
+            report.optimal.to_string(),
+            format!("{:.6}", report.mean_suboptimality),
+            format!("{:.6}", report.max_suboptimality),
+            report.expansions.to_string(),
+            format!("{:.3}", report.time.as_secs_f64() * 1000.0),
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+    wtr.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinding::{get_algorithm, BreadthFirstSearch};
+
+    const MAP: &str = "type octile
+height 3
+width 4
+map
+....
+.@@.
+....
+";
+
+    // ------- parse_scenarios -------
+
+    #[test]
+    fn test_parse_scenarios_reads_rows() {
+        let scen = "version 1\n0\tmaze.map\t4\t3\t0\t0\t3\t0\t3.00000000\n1\tmaze.map\t4\t3\t0\t0\t3\t2\t4.41421356\n";
+        let scenarios = parse_scenarios(scen).unwrap();
+        assert_eq!(scenarios.len(), 2);
+        assert_eq!(scenarios[1].bucket, 1);
+        assert_eq!(scenarios[1].map, "maze.map");
+        assert_eq!(scenarios[1].start, (0, 0));
+        assert_eq!(scenarios[1].goal, (3, 2));
+        assert!((scenarios[1].optimal_length - 4.41421356).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_scenarios_requires_version_header() {
+        assert!(parse_scenarios("0\tm.map\t4\t3\t0\t0\t1\t1\t1.0\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_scenarios_reports_line_of_bad_row() {
+        let err = parse_scenarios("version 1\n0 m.map 4 3 0 0 x 1 1.0\n").unwrap_err();
+        assert!(err.starts_with("Line 2"));
+    }
+
+    // ------- parse_map -------
+
+    #[test]
+    fn test_parse_map_reads_non_square_map() {
+        let grid = parse_map(MAP).unwrap();
+        assert_eq!((grid.width, grid.height), (4, 3));
+        assert!(!grid.tile((1, 1)).unwrap().is_traversable());
+        assert!(grid.tile((3, 1)).unwrap().is_traversable());
+        assert_eq!(grid.tile((3, 2)).unwrap().position, (3, 2));
+    }
+
+    #[test]
+    fn test_parse_map_rejects_short_row() {
+        assert!(parse_map("type octile\nheight 1\nwidth 3\nmap\n..\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_map_reports_line_and_column() {
+        let err = parse_map("type octile\nheight 2\nwidth 3\nmap\n...\n.x.\n").unwrap_err();
+        assert!(err.starts_with("Line 6, column 2"), "{}", err);
+        let err = parse_map("type octile\nheight 1\nwidth 2\nmap\n...\n").unwrap_err();
+        assert!(err.starts_with("Line 5, column 3"), "{}", err);
+        let err = parse_map("type octile\nheight x\nwidth 2\nmap\n..\n").unwrap_err();
+        assert!(err.starts_with("Line 2, column 8"), "{}", err);
+        assert!(parse_map("type octile\nheight 2\nwidth 2\nmap\n..\n").is_err());
+        assert!(parse_map("type octile\nheight 1\nwidth 2\nmap\n..\n..\n").is_err());
+        assert!(parse_map("type octile\nheight 1\nwidth 2\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_map_has_no_size_cap() {
+        let row = ".".repeat(600);
+        let contents = format!(
+            "type octile\nheight 520\nwidth 600\nmap\n{}",
+            format!("{}\n", row).repeat(520)
+        );
+        let grid = parse_map(&contents).unwrap();
+        assert_eq!((grid.width, grid.height), (600, 520));
+        assert_eq!(grid.tile((599, 519)).unwrap().position, (599, 519));
+    }
+
+    #[test]
+    fn test_parse_map_applies_terrain_costs() {
+        let contents = "type octile\r\nheight 1\r\nwidth 5\r\nmap\r\n.GSWT\r\n";
+        let costs = TerrainCosts {
+            ground: 2,
+            swamp: 5,
+            water: 9,
+        };
+        let grid = parse_map_with_costs(contents, &costs).unwrap();
+        let weights: Vec<u8> = grid.tiles.iter().map(|t| t.weight).collect();
+        assert_eq!(&weights[..4], &[2, 2, 5, 9]);
+        assert_eq!(grid.tiles[2].tile_type(), TileType::Floor);
+        assert!(grid.tiles[3].is_water());
+        assert!(!grid.tiles[4].is_traversable());
+        // Unit costs by default
+        let grid = parse_map(contents).unwrap();
+        assert!(grid.tiles.iter().all(|t| t.weight == 1));
+    }
+
+    // ------- run_scenarios -------
+
+    #[test]
+    fn test_run_scenarios_reports_per_bucket() {
+        let grid = parse_map(MAP).unwrap();
+        let scenarios = parse_scenarios(
+            "version 1\n0 m.map 4 3 0 0 3 0 3.0\n0 m.map 4 3 0 0 0 2 2.0\n1 m.map 4 3 0 0 3 2 4.41421356\n",
+        )
+        .unwrap();
+        let results = run_scenarios(
+            &grid,
+            &scenarios,
+            &BreadthFirstSearch::default(),
+            &SearchBudget::default(),
+        )
+        .unwrap();
+        assert!(results
+            .iter()
+            .all(|r| r.found && !r.is_shorter_than_optimal()));
+
+        let reports = bucket_reports(&results);
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].bucket, 0);
+        assert_eq!(reports[0].scenarios, 2);
+        assert_eq!(reports[0].solved, 2);
+        assert!(reports[0].mean_suboptimality >= 1.0 - LENGTH_TOLERANCE);
+    }
+
+    #[test]
+    fn test_run_scenarios_reconstructs_jump_point_paths() {
+        let grid = parse_map(MAP).unwrap();
+        let scenarios = parse_scenarios("version 1\n0 m.map 4 3 0 0 3 2 4.41421356\n").unwrap();
+        let results = run_scenarios(
+            &grid,
+            &scenarios,
+            get_algorithm("JPSW").as_ref(),
+            &SearchBudget::default(),
+        )
+        .unwrap();
+        assert!(results[0].found);
+        assert!(!results[0].is_shorter_than_optimal());
+    }
+
+    #[test]
+    fn test_run_scenarios_measures_any_angle_waypoints() {
+        let grid = parse_map("type octile\nheight 5\nwidth 10\nmap\n..........\n..........\n..........\n..........\n..........\n").unwrap();
+        let scenarios = parse_scenarios("version 1\n0 m.map 10 5 0 0 9 4 9.84885780\n").unwrap();
+        for name in ["Theta*", "Lazy Theta*"] {
+            let results = run_scenarios(
+                &grid,
+                &scenarios,
+                get_algorithm(name).as_ref(),
+                &SearchBudget::default(),
+            )
+            .unwrap();
+            assert!(results[0].found);
+            assert!(
+                (results[0].length - scenarios[0].optimal_length).abs() < LENGTH_TOLERANCE,
+                "{} reported {}",
+                name,
+                results[0].length
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_scenarios_rejects_mismatched_map() {
+        let grid = Grid::new(10, 10);
+        let scenarios = parse_scenarios("version 1\n0 m.map 4 3 0 0 3 0 3.0\n").unwrap();
+        assert!(run_scenarios(
+            &grid,
+            &scenarios,
+            &BreadthFirstSearch::default(),
+            &SearchBudget::default()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unsolved_scenarios_have_no_suboptimality() {
+        let result = ScenarioResult {
+            bucket: 0,
+            found: false,
+            length: 0.0,
+            optimal_length: 5.0,
+            expansions: 10,
+            time: Duration::ZERO,
+        };
+        assert_eq!(result.suboptimality(), None);
+        let reports = bucket_reports(&[result]);
+        assert_eq!(reports[0].solved, 0);
+        assert_eq!(reports[0].mean_suboptimality, 0.0);
+    }
+}
@@ -0,0 +1,962 @@
+//! # Multi-Agent Module
+//!
+//! This module plans several agents together so that they never collide:
+//! - `MultiAgentPlanner`: which planner `plan_agents` runs
+//! - `Reservations`: tiles and moves that are taken at given time steps
+//! - `space_time_search`: A* over (tile, time step) around reservations,
+//!   the low-level search of both planners
+//! - `cooperative_astar`: prioritized planning with a reservation table
+//! - `conflict_based_search`: Conflict-Based Search (CBS)
+//! - `find_conflict`: first collision between time-indexed plans
+//!
+//! ## Plans
+//! A plan holds an agent's tile at every time step, start first; waiting
+//! repeats a tile. Agents stay on their goal once their plan ends. Two
+//! agents collide when they are on the same tile at the same time (vertex
+//! conflict) or swap tiles in the same step (edge conflict).
+//!
+//! ## Metrics
+//! - **Makespan**: time steps until the last agent arrives
+//! - **Sum of costs**: total cost of all plans under the `CostModel`, where
+//!   waiting costs an orthogonal step onto the tile waited on
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cost::{from_units, to_units, CostModel};
+use crate::grid::{get_idx_from_coordinate, Tile};
+use crate::metrics::AllocationCounter;
+use crate::pathfinding::{get_possible_moves, Agent, FailureReason, MovementRule, SearchContext};
+use crate::trace::TraceCell;
+
+/// Constraint tree nodes CBS expands before giving up.
+const CBS_NODE_LIMIT: u32 = 2000;
+
+/// Time-indexed plans of several agents, in agent order.
+pub type Plans = Vec<Vec<(i32, i32)>>;
+
+/// A step from one tile to another.
+type Move = ((i32, i32), (i32, i32));
+
+/// A tile at a time step, the node of the space-time search.
+type SpaceTime = ((i32, i32), u32);
+
+/// Planner used when several agents walk the board at once.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultiAgentPlanner {
+    /// Every agent plans on its own and may walk through the others
+    Independent,
+    /// Agents plan one after another around the plans before them
+    CooperativeAStar,
+    /// Conflict-Based Search: optimal sum of costs
+    #[default]
+    ConflictBasedSearch,
+}
+
+impl MultiAgentPlanner {
+    /// Every planner, in the order the UI lists them.
+    pub const ALL: [MultiAgentPlanner; 3] = [
+        MultiAgentPlanner::Independent,
+        MultiAgentPlanner::CooperativeAStar,
+        MultiAgentPlanner::ConflictBasedSearch,
+    ];
+
+    /// Display name used by the UI.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MultiAgentPlanner::Independent => "Independent",
+            MultiAgentPlanner::CooperativeAStar => "Cooperative A*",
+            MultiAgentPlanner::ConflictBasedSearch => "CBS",
+        }
+    }
+
+    /// Look up a planner by its display name.
+    pub fn from_name(name: &str) -> Option<MultiAgentPlanner> {
+        Self::ALL.iter().copied().find(|p| p.name() == name.trim())
+    }
+}
+
+/// A collision between two time-indexed plans.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Both agents are on `position` at `time`
+    Vertex {
+        agents: (usize, usize),
+        position: (i32, i32),
+        time: u32,
+    },
+    /// The first agent moves `from` -> `to` while the second moves
+    /// `to` -> `from`, in the step ending at `time`
+    Edge {
+        agents: (usize, usize),
+        from: (i32, i32),
+        to: (i32, i32),
+        time: u32,
+    },
+}
+
+/// Tiles and moves an agent may not use at given time steps.
+///
+/// Cooperative A* fills it with the plans of the agents planned before;
+/// CBS with the constraints of one agent.
+#[derive(Clone, Debug, Default)]
+pub struct Reservations {
+    /// Tiles taken at a time step
+    tiles: HashSet<SpaceTime>,
+    /// Moves (from, to) forbidden in the step ending at a time step
+    moves: HashSet<(Move, u32)>,
+    /// Tiles taken for good from a time step on (agents resting at their goal)
+    parked: HashMap<(i32, i32), u32>,
+    /// Last time step each tile is taken at
+    last_taken: HashMap<(i32, i32), u32>,
+    /// Last time step anything changes; after it the reservations are static
+    horizon: u32,
+    /// Tiles other agents use at a time step; allowed, but avoided on ties
+    avoided: HashMap<SpaceTime, u32>,
+    /// Goals other agents rest on from a time step on; avoided on ties
+    avoided_goals: HashMap<(i32, i32), u32>,
+}
+
+impl Reservations {
+    /// Forbid being on `pos` at `time`.
+    pub fn reserve_tile(&mut self, pos: (i32, i32), time: u32) {
+        self.tiles.insert((pos, time));
+        let last = self.last_taken.entry(pos).or_insert(time);
+        *last = (*last).max(time);
+        self.horizon = self.horizon.max(time);
+    }
+
+    /// Forbid moving from `from` to `to` in the step ending at `time`.
+    pub fn reserve_move(&mut self, from: (i32, i32), to: (i32, i32), time: u32) {
+        self.moves.insert(((from, to), time));
+        self.horizon = self.horizon.max(time);
+    }
+
+    /// Reserve everything a plan uses, so later plans neither meet nor swap
+    /// with it, and keep its goal taken once it arrives.
+    pub fn reserve_plan(&mut self, plan: &[(i32, i32)]) {
+        for (time, &pos) in plan.iter().enumerate() {
+            self.reserve_tile(pos, time as u32);
+        }
+        for (time, step) in plan.windows(2).enumerate() {
+            // Walking the same step backwards would swap with this plan
+            self.reserve_move(step[1], step[0], time as u32 + 1);
+        }
+        if let Some(&goal) = plan.last() {
+            let arrival = plan.len() as u32 - 1;
+            self.parked.insert(goal, arrival);
+            self.horizon = self.horizon.max(arrival);
+        }
+    }
+
+    /// Prefer plans that stay clear of `plan` without forbidding anything.
+    pub fn avoid_plan(&mut self, plan: &[(i32, i32)]) {
+        for (time, &pos) in plan.iter().enumerate() {
+            *self.avoided.entry((pos, time as u32)).or_insert(0) += 1;
+        }
+        if let Some(&goal) = plan.last() {
+            let arrival = plan.len() as u32 - 1;
+            self.avoided_goals.insert(goal, arrival);
+            self.horizon = self.horizon.max(arrival);
+        }
+    }
+
+    /// Number of other agents on `pos` at `time`, for tie-breaking.
+    fn collisions(&self, pos: (i32, i32), time: u32) -> u32 {
+        let parked = self
+            .avoided_goals
+            .get(&pos)
+            .is_some_and(|&since| time >= since);
+        self.avoided.get(&(pos, time)).copied().unwrap_or(0) + parked as u32
+    }
+
+    /// Check if moving (or waiting) from `from` to `to`, arriving at `time`,
+    /// is allowed.
+    fn allows(&self, from: (i32, i32), to: (i32, i32), time: u32) -> bool {
+        !self.tiles.contains(&(to, time))
+            && !self.moves.contains(&((from, to), time))
+            && self.parked.get(&to).is_none_or(|&since| time < since)
+    }
+
+    /// Check if an agent can stay on `pos` for good from `time` on.
+    fn free_after(&self, pos: (i32, i32), time: u32) -> bool {
+        self.last_taken.get(&pos).is_none_or(|&last| last < time) && !self.parked.contains_key(&pos)
+    }
+}
+
+/// Outcome and measurements of planning all agents together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MultiAgentResult {
+    /// Name of the planner that produced the result
+    pub planner: String,
+    /// Time-indexed plan of every agent, start first; empty on failure
+    pub plans: Plans,
+    /// Time steps until the last agent arrives
+    pub makespan: u32,
+    /// Total cost of all plans
+    pub sum_of_costs: f64,
+    /// Nodes expanded by the low-level searches
+    pub expansions: u32,
+    /// Constraint tree nodes expanded (CBS only)
+    pub high_level_nodes: u32,
+    /// Time taken by the planner
+    pub time: Duration,
+    /// Memory allocated while planning (bytes)
+    pub memory: u64,
+    /// Why no plans were returned, `None` on success
+    pub failure: Option<FailureReason>,
+}
+
+impl MultiAgentResult {
+    /// Check if every agent got a plan.
+    pub fn is_success(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// The plans as agent paths: goal first, ending at the start.
+    pub fn agent_paths(&self) -> Vec<Vec<(i32, i32)>> {
+        self.plans
+            .iter()
+            .map(|plan| plan.iter().rev().copied().collect())
+            .collect()
+    }
+}
+
+impl fmt::Display for MultiAgentResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            " Planner: {}\n Makespan: {}\n Sum of Costs: {}",
+            self.planner, self.makespan, self.sum_of_costs
+        )
+    }
+}
+
+/// Tile of a plan at a time step; agents stay on their last tile.
+#[inline]
+fn position_at(plan: &[(i32, i32)], time: usize) -> Option<(i32, i32)> {
+    plan.get(time).or(plan.last()).copied()
+}
+
+/// Cost of a plan in the fixed-point units of the cost model; a wait is
+/// priced as a step from a tile onto itself.
+fn plan_units(
+    plan: &[(i32, i32)],
+    cost_model: &CostModel,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> u32 {
+    plan.windows(2)
+        .filter_map(|step| cost_model.step_units(step[0], step[1], map, width, height))
+        .map(|units| units as u32)
+        .sum()
+}
+
+/// Cost of a plan under `cost_model`, waiting included.
+pub fn plan_cost(
+    plan: &[(i32, i32)],
+    cost_model: &CostModel,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> f64 {
+    from_units(plan_units(plan, cost_model, map, width, height) as i64)
+}
+
+/// Find the earliest collision between time-indexed plans.
+///
+/// # Returns
+/// The first conflict in time, or `None` if the plans are collision-free
+pub fn find_conflict(plans: &[Vec<(i32, i32)>]) -> Option<Conflict> {
+    let makespan = plans.iter().map(Vec::len).max().unwrap_or(0);
+    for time in 0..makespan {
+        let mut taken: HashMap<(i32, i32), usize> = HashMap::new();
+        let mut moves: HashMap<Move, usize> = HashMap::new();
+        for (agent, plan) in plans.iter().enumerate() {
+            let Some(position) = position_at(plan, time) else {
+                continue;
+            };
+            if let Some(&other) = taken.get(&position) {
+                return Some(Conflict::Vertex {
+                    agents: (other, agent),
+                    position,
+                    time: time as u32,
+                });
+            }
+            taken.insert(position, agent);
+            if time == 0 {
+                continue;
+            }
+            let Some(from) = position_at(plan, time - 1) else {
+                continue;
+            };
+            if from == position {
+                continue;
+            }
+            if let Some(&other) = moves.get(&(position, from)) {
+                return Some(Conflict::Edge {
+                    agents: (other, agent),
+                    from: position,
+                    to: from,
+                    time: time as u32,
+                });
+            }
+            moves.insert((from, position), agent);
+        }
+    }
+    None
+}
+
+/// Number of agent pairs whose plans collide, used to break ties between
+/// equally cheap CBS nodes.
+fn count_conflicts(plans: &[Vec<(i32, i32)>]) -> u32 {
+    let mut count = 0;
+    for a in 0..plans.len() {
+        for b in a + 1..plans.len() {
+            if find_conflict(&[plans[a].clone(), plans[b].clone()]).is_some() {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// A* over (tile, time step) that waits or detours around reservations.
+///
+/// Moves are priced by `cost_model`; waiting costs an orthogonal step onto
+/// the tile waited on. Among equally cheap plans the one meeting the fewest avoided plans wins.
+/// After the last reserved time step nothing changes any more, so later
+/// time steps are merged into one and the search always terminates.
+///
+/// # Arguments
+/// * `start` - Tile of the agent at time 0
+/// * `goal` - Tile the agent has to reach and stay on
+/// * `cost_model` - Cost of moves
+/// * `movement` - Neighbors the agent may move to
+/// * `map` - Reference to the tile map
+/// * `reservations` - Tiles and moves the agent may not use
+/// * `ctx` - Per-query state (e.g. the optional search trace)
+///
+/// # Returns
+/// The time-indexed plan, start first, or an empty plan if the goal cannot
+/// be reached around the reservations, together with the nodes expanded
+#[allow(clippy::too_many_arguments)]
+pub fn space_time_search(
+    start: (i32, i32),
+    goal: (i32, i32),
+    cost_model: &CostModel,
+    movement: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+    reservations: &Reservations,
+    ctx: &mut SearchContext,
+) -> (Vec<(i32, i32)>, u32) {
+    if get_idx_from_coordinate(start, width, height).is_none()
+        || get_idx_from_coordinate(goal, width, height).is_none()
+    {
+        return (vec![], 0);
+    }
+    // Every step costs at least the cheapest one, so Chebyshev is admissible
+    let min_step = to_units(cost_model.min_step()) as u32;
+    let heuristic =
+        |pos: (i32, i32)| (pos.0 - goal.0).abs().max((pos.1 - goal.1).abs()) as u32 * min_step;
+    let last_change = reservations.horizon + 1;
+    let key = |pos: (i32, i32), time: u32| (pos, time.min(last_change));
+
+    let mut open = BinaryHeap::new();
+    // (cost, collisions with avoided plans) of the best way to each node
+    let mut g_score: HashMap<SpaceTime, (u32, u32)> = HashMap::new();
+    let mut parent: HashMap<SpaceTime, SpaceTime> = HashMap::new();
+    let mut closed: HashSet<SpaceTime> = HashSet::new();
+    g_score.insert(key(start, 0), (0, 0));
+    open.push(Reverse((heuristic(start), 0, 0, start)));
+
+    let mut steps: u32 = 0;
+    while let Some(Reverse((_, _, time, current))) = open.pop() {
+        let current_key = key(current, time);
+        if !closed.insert(current_key) {
+            continue;
+        }
+        steps += 1;
+        ctx.record(current, TraceCell::Expanded);
+        if ctx.out_of_budget(steps) {
+            return (vec![], steps);
+        }
+        if current == goal && reservations.free_after(goal, time) {
+            let mut plan = vec![current];
+            let mut node = current_key;
+            while let Some(&prev) = parent.get(&node) {
+                plan.push(prev.0);
+                node = prev;
+            }
+            plan.reverse();
+            return (plan, steps);
+        }
+
+        let (cost, collisions) = g_score[&current_key];
+        let next_time = time + 1;
+        for next in get_possible_moves(current, map, width, height, movement)
+            .into_iter()
+            .chain([current])
+        {
+            let next_key = key(next, next_time);
+            if closed.contains(&next_key) || !reservations.allows(current, next, next_time) {
+                continue;
+            }
+            let Some(step) = cost_model.step_units(current, next, map, width, height) else {
+                continue;
+            };
+            let next_score = (
+                cost + step as u32,
+                collisions + reservations.collisions(next, next_time),
+            );
+            if g_score
+                .get(&next_key)
+                .is_none_or(|&known| next_score < known)
+            {
+                g_score.insert(next_key, next_score);
+                parent.insert(next_key, current_key);
+                let f_score = next_score.0 + heuristic(next);
+                open.push(Reverse((f_score, next_score.1, next_time, next)));
+                ctx.record(next, TraceCell::Opened);
+            }
+        }
+    }
+    (vec![], steps)
+}
+
+/// Cooperative A*: plan the agents one after another, each around the
+/// plans of the agents before it.
+///
+/// Fast, but not complete: an early agent may take the only way through,
+/// in which case a later agent gets no plan.
+///
+/// # Returns
+/// The plans in agent order, the nodes expanded and why planning failed
+pub fn cooperative_astar(
+    agents: &[Agent],
+    cost_model: &CostModel,
+    movement: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> (Plans, u32, Option<FailureReason>) {
+    let mut reservations = Reservations::default();
+    let mut plans = Vec::with_capacity(agents.len());
+    let mut expansions: u32 = 0;
+    for agent in agents {
+        let (plan, steps) = space_time_search(
+            agent.start,
+            agent.goal,
+            cost_model,
+            movement,
+            map,
+            width,
+            height,
+            &reservations,
+            &mut SearchContext::default(),
+        );
+        expansions += steps;
+        if plan.is_empty() {
+            return (vec![], expansions, Some(FailureReason::Blocked));
+        }
+        reservations.reserve_plan(&plan);
+        plans.push(plan);
+    }
+    (plans, expansions, None)
+}
+
+/// A constraint tree node of CBS.
+struct CbsNode {
+    /// Tiles and moves forbidden to each agent
+    constraints: Vec<Reservations>,
+    /// Plans satisfying the constraints
+    plans: Vec<Vec<(i32, i32)>>,
+}
+
+/// Conflict-Based Search.
+///
+/// Plans every agent on its own, then resolves the earliest conflict by
+/// branching: in each branch one of the two agents is forbidden the tile
+/// (or move) of the conflict and replans. The cheapest branch without
+/// conflicts is an optimal sum-of-costs solution. Among equally cheap nodes
+/// the one with the fewest colliding agent pairs is expanded first.
+///
+/// # Returns
+/// The plans in agent order, the low-level nodes expanded, the constraint
+/// tree nodes expanded and why planning failed (`StepLimit` once
+/// `CBS_NODE_LIMIT` nodes were expanded)
+pub fn conflict_based_search(
+    agents: &[Agent],
+    cost_model: &CostModel,
+    movement: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> (Plans, u32, u32, Option<FailureReason>) {
+    let mut expansions: u32 = 0;
+    // Replan one agent under its constraints, steering clear of the others
+    let replan = |agent: usize,
+                  constraints: &Reservations,
+                  plans: &[Vec<(i32, i32)>],
+                  expansions: &mut u32| {
+        let mut reservations = constraints.clone();
+        for (other, plan) in plans.iter().enumerate() {
+            if other != agent {
+                reservations.avoid_plan(plan);
+            }
+        }
+        let (plan, steps) = space_time_search(
+            agents[agent].start,
+            agents[agent].goal,
+            cost_model,
+            movement,
+            map,
+            width,
+            height,
+            &reservations,
+            &mut SearchContext::default(),
+        );
+        *expansions += steps;
+        plan
+    };
+
+    let constraints = vec![Reservations::default(); agents.len()];
+    let mut plans = Vec::with_capacity(agents.len());
+    for agent in 0..agents.len() {
+        let path = replan(agent, &Reservations::default(), &plans, &mut expansions);
+        if path.is_empty() {
+            return (vec![], expansions, 0, Some(FailureReason::Unreachable));
+        }
+        plans.push(path);
+    }
+    let cost: u32 = plans
+        .iter()
+        .map(|p| plan_units(p, cost_model, map, width, height))
+        .sum();
+
+    let mut nodes = vec![CbsNode { constraints, plans }];
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((cost, count_conflicts(&nodes[0].plans), 0)));
+    let mut expanded: u32 = 0;
+    while let Some(Reverse((_, _, id))) = open.pop() {
+        if expanded >= CBS_NODE_LIMIT {
+            return (vec![], expansions, expanded, Some(FailureReason::StepLimit));
+        }
+        expanded += 1;
+        let Some(conflict) = find_conflict(&nodes[id].plans) else {
+            let node = nodes.swap_remove(id);
+            return (node.plans, expansions, expanded, None);
+        };
+        let branches = match conflict {
+            Conflict::Vertex {
+                agents: (a, b),
+                position,
+                time,
+            } => [(a, None, position, time), (b, None, position, time)],
+            Conflict::Edge {
+                agents: (a, b),
+                from,
+                to,
+                time,
+            } => [(a, Some(from), to, time), (b, Some(to), from, time)],
+        };
+        for (agent, from, to, time) in branches {
+            let mut constraints = nodes[id].constraints.clone();
+            match from {
+                Some(from) => constraints[agent].reserve_move(from, to, time),
+                None => constraints[agent].reserve_tile(to, time),
+            }
+            let path = replan(
+                agent,
+                &constraints[agent],
+                &nodes[id].plans,
+                &mut expansions,
+            );
+            if path.is_empty() {
+                continue;
+            }
+            let mut plans = nodes[id].plans.clone();
+            plans[agent] = path;
+            let cost: u32 = plans
+                .iter()
+                .map(|p| plan_units(p, cost_model, map, width, height))
+                .sum();
+            let conflicts = count_conflicts(&plans);
+            open.push(Reverse((cost, conflicts, nodes.len())));
+            nodes.push(CbsNode { constraints, plans });
+        }
+    }
+    (vec![], expansions, expanded, Some(FailureReason::Blocked))
+}
+
+/// Plan every agent with the chosen multi-agent planner and measure it.
+///
+/// # Arguments
+/// * `planner` - Planner to run; `Independent` plans each agent alone
+/// * `agents` - Agents with their starts and goals
+/// * `cost_model` - Cost of moves
+/// * `movement` - Neighbors the agents may move to
+/// * `map` - Reference to the tile map
+///
+/// # Returns
+/// A `MultiAgentResult` with the plans and their makespan and sum of costs.
+/// Collision-free planners fail with `FailureReason::Blocked` when two
+/// agents share a goal.
+pub fn plan_agents(
+    planner: MultiAgentPlanner,
+    agents: &[Agent],
+    cost_model: &CostModel,
+    movement: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> MultiAgentResult {
+    // Agents rest on their goal, so two agents can never share one
+    let mut goals: Vec<(i32, i32)> = agents.iter().map(|agent| agent.goal).collect();
+    goals.sort();
+    goals.dedup();
+    if planner != MultiAgentPlanner::Independent && goals.len() < agents.len() {
+        return MultiAgentResult {
+            planner: planner.name().to_string(),
+            failure: Some(FailureReason::Blocked),
+            ..Default::default()
+        };
+    }
+
+    let allocation_counter = AllocationCounter::default();
+    let now = Instant::now();
+    let before = allocation_counter.allocated();
+
+    let (plans, expansions, high_level_nodes, failure) = match planner {
+        MultiAgentPlanner::Independent => {
+            let mut expansions: u32 = 0;
+            let mut plans = Vec::with_capacity(agents.len());
+            let mut failure = None;
+            for agent in agents {
+                let (plan, steps) = space_time_search(
+                    agent.start,
+                    agent.goal,
+                    cost_model,
+                    movement,
+                    map,
+                    width,
+                    height,
+                    &Reservations::default(),
+                    &mut SearchContext::default(),
+                );
+                expansions += steps;
+                if plan.is_empty() {
+                    failure = Some(FailureReason::Unreachable);
+                }
+                plans.push(plan);
+            }
+            (plans, expansions, 0, failure)
+        }
+        MultiAgentPlanner::CooperativeAStar => {
+            let (plans, expansions, failure) =
+                cooperative_astar(agents, cost_model, movement, map, width, height);
+            (plans, expansions, 0, failure)
+        }
+        MultiAgentPlanner::ConflictBasedSearch => {
+            conflict_based_search(agents, cost_model, movement, map, width, height)
+        }
+    };
+
+    let memory = allocation_counter.allocated().saturating_sub(before);
+    let mut result = MultiAgentResult {
+        planner: planner.name().to_string(),
+        expansions,
+        high_level_nodes,
+        time: now.elapsed(),
+        memory,
+        failure,
+        ..Default::default()
+    };
+    if failure.is_some() {
+        return result;
+    }
+    result.makespan = plans
+        .iter()
+        .map(|plan| plan.len().saturating_sub(1) as u32)
+        .max()
+        .unwrap_or(0);
+    result.sum_of_costs = plans
+        .iter()
+        .map(|plan| plan_cost(plan, cost_model, map, width, height))
+        .sum();
+    result.plans = plans;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation;
+    use crate::grid::{Grid, TileType};
+
+    fn agent(start: (i32, i32), goal: (i32, i32)) -> Agent {
+        Agent {
+            start,
+            goal,
+            position: start,
+            path: vec![],
+            waypoints: vec![],
+        }
+    }
+
+    /// Helper: 7x3 board that is a corridor apart from one pocket above its middle
+    fn corridor_with_pocket() -> Grid {
+        let mut grid = Grid::new(7, 3);
+        for x in 0..7 {
+            grid.tiles[x].change_tile_type(TileType::Obstacle);
+            grid.tiles[14 + x].change_tile_type(TileType::Obstacle);
+        }
+        grid.tiles[3].change_tile_type(TileType::Floor);
+        grid
+    }
+
+    fn assert_valid_plans(plans: &[Vec<(i32, i32)>], agents: &[Agent], grid: &Grid) {
+        for (plan, agent) in plans.iter().zip(agents) {
+            assert_eq!(plan.first(), Some(&agent.start));
+            assert_eq!(plan.last(), Some(&agent.goal));
+            for step in plan.windows(2) {
+                let moves = get_possible_moves(
+                    step[0],
+                    &grid.tiles,
+                    grid.width,
+                    grid.height,
+                    MovementRule::default(),
+                );
+                assert!(step[0] == step[1] || moves.contains(&step[1]));
+            }
+        }
+        assert_eq!(find_conflict(plans), None);
+    }
+
+    // ------- find_conflict -------
+
+    #[test]
+    fn test_find_conflict_vertex_edge_and_parked() {
+        let vertex = vec![vec![(0, 0), (1, 0)], vec![(2, 0), (1, 0)]];
+        assert_eq!(
+            find_conflict(&vertex),
+            Some(Conflict::Vertex {
+                agents: (0, 1),
+                position: (1, 0),
+                time: 1
+            })
+        );
+        let swap = vec![vec![(0, 0), (1, 0)], vec![(1, 0), (0, 0)]];
+        assert_eq!(
+            find_conflict(&swap),
+            Some(Conflict::Edge {
+                agents: (0, 1),
+                from: (0, 0),
+                to: (1, 0),
+                time: 1
+            })
+        );
+        // The first agent rests on (1, 0) when the second passes
+        let parked = vec![vec![(0, 0), (1, 0)], vec![(3, 0), (2, 0), (1, 0), (0, 1)]];
+        assert!(matches!(
+            find_conflict(&parked),
+            Some(Conflict::Vertex { time: 2, .. })
+        ));
+        let following = vec![vec![(1, 0), (2, 0)], vec![(0, 0), (1, 0)]];
+        assert_eq!(find_conflict(&following), None);
+    }
+
+    // ------- space_time_search -------
+
+    #[test]
+    fn test_space_time_search_waits_for_reserved_tile() {
+        let grid = Grid::new(3, 1);
+        let mut reservations = Reservations::default();
+        reservations.reserve_tile((1, 0), 1);
+        let (plan, _) = space_time_search(
+            (0, 0),
+            (2, 0),
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            3,
+            1,
+            &reservations,
+            &mut SearchContext::default(),
+        );
+        assert_eq!(plan, vec![(0, 0), (0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn test_space_time_search_waits_to_park_on_goal() {
+        // Someone crosses the goal at time 3, so the agent arrives after it
+        let grid = Grid::new(3, 1);
+        let mut reservations = Reservations::default();
+        reservations.reserve_tile((1, 0), 3);
+        let (plan, _) = space_time_search(
+            (0, 0),
+            (1, 0),
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            3,
+            1,
+            &reservations,
+            &mut SearchContext::default(),
+        );
+        assert_eq!(plan.len(), 5);
+        assert_eq!(plan.last(), Some(&(1, 0)));
+    }
+
+    // ------- Planners -------
+
+    #[test]
+    fn test_agents_pass_each_other_using_the_pocket() {
+        let grid = corridor_with_pocket();
+        let agents = [agent((0, 1), (6, 1)), agent((6, 1), (0, 1))];
+        let independent = plan_agents(
+            MultiAgentPlanner::Independent,
+            &agents,
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            7,
+            3,
+        );
+        assert!(find_conflict(&independent.plans).is_some());
+
+        let cbs = plan_agents(
+            MultiAgentPlanner::ConflictBasedSearch,
+            &agents,
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            7,
+            3,
+        );
+        assert!(cbs.is_success());
+        assert_valid_plans(&cbs.plans, &agents, &grid);
+        assert!(cbs.makespan > independent.makespan);
+        assert!(cbs.plans.iter().any(|plan| plan.contains(&(3, 0))));
+
+        // The first agent walks straight through before the second can dodge
+        let cooperative = plan_agents(
+            MultiAgentPlanner::CooperativeAStar,
+            &agents,
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            7,
+            3,
+        );
+        assert_eq!(cooperative.failure, Some(FailureReason::Blocked));
+    }
+
+    #[test]
+    fn test_cbs_is_collision_free_and_not_worse_than_cooperative() {
+        let mut solved = 0;
+        for seed in 0..5 {
+            let mut grid = Grid::new(16, 16);
+            generation::random_grid(&mut grid, 5, 15, 20, false, Some(seed));
+            let agents: Vec<Agent> = (0..5)
+                .map(|i| agent((0, i * 3), (15, 15 - i * 3)))
+                .collect();
+            for agent in &agents {
+                for pos in [agent.start, agent.goal] {
+                    grid.tiles[(pos.1 * 16 + pos.0) as usize] = Tile::new(pos, TileType::Floor, 1);
+                }
+            }
+            let independent = plan_agents(
+                MultiAgentPlanner::Independent,
+                &agents,
+                &CostModel::default(),
+                MovementRule::default(),
+                &grid.tiles,
+                16,
+                16,
+            );
+            if !independent.is_success() {
+                continue;
+            }
+            let cbs = plan_agents(
+                MultiAgentPlanner::ConflictBasedSearch,
+                &agents,
+                &CostModel::default(),
+                MovementRule::default(),
+                &grid.tiles,
+                16,
+                16,
+            );
+            assert!(cbs.is_success(), "seed {}", seed);
+            solved += 1;
+            assert_valid_plans(&cbs.plans, &agents, &grid);
+            assert!(cbs.sum_of_costs >= independent.sum_of_costs);
+            let cooperative = plan_agents(
+                MultiAgentPlanner::CooperativeAStar,
+                &agents,
+                &CostModel::default(),
+                MovementRule::default(),
+                &grid.tiles,
+                16,
+                16,
+            );
+            if cooperative.is_success() {
+                assert_valid_plans(&cooperative.plans, &agents, &grid);
+                assert!(
+                    cbs.sum_of_costs <= cooperative.sum_of_costs,
+                    "seed {}",
+                    seed
+                );
+            }
+        }
+        assert!(solved >= 3);
+    }
+
+    #[test]
+    fn test_plan_agents_metrics_and_shared_goals() {
+        let grid = Grid::new(8, 8);
+        let agents = [agent((0, 0), (3, 0)), agent((0, 2), (0, 7))];
+        let result = plan_agents(
+            MultiAgentPlanner::CooperativeAStar,
+            &agents,
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            8,
+            8,
+        );
+        assert_eq!(result.makespan, 5);
+        assert_eq!(result.sum_of_costs, 3.0 + 5.0);
+        assert_eq!(
+            result.agent_paths()[0],
+            vec![(3, 0), (2, 0), (1, 0), (0, 0)]
+        );
+
+        let shared = [agent((0, 0), (3, 3)), agent((7, 7), (3, 3))];
+        let result = plan_agents(
+            MultiAgentPlanner::ConflictBasedSearch,
+            &shared,
+            &CostModel::default(),
+            MovementRule::default(),
+            &grid.tiles,
+            8,
+            8,
+        );
+        assert_eq!(result.failure, Some(FailureReason::Blocked));
+    }
+
+    #[test]
+    fn test_planner_names_round_trip() {
+        for planner in MultiAgentPlanner::ALL {
+            assert_eq!(MultiAgentPlanner::from_name(planner.name()), Some(planner));
+        }
+        assert_eq!(MultiAgentPlanner::from_name("Unknown"), None);
+    }
+}
@@ -0,0 +1,208 @@
+//! # Image Palette Module
+//!
+//! This module turns the pixels of a level sketch into a grid:
+//! - `PaletteTile`: what a palette color stands for (floor, obstacle, weight,
+//!   start or goal)
+//! - `Palette`: the configurable list of colors, matched to the nearest entry
+//!   so anti-aliased edges still resolve to a sensible tile
+//!
+//! Decoding the image file itself is left to the front-end; this module only
+//! sees RGB pixels in row-major order, so it stays free of SDL.
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::{get_coordinate_from_idx, Grid, Tile, TileType};
+
+/// What a pixel of a given palette color becomes.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum PaletteTile {
+    /// Weight 1 floor
+    Floor,
+    /// Impassable obstacle
+    Obstacle,
+    /// Floor with the given traversal weight
+    Weighted(u8),
+    /// Floor with an agent starting position
+    Start,
+    /// Floor with a goal position
+    Goal,
+}
+
+/// A single color of the palette.
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PaletteEntry {
+    /// RGB color of the pixel
+    pub color: [u8; 3],
+    /// Tile placed for pixels of this color
+    pub tile: PaletteTile,
+}
+
+/// Color-to-tile mapping used when importing images.
+///
+/// Every pixel becomes the tile of the palette entry closest to its color.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Palette {
+    /// Colors recognised in imported images
+    pub entries: Vec<PaletteEntry>,
+}
+
+/// White floor, black obstacles, green starts, red goals and the tints the
+/// board uses for weighted tiles.
+impl Default for Palette {
+    fn default() -> Self {
+        let entry = |color, tile| PaletteEntry { color, tile };
+        Palette {
+            entries: vec![
+                entry([255, 255, 255], PaletteTile::Floor),
+                entry([0, 0, 0], PaletteTile::Obstacle),
+                entry([0, 255, 0], PaletteTile::Start),
+                entry([255, 0, 0], PaletteTile::Goal),
+                entry([255, 198, 191], PaletteTile::Weighted(64)),
+                entry([255, 166, 127], PaletteTile::Weighted(128)),
+                entry([255, 134, 63], PaletteTile::Weighted(192)),
+            ],
+        }
+    }
+}
+
+impl Palette {
+    /// Find the tile for a pixel color.
+    ///
+    /// # Returns
+    /// The tile of the nearest palette color, or `None` for an empty palette
+    pub fn tile_for(&self, color: [u8; 3]) -> Option<PaletteTile> {
+        self.entries
+            .iter()
+            .min_by_key(|entry| color_distance(entry.color, color))
+            .map(|entry| entry.tile)
+    }
+
+    /// Build a grid from row-major RGB pixels, one tile per pixel.
+    ///
+    /// # Arguments
+    /// * `width` - Image width in pixels
+    /// * `height` - Image height in pixels
+    /// * `pixels` - `width * height` RGB pixels in row-major order
+    ///
+    /// # Returns
+    /// The grid with its starts and goals, or an error if the pixel data does
+    /// not match the size or the palette is empty
+    pub fn grid_from_pixels(
+        &self,
+        width: u32,
+        height: u32,
+        pixels: &[[u8; 3]],
+    ) -> Result<Grid, &'static str> {
+        if width == 0 || height == 0 {
+            return Err("Image is empty");
+        }
+        if pixels.len() != (width * height) as usize {
+            return Err("Image size does not match its pixel data");
+        }
+        if self.entries.is_empty() {
+            return Err("Image palette has no colors");
+        }
+        let mut grid = Grid::new(width, height);
+        for (idx, &color) in pixels.iter().enumerate() {
+            let position = get_coordinate_from_idx(idx, width, height);
+            grid.tiles[idx] = match self.tile_for(color) {
+                Some(PaletteTile::Obstacle) => Tile::new(position, TileType::Obstacle, 1),
+                Some(PaletteTile::Weighted(weight)) => Tile::new(position, TileType::Floor, weight),
+                Some(PaletteTile::Start) => {
+                    grid.starts.push(idx);
+                    Tile::new(position, TileType::Player, 1)
+                }
+                Some(PaletteTile::Goal) => {
+                    grid.goals.push(idx);
+                    Tile::new(position, TileType::Enemy, 1)
+                }
+                Some(PaletteTile::Floor) | None => Tile::new(position, TileType::Floor, 1),
+            };
+        }
+        Ok(grid)
+    }
+}
+
+/// Squared euclidean distance between two RGB colors.
+#[inline]
+fn color_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&x, &y)| (x as i32 - y as i32).pow(2) as u32)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+    const GREEN: [u8; 3] = [0, 255, 0];
+    const RED: [u8; 3] = [255, 0, 0];
+
+    // ------- tile_for -------
+
+    #[test]
+    fn test_tile_for_matches_nearest_color() {
+        let palette = Palette::default();
+        assert_eq!(palette.tile_for(WHITE), Some(PaletteTile::Floor));
+        assert_eq!(palette.tile_for([20, 10, 30]), Some(PaletteTile::Obstacle));
+        assert_eq!(palette.tile_for([30, 220, 40]), Some(PaletteTile::Start));
+        assert_eq!(palette.tile_for([230, 20, 10]), Some(PaletteTile::Goal));
+        assert_eq!(
+            palette.tile_for([255, 166, 127]),
+            Some(PaletteTile::Weighted(128))
+        );
+        assert_eq!(Palette { entries: vec![] }.tile_for(WHITE), None);
+    }
+
+    // ------- grid_from_pixels -------
+
+    #[test]
+    fn test_grid_from_pixels_places_tiles_starts_and_goals() {
+        let pixels = [GREEN, WHITE, BLACK, [255, 166, 127], WHITE, RED];
+        let grid = Palette::default().grid_from_pixels(3, 2, &pixels).unwrap();
+        assert_eq!(grid.width, 3);
+        assert_eq!(grid.height, 2);
+        assert_eq!(grid.starts, vec![0]);
+        assert_eq!(grid.goals, vec![5]);
+        assert_eq!(grid.tiles[0].tile_type(), TileType::Player);
+        assert_eq!(grid.tiles[2].tile_type(), TileType::Obstacle);
+        assert_eq!(grid.tiles[3].weight, 128);
+        assert_eq!(grid.tiles[5].tile_type(), TileType::Enemy);
+        assert_eq!(grid.tiles[5].position, (2, 1));
+    }
+
+    #[test]
+    fn test_grid_from_pixels_uses_custom_palette() {
+        let palette = Palette {
+            entries: vec![
+                PaletteEntry {
+                    color: [0, 0, 255],
+                    tile: PaletteTile::Obstacle,
+                },
+                PaletteEntry {
+                    color: [255, 255, 0],
+                    tile: PaletteTile::Weighted(9),
+                },
+            ],
+        };
+        let grid = palette
+            .grid_from_pixels(2, 1, &[[0, 0, 200], [250, 250, 0]])
+            .unwrap();
+        assert!(!grid.tiles[0].is_traversable());
+        assert_eq!(grid.tiles[1].weight, 9);
+        assert!(grid.starts.is_empty());
+    }
+
+    #[test]
+    fn test_grid_from_pixels_rejects_bad_input() {
+        let palette = Palette::default();
+        assert!(palette.grid_from_pixels(2, 2, &[WHITE; 3]).is_err());
+        assert!(palette.grid_from_pixels(0, 0, &[]).is_err());
+        assert!(Palette { entries: vec![] }
+            .grid_from_pixels(1, 1, &[WHITE])
+            .is_err());
+    }
+}
@@ -5,31 +5,41 @@
 //! ## Algorithms
 //! - **Greedy Search**: Fast but not optimal, always moves toward the goal
 //! - **Breadth-First Search (BFS)**: Guarantees shortest path in unweighted graphs
-//! - **A* Search**: Optimal pathfinding with weighted tiles using Manhattan heuristic
+//! - **Dijkstra**: Optimal uniform-cost search, used as the reference for the others
+//! - **A* Search**: Optimal pathfinding with weighted tiles using a configurable heuristic
+//! - **Bidirectional A***: A* run from the start and the goal at once, meeting in the middle
+//! - **Theta* / Lazy Theta***: Any-angle A* variants returning straight segments
+//! - **D* Lite**: Incremental planner that repairs its search after tile changes
 //! - **JPS with Weights (JPSW)**: Jump Point Search adapted for weighted grids
+//! - **HPA***: Hierarchical search over grid clusters (see the `hierarchical` module)
 //!
 //! ## Usage
 //! All algorithms implement the `PathfindingAlgorithm` trait, allowing them to be
 //! used interchangeably through the `get_algorithm()` factory function.
+//! `get_configured_algorithm()` additionally takes a `SearchConfig` choosing the
+//! heuristic and heuristic weight used by the A* and Theta* variants and JPSW,
+//! and the `CostModel` every weighted planner optimises.
 
 use crate::benchmarks::sobel_method;
-use crate::components::board::Tile;
-use crate::util;
-
-// Memory tracking for benchmarking
-#[cfg(not(target_os = "windows"))]
-use jemalloc_ctl::{epoch, thread};
+use crate::cost::{CostModel, COST_SCALE};
+use crate::grid::{get_idx_from_coordinate, Tile, TileType};
+use crate::hierarchical::HpaStar;
+use crate::metrics::AllocationCounter;
+use crate::trace::{SearchTrace, TraceCell};
 
 // Random number generation for Greedy search tie-breaking
 use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
 
 use std::cell::RefCell;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::f32::consts::SQRT_2;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -62,6 +72,75 @@ const OBSTACLE_BLOCK_MASK: [u8; 8] = [
     0b1000_0000, // 7 SE: only itself
 ];
 
+/// Bitmask of the orthogonal DELTAS directions (N, W, E, S).
+const ORTHOGONAL_MASK: u8 = 0b0101_1010;
+
+/// Each diagonal DELTAS direction with the two orthogonal directions beside it.
+const DIAGONAL_SIDES: [(usize, usize, usize); 4] = [
+    (0, 1, 3), // NW: N, W
+    (2, 1, 4), // NE: N, E
+    (5, 3, 6), // SW: W, S
+    (7, 4, 6), // SE: E, S
+];
+
+/// Which neighbors a tile connects to.
+///
+/// A side tile of a diagonal move is free when it could be stepped onto from
+/// the current tile, so obstacles, the map edge and water boundaries all
+/// count as blocked sides.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementRule {
+    /// Only north, south, east and west
+    FourConnected,
+    /// All 8 directions; diagonals may pass blocked side tiles
+    CornerCutting,
+    /// All 8 directions; a diagonal needs both side tiles free
+    #[default]
+    NoCornerCutting,
+    /// All 8 directions; a diagonal needs at least one side tile free
+    OneSideFree,
+}
+
+impl MovementRule {
+    /// Every movement rule, in the order shown in the UI.
+    pub const ALL: [MovementRule; 4] = [
+        MovementRule::NoCornerCutting,
+        MovementRule::OneSideFree,
+        MovementRule::CornerCutting,
+        MovementRule::FourConnected,
+    ];
+
+    /// Display name of the rule.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MovementRule::FourConnected => "4-Connected",
+            MovementRule::CornerCutting => "Corner Cutting",
+            MovementRule::NoCornerCutting => "No Corner Cutting",
+            MovementRule::OneSideFree => "One Side Free",
+        }
+    }
+
+    /// Look up a rule by name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<MovementRule> {
+        MovementRule::ALL
+            .into_iter()
+            .find(|rule| rule.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Whether a diagonal move may pass a blocked side tile.
+    pub fn passes_blocked_sides(&self) -> bool {
+        matches!(
+            self,
+            MovementRule::CornerCutting | MovementRule::OneSideFree
+        )
+    }
+
+    /// Whether diagonal moves are possible at all.
+    pub fn allows_diagonals(&self) -> bool {
+        *self != MovementRule::FourConnected
+    }
+}
+
 /// Stack-allocated collection of valid moves (max 8 neighbors).
 pub struct PossibleMoves {
     moves: [(i32, i32); 8],
@@ -119,32 +198,37 @@ impl IntoIterator for PossibleMoves {
     }
 }
 
-/// Get all valid moves from a position, respecting obstacle collision and corner-cutting rules.
+/// Get all valid moves from a position, respecting obstacle collision and the movement rule.
 ///
 /// This function examines all 8 neighbors and returns only those that are:
-/// 1. Traversable (not obstacles)
-/// 2. Not blocked by corner-cutting rules
+/// 1. Enterable from the current tile (not obstacles, and water only from water)
+/// 2. Allowed by `rule`
 ///
-/// Corner-cutting is prevented by blocking diagonal moves when either adjacent
-/// cardinal direction is blocked by an obstacle.
+/// With `MovementRule::NoCornerCutting` diagonal moves are blocked when either
+/// adjacent cardinal direction is blocked by an obstacle. Terrain that cannot
+/// be entered from the current tile (water from land and vice versa) blocks
+/// corners the same way.
 ///
 /// # Arguments
 /// * `current` - Current position on the grid
 /// * `map` - Reference to the tile map
+/// * `rule` - Connectivity and corner-cutting rule
 ///
 /// # Returns
 /// A stack-allocated `PossibleMoves` containing up to 8 valid neighbor positions
 pub fn get_possible_moves(
     current: (i32, i32),
-    map: &Vec<Tile>,
+    map: &[Tile],
     width: u32,
     height: u32,
+    rule: MovementRule,
 ) -> PossibleMoves {
     // Each bit in `traversable` marks a DELTAS direction with a walkable neighbor.
     // Each bit in `blocked` marks directions that are forbidden due to obstacles
     // or corner-cutting prevention (via OBSTACLE_BLOCK_MASK).
     let mut traversable: u8 = 0;
     let mut blocked: u8 = 0;
+    let from = get_idx_from_coordinate(current, width, height).and_then(|idx| map.get(idx));
 
     // Classify each of the 8 neighbors as traversable or obstacle.
     // Obstacle neighbors propagate blocks to adjacent diagonals via OBSTACLE_BLOCK_MASK
@@ -152,9 +236,13 @@ pub fn get_possible_moves(
     for (i, &(dx, dy)) in DELTAS.iter().enumerate() {
         let neighbor = (current.0 + dx, current.1 + dy);
         if let Some(tile) =
-            util::get_idx_from_coordinate(neighbor, width, height).and_then(|idx| map.get(idx))
+            get_idx_from_coordinate(neighbor, width, height).and_then(|idx| map.get(idx))
         {
-            if tile.is_traversable() {
+            let enterable = match from {
+                Some(from) => tile.can_enter_from(from),
+                None => tile.is_traversable(),
+            };
+            if enterable {
                 traversable |= 1 << i;
             } else {
                 blocked |= OBSTACLE_BLOCK_MASK[i];
@@ -162,9 +250,22 @@ pub fn get_possible_moves(
         }
     }
 
-    // A direction is valid only if the neighbor is traversable AND not blocked
-    // by an adjacent obstacle's corner-cutting mask.
-    let valid = traversable & !blocked;
+    // A direction is valid only if the neighbor is traversable and the rule
+    // allows the move past the side tiles.
+    let valid = match rule {
+        MovementRule::FourConnected => traversable & ORTHOGONAL_MASK,
+        MovementRule::CornerCutting => traversable,
+        MovementRule::NoCornerCutting => traversable & !blocked,
+        MovementRule::OneSideFree => {
+            let mut valid = traversable;
+            for (diagonal, side1, side2) in DIAGONAL_SIDES {
+                if traversable & (1 << side1 | 1 << side2) == 0 {
+                    valid &= !(1 << diagonal);
+                }
+            }
+            valid
+        }
+    };
 
     // Collect valid directions into a fixed-size array (no heap allocation).
     let mut result = PossibleMoves {
@@ -181,34 +282,268 @@ pub fn get_possible_moves(
     result
 }
 
+/// Why a search ended without a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FailureReason {
+    /// No route connects the start and the goal
+    Unreachable,
+    /// The planner gave up after its maximum number of steps (Greedy)
+    StepLimit,
+    /// The search ran longer than it was allowed to
+    Timeout,
+    /// Other agents' plans leave no collision-free path (multi-agent planning)
+    Blocked,
+    /// The search expanded more nodes than its budget allows
+    ExpansionLimit,
+    /// The search was cancelled through its `CancelToken`
+    Cancelled,
+}
+
+impl FailureReason {
+    /// Short description used in the UI and in benchmark output.
+    pub fn description(&self) -> &'static str {
+        match self {
+            FailureReason::Unreachable => "No possible Path",
+            FailureReason::StepLimit => "Path is possible but algorithm couldn't find a solution in a reasonable amount of time",
+            FailureReason::Timeout => "Search timed out",
+            FailureReason::Blocked => "Agents block each other's paths",
+            FailureReason::ExpansionLimit => "Search expanded more nodes than its budget allows",
+            FailureReason::Cancelled => "Search was cancelled",
+        }
+    }
+
+    /// Short machine-readable name used in CSV output.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FailureReason::Unreachable => "unreachable",
+            FailureReason::StepLimit => "step_limit",
+            FailureReason::Timeout => "timeout",
+            FailureReason::Blocked => "blocked",
+            FailureReason::ExpansionLimit => "expansion_limit",
+            FailureReason::Cancelled => "cancelled",
+        }
+    }
+
+    /// Check if the search gave up on a limit rather than proving there is
+    /// no path.
+    pub fn is_exhausted(&self) -> bool {
+        matches!(
+            self,
+            FailureReason::StepLimit | FailureReason::Timeout | FailureReason::ExpansionLimit
+        )
+    }
+}
+
+/// Expansions between two reads of the clock and the cancellation token.
+const BUDGET_CHECK_INTERVAL: u32 = 64;
+
+/// Limits a single query has to stay within; `None` means unlimited.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchBudget {
+    /// Wall-clock time the search may take
+    pub max_time: Option<Duration>,
+    /// Nodes the search may expand
+    pub max_expansions: Option<u32>,
+}
+
+/// Shared flag that stops running searches from another thread.
+///
+/// Clones share the flag, so the UI keeps one clone and hands the others to
+/// the search threads.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Ask every search holding this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+
+    /// Check if the searches were asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// Outcome and measurements of a single `Agent::get_path` query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathResult {
+    /// Name of the algorithm that produced the result
+    pub algorithm: String,
+    /// Full path, goal first; empty when the search failed
+    pub path: Vec<(i32, i32)>,
+    /// Cost of the path under the query's `CostModel`
+    pub cost: f64,
+    /// Euclidean length of the path (of its segments for any-angle planners)
+    pub length: f64,
+    /// Any-angle waypoints, goal first; empty for grid planners
+    pub waypoints: Vec<(i32, i32)>,
+    /// Number of nodes expanded
+    pub expansions: u32,
+    /// Time taken by the search, excluding preprocessing
+    pub time: Duration,
+    /// Time spent building data structures before searching (HPA*)
+    pub preprocessing: Duration,
+    /// Memory allocated during the search (bytes)
+    pub memory: u64,
+    /// WCF (Weighted Complexity Factor) of the searched map
+    pub wcf: f64,
+    /// Why no path was returned, `None` on success
+    pub failure: Option<FailureReason>,
+}
+
+impl PathResult {
+    /// Result for a query that was rejected before any search ran.
+    pub fn failed(algorithm: &str, reason: FailureReason) -> Self {
+        PathResult {
+            algorithm: algorithm.to_string(),
+            failure: Some(reason),
+            ..Default::default()
+        }
+    }
+
+    /// Check if a path was found.
+    pub fn is_success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Per-query state handed to `PathfindingAlgorithm::search`.
+#[derive(Debug, Default)]
+pub struct SearchContext {
+    /// Recorded search events, if tracing was requested
+    pub trace: Option<SearchTrace>,
+    /// Set by the algorithm when it stops for a reason other than
+    /// exhausting the search space
+    pub failure: Option<FailureReason>,
+    /// Time the algorithm spent preprocessing the map, which is reported
+    /// separately from the search time
+    pub preprocessing: Duration,
+    /// Expansions allowed before the search gives up
+    max_expansions: Option<u32>,
+    /// Time after which the search gives up
+    deadline: Option<Instant>,
+    /// Token that stops the search from another thread
+    cancel: Option<CancelToken>,
+}
+
+impl SearchContext {
+    /// Create a context that records a search trace.
+    pub fn traced() -> Self {
+        SearchContext {
+            trace: Some(SearchTrace::default()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a context that enforces `budget` from now on and stops once
+    /// `cancel` is cancelled.
+    pub fn budgeted(budget: &SearchBudget, cancel: Option<CancelToken>) -> Self {
+        SearchContext {
+            max_expansions: budget.max_expansions,
+            deadline: budget.max_time.map(|max_time| Instant::now() + max_time),
+            cancel,
+            ..Default::default()
+        }
+    }
+
+    /// Check the budget and the cancellation token after an expansion.
+    ///
+    /// Every algorithm calls this once per expanded node. The clock and the
+    /// token are only read every `BUDGET_CHECK_INTERVAL` expansions.
+    ///
+    /// # Arguments
+    /// * `expansions` - Nodes expanded so far, including the current one
+    ///
+    /// # Returns
+    /// `true` if the search has to stop; `failure` then says why
+    pub fn out_of_budget(&mut self, expansions: u32) -> bool {
+        if self.max_expansions.is_some_and(|max| expansions > max) {
+            self.fail(FailureReason::ExpansionLimit);
+            return true;
+        }
+        if !expansions.is_multiple_of(BUDGET_CHECK_INTERVAL) {
+            return false;
+        }
+        if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            self.fail(FailureReason::Cancelled);
+            return true;
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.fail(FailureReason::Timeout);
+            return true;
+        }
+        false
+    }
+
+    /// Report why the search is giving up.
+    #[inline]
+    pub fn fail(&mut self, reason: FailureReason) {
+        self.failure = Some(reason);
+    }
+
+    /// Record a trace event (no-op when tracing is disabled).
+    #[inline]
+    pub fn record(&mut self, position: (i32, i32), cell: TraceCell) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.record(position, cell);
+        }
+    }
+}
+
 /// Trait for custom pathfinding algorithms.
 ///
 /// Implementers can create their own A*, Dijkstra, BFS, etc.
 /// All algorithms follow a common interface for easy swapping.
 pub trait PathfindingAlgorithm {
-    /// Find a path from start to goal.
+    /// Find a path from start to goal, reporting progress to `ctx`.
     ///
     /// # Arguments
     /// * `start` - Starting position coordinates
     /// * `goal` - Target position coordinates
     /// * `map` - Reference to the tile map with traversability info
+    /// * `ctx` - Per-query state (e.g. the optional search trace)
     ///
     /// # Returns
     /// A tuple containing:
     /// - Vec of waypoints from start to goal (may be reversed or just jump points)
     /// - Total number of steps/nodes expanded during search
-    fn find_path(
+    fn search(
         &self,
         start: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
+        ctx: &mut SearchContext,
     ) -> (Vec<(i32, i32)>, u32);
 
+    /// Find a path from start to goal without recording a trace.
+    ///
+    /// See `search` for the arguments and return value.
+    fn find_path(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> (Vec<(i32, i32)>, u32) {
+        self.search(start, goal, map, width, height, &mut SearchContext::default())
+    }
+
     /// Returns true if find_path returns the complete path, false if it returns jump points.
     fn returns_full_path(&self) -> bool;
 
+    /// Returns true if the returned points are any-angle waypoints joined by
+    /// straight segments rather than grid steps (Theta*).
+    fn is_any_angle(&self) -> bool {
+        false
+    }
+
     /// Reconstruct the full path from jump points (for algorithms like JPS).
     fn reconstruct_path(&self, path: Vec<(i32, i32)>) -> Vec<(i32, i32)>;
 
@@ -230,31 +565,8 @@ pub struct Agent {
     pub position: (i32, i32),
     /// Computed path from start to goal (reversed for pop access)
     pub path: Vec<(i32, i32)>,
-}
-
-/// Calculate the total weight cost of traversing a path.
-///
-/// # Arguments
-/// * `path` - Vector of positions along the path
-/// * `map` - Reference to the tile map
-///
-/// # Returns
-/// Sum of all tile weights along the path
-fn get_overall_path_weight(
-    path: &Vec<(i32, i32)>,
-    map: &Vec<Tile>,
-    width: u32,
-    height: u32,
-) -> u32 {
-    let mut total_weight: u32 = 0;
-    for moves in path {
-        if let Some(tile) =
-            util::get_idx_from_coordinate(*moves, width, height).and_then(|idx| map.get(idx))
-        {
-            total_weight += tile.weight as u32;
-        }
-    }
-    return total_weight;
+    /// Any-angle waypoints of the computed path, goal first (empty for grid planners)
+    pub waypoints: Vec<(i32, i32)>,
 }
 
 impl Agent {
@@ -265,80 +577,139 @@ impl Agent {
     ///
     /// # Arguments
     /// * `algorithm` - Name of the algorithm to use
+    /// * `config` - Heuristic settings and budget of the query
     /// * `map` - Reference to the tile map
     ///
     /// # Returns
-    /// A tuple containing:
-    /// - Success flag
-    /// - The computed path
-    /// - WCF (Weighted Cost Factor) value
-    /// - Memory allocated during search (bytes)
-    /// - Time taken for pathfinding
-    /// - Number of nodes expanded
-    /// - Total path weight/cost
+    /// A `PathResult` with the full path (goal first) and its measurements.
+    /// When no path is found `failure` says why; a search that simply runs
+    /// out of nodes is reported as `FailureReason::Unreachable`, one that
+    /// runs out of its budget as `Timeout` or `ExpansionLimit`.
     pub fn get_path(
         &mut self,
         algorithm: &str,
-        map: &Vec<Tile>,
+        config: &SearchConfig,
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> PathResult {
+        let ctx = SearchContext::budgeted(&config.budget, None);
+        self.run_search(algorithm, config, ctx, map, width, height)
+    }
+
+    /// Like `get_path`, but the search stops with `FailureReason::Cancelled`
+    /// once `cancel` is cancelled from another thread.
+    pub fn get_path_cancellable(
+        &mut self,
+        algorithm: &str,
+        config: &SearchConfig,
+        cancel: &CancelToken,
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> PathResult {
+        let ctx = SearchContext::budgeted(&config.budget, Some(cancel.clone()));
+        self.run_search(algorithm, config, ctx, map, width, height)
+    }
+
+    /// Run and measure one query with the given search context.
+    fn run_search(
+        &mut self,
+        algorithm: &str,
+        config: &SearchConfig,
+        mut ctx: SearchContext,
+        map: &[Tile],
         width: u32,
         height: u32,
-    ) -> (bool, Vec<(i32, i32)>, f64, u64, Duration, u32, u32) {
+    ) -> PathResult {
         // Memory tracking: jemalloc on Unix, cap allocator on Windows
-        #[cfg(not(target_os = "windows"))]
-        let allocated = thread::allocatedp::mib().unwrap();
-        let algorithm = get_algorithm(algorithm);
+        let allocation_counter = AllocationCounter::default();
+        let algorithm = get_configured_algorithm(algorithm, config);
         let now = Instant::now();
 
         // Snapshot memory before pathfinding
-        #[cfg(not(target_os = "windows"))]
-        epoch::advance().unwrap();
-        #[cfg(not(target_os = "windows"))]
-        let before = allocated.read().unwrap().get() as u64;
-        #[cfg(target_os = "windows")]
-        let before = crate::ALLOC.allocated() as u64;
+        let before = allocation_counter.allocated();
 
         // Run pathfinding
-        let (mut path, steps) = algorithm.find_path(self.start, self.goal, &map, width, height);
+        let (mut path, steps) =
+            algorithm.search(self.start, self.goal, map, width, height, &mut ctx);
 
         // Snapshot memory after pathfinding
-        #[cfg(not(target_os = "windows"))]
-        epoch::advance().unwrap();
-        #[cfg(not(target_os = "windows"))]
-        let after = allocated.read().unwrap().get() as u64;
-        #[cfg(target_os = "windows")]
-        let after = crate::ALLOC.allocated() as u64;
-        let time = now.elapsed();
+        let after = allocation_counter.allocated();
+        let memory = after.saturating_sub(before);
+        let time = now.elapsed().saturating_sub(ctx.preprocessing);
+        let mut result = PathResult {
+            algorithm: algorithm.name().to_string(),
+            expansions: steps,
+            time,
+            preprocessing: ctx.preprocessing,
+            memory,
+            wcf: sobel_method(map, width, height),
+            ..Default::default()
+        };
         // An empty path means no route was found
         if path.is_empty() {
-            return (
-                false,
-                vec![],
-                sobel_method(&map, width, height),
-                after - before,
-                time,
-                steps,
-                0,
-            );
+            result.failure = Some(ctx.failure.unwrap_or(FailureReason::Unreachable));
+            return result;
+        }
+        if algorithm.is_any_angle() {
+            result.waypoints = path.clone();
         }
         if !algorithm.returns_full_path() {
             path = algorithm.reconstruct_path(path);
             path.push(self.start);
         }
-        let weight = get_overall_path_weight(&path, map, width, height);
-
-        return (
-            true,
-            path,
-            sobel_method(&map, width, height),
-            after - before,
-            time,
-            steps,
-            weight,
-        );
+        let model = &config.cost_model;
+        result.cost = match result.waypoints.is_empty() {
+            true => model.path_cost(&path, map, width, height),
+            false => result
+                .waypoints
+                .windows(2)
+                .filter_map(|segment| {
+                    segment_cost(
+                        segment[1],
+                        segment[0],
+                        model,
+                        config.movement,
+                        map,
+                        width,
+                        height,
+                    )
+                })
+                .map(f64::from)
+                .sum(),
+        };
+        result.length = match result.waypoints.is_empty() {
+            true => path_length(&path),
+            false => path_length(&result.waypoints),
+        };
+        result.path = path;
+        result
+    }
+    /// Run the algorithm again with trace recording enabled.
+    ///
+    /// Kept separate from `get_path` so that recording never affects the
+    /// measured time and memory.
+    ///
+    /// # Returns
+    /// The events of the search, in the order the algorithm produced them
+    pub fn trace_search(
+        &self,
+        algorithm: &str,
+        config: &SearchConfig,
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> SearchTrace {
+        let algorithm = get_configured_algorithm(algorithm, config);
+        let mut ctx = SearchContext::traced();
+        algorithm.search(self.start, self.goal, map, width, height, &mut ctx);
+        ctx.trace.unwrap_or_default()
     }
+
     /// Check if the agent has reached its goal.
     pub fn goal_reached(&self) -> bool {
-        return self.position == self.goal;
+        self.position == self.goal
     }
 
     /// Verify if any path exists from start to goal using bidirectional BFS.
@@ -349,10 +720,17 @@ impl Agent {
     ///
     /// # Arguments
     /// * `map` - Reference to the tile map
+    /// * `rule` - Neighbors the agent may move to
     ///
     /// # Returns
     /// `true` if a path exists, `false` otherwise
-    pub fn is_path_possible(&self, map: &Vec<Tile>, width: u32, height: u32) -> bool {
+    pub fn is_path_possible(
+        &self,
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        rule: MovementRule,
+    ) -> bool {
         let start = self.start;
         let goal = self.goal;
         if start == goal {
@@ -377,7 +755,7 @@ impl Agent {
                 if visited_goal.contains(&current) {
                     return true;
                 }
-                for neighbor in get_possible_moves(current, map, width, height) {
+                for neighbor in get_possible_moves(current, map, width, height, rule) {
                     if visited_start.insert(neighbor) {
                         queue_start.push_back(neighbor);
                     }
@@ -389,7 +767,7 @@ impl Agent {
                 if visited_start.contains(&current) {
                     return true;
                 }
-                for neighbor in get_possible_moves(current, map, width, height) {
+                for neighbor in get_possible_moves(current, map, width, height, rule) {
                     if visited_goal.insert(neighbor) {
                         queue_goal.push_back(neighbor);
                     }
@@ -400,32 +778,208 @@ impl Agent {
     }
 }
 
+/// Distance estimate used by the informed planners (A* and JPSW).
+///
+/// `distance` is geometric, in steps of length 1 and √2. The planners use
+/// `cost`, which prices the same shape with the cheapest steps of the
+/// `CostModel`: Octile is then the exact cost over weight 1 tiles (it equals
+/// Chebyshev when diagonals cost the same as straight steps, as in the
+/// uniform model), and Euclidean and Chebyshev stay below it. All three are
+/// admissible under every model. Manhattan overestimates with diagonal moves
+/// and Zero turns the search into Dijkstra's algorithm.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Heuristic {
+    /// |dx| + |dy|
+    Manhattan,
+    /// min(dx, dy) * √2 + (max(dx, dy) - min(dx, dy))
+    #[default]
+    Octile,
+    /// Straight-line distance
+    Euclidean,
+    /// max(|dx|, |dy|)
+    Chebyshev,
+    /// Always 0
+    Zero,
+}
+
+impl Heuristic {
+    /// Every heuristic, in the order shown in the UI.
+    pub const ALL: [Heuristic; 5] = [
+        Heuristic::Manhattan,
+        Heuristic::Octile,
+        Heuristic::Euclidean,
+        Heuristic::Chebyshev,
+        Heuristic::Zero,
+    ];
+
+    /// Display name of the heuristic.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Heuristic::Manhattan => "Manhattan",
+            Heuristic::Octile => "Octile",
+            Heuristic::Euclidean => "Euclidean",
+            Heuristic::Chebyshev => "Chebyshev",
+            Heuristic::Zero => "Zero",
+        }
+    }
+
+    /// Look up a heuristic by name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Heuristic> {
+        Heuristic::ALL
+            .into_iter()
+            .find(|h| h.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Estimated distance between two grid positions.
+    pub fn distance(&self, from: (i32, i32), to: (i32, i32)) -> f32 {
+        let dx = (from.0 - to.0).abs() as f32;
+        let dy = (from.1 - to.1).abs() as f32;
+        match self {
+            Heuristic::Manhattan => dx + dy,
+            Heuristic::Octile => {
+                let diag = dx.min(dy);
+                diag * SQRT_2 + (dx.max(dy) - diag)
+            }
+            Heuristic::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Heuristic::Chebyshev => dx.max(dy),
+            Heuristic::Zero => 0.0,
+        }
+    }
+
+    /// Estimated cost of moving between two grid positions under `model`.
+    ///
+    /// Steps are charged at the model's cost over weight 1 tiles, and a
+    /// diagonal never at more than two orthogonal steps, so every heuristic
+    /// but Manhattan is a lower bound on the true cost.
+    pub fn cost(&self, from: (i32, i32), to: (i32, i32), model: &CostModel) -> f32 {
+        let dx = (from.0 - to.0).abs() as f32;
+        let dy = (from.1 - to.1).abs() as f32;
+        let straight = model.min_orthogonal();
+        let diagonal = model.min_diagonal().min(2.0 * straight);
+        match self {
+            Heuristic::Manhattan => (dx + dy) * straight,
+            Heuristic::Octile => {
+                let diag = dx.min(dy);
+                diag * diagonal + (dx.max(dy) - diag) * straight
+            }
+            Heuristic::Euclidean => (dx * dx + dy * dy).sqrt() * straight.min(diagonal / SQRT_2),
+            Heuristic::Chebyshev => dx.max(dy) * straight.min(diagonal),
+            Heuristic::Zero => 0.0,
+        }
+    }
+}
+
+/// Search parameters of a run.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// Distance estimate to the goal (informed planners)
+    pub heuristic: Heuristic,
+    /// Multiplier applied to the heuristic (1.0 = plain A*, > 1.0 = weighted A*)
+    pub heuristic_weight: f32,
+    /// Cost of moves, optimised by every weighted planner and used for the
+    /// reported path cost
+    #[serde(default)]
+    pub cost_model: CostModel,
+    /// Neighbors every planner may move to
+    #[serde(default)]
+    pub movement: MovementRule,
+    /// Time and expansion limits of every query (all algorithms)
+    #[serde(default)]
+    pub budget: SearchBudget,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        SearchConfig {
+            heuristic: Heuristic::default(),
+            heuristic_weight: 1.0,
+            cost_model: CostModel::default(),
+            movement: MovementRule::default(),
+            budget: SearchBudget::default(),
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Weighted heuristic estimate from `from` to `to`, in the units of the
+    /// cost model.
+    #[inline]
+    pub fn estimate(&self, from: (i32, i32), to: (i32, i32)) -> f32 {
+        self.heuristic.cost(from, to, &self.cost_model) * self.heuristic_weight
+    }
+}
+
+/// Names of every algorithm understood by `get_algorithm`.
+pub const ALGORITHMS: [&str; 10] = [
+    "Greedy",
+    "Breadth First Search",
+    "Dijkstra",
+    "A* search",
+    "Bidirectional A*",
+    "Theta*",
+    "Lazy Theta*",
+    "D* Lite",
+    "JPSW",
+    "HPA*",
+];
+
 /// Factory function to create a pathfinding algorithm by name.
 ///
+/// Planners use the default `SearchConfig` (octile heuristic, weight 1.0,
+/// uniform cost model); see `get_configured_algorithm` to choose another.
+///
 /// # Arguments
-/// * `algorithm` - Name of the algorithm ("A* search", "Breadth First Search", "JPSW", or Greedy by default)
+/// * `algorithm` - Name of the algorithm ("A* search", "Bidirectional A*", "Theta*", "Lazy Theta*", "D* Lite", "Breadth First Search", "Dijkstra", "JPSW", "HPA*", or Greedy by default)
 ///
 /// # Returns
 /// Boxed trait object implementing PathfindingAlgorithm
 pub fn get_algorithm(algorithm: &str) -> Box<dyn PathfindingAlgorithm> {
-    println!("{}", algorithm);
+    get_configured_algorithm(algorithm, &SearchConfig::default())
+}
+
+/// Factory function to create a pathfinding algorithm with search parameters.
+///
+/// # Arguments
+/// * `algorithm` - Name of the algorithm (see `ALGORITHMS`)
+/// * `config` - Heuristic and heuristic weight for the A* and Theta* variants and JPSW, cost model for every weighted planner
+///
+/// # Returns
+/// Boxed trait object implementing PathfindingAlgorithm
+pub fn get_configured_algorithm(
+    algorithm: &str,
+    config: &SearchConfig,
+) -> Box<dyn PathfindingAlgorithm> {
     match algorithm.trim() {
-        "A* search" => {
-            println!("Using A star algorithm");
-            return Box::new(AStarSearch);
-        }
-        "Breadth First Search" => {
-            println!("Using BFS");
-            return Box::new(BreadthFirstSearch);
-        }
-        "JPSW" => {
-            println!("Using JPSW");
-            return Box::new(JPSW::default());
-        }
-        _ => {
-            println!("Using Greedy");
-            return Box::new(GreedySearch);
-        }
+        "A* search" => Box::new(AStarSearch { config: *config }),
+        "Bidirectional A*" => Box::new(BidirectionalAStar { config: *config }),
+        "Theta*" => Box::new(ThetaStar {
+            config: *config,
+            lazy: false,
+        }),
+        "Lazy Theta*" => Box::new(ThetaStar {
+            config: *config,
+            lazy: true,
+        }),
+        "D* Lite" => Box::new(DStarLiteSearch {
+            cost_model: config.cost_model,
+            movement: config.movement,
+        }),
+        "Breadth First Search" => Box::new(BreadthFirstSearch {
+            movement: config.movement,
+        }),
+        "Dijkstra" => Box::new(DijkstraSearch {
+            cost_model: config.cost_model,
+            movement: config.movement,
+        }),
+        "JPSW" => Box::new(JPSW::with_config(*config)),
+        "HPA*" => Box::new(HpaStar {
+            cost_model: config.cost_model,
+            movement: config.movement,
+            ..HpaStar::default()
+        }),
+        _ => Box::new(GreedySearch {
+            movement: config.movement,
+        }),
     }
 }
 
@@ -438,7 +992,11 @@ pub fn get_algorithm(algorithm: &str) -> Box<dyn PathfindingAlgorithm> {
 /// - Uses Manhattan distance heuristic
 /// - Falls back to random valid moves when stuck
 /// - Maintains a blacklist of visited positions to avoid cycles
-pub struct GreedySearch;
+#[derive(Default)]
+pub struct GreedySearch {
+    /// Neighbors the search may move to
+    pub movement: MovementRule,
+}
 
 impl PathfindingAlgorithm for GreedySearch {
     /// Find a path using greedy best-first search.
@@ -446,13 +1004,14 @@ impl PathfindingAlgorithm for GreedySearch {
     /// The algorithm prioritizes moves that reduce the Manhattan distance
     /// to the goal. When no improving move exists, it randomly selects
     /// from remaining valid moves while blacklisting the current position.
-    fn find_path(
+    fn search(
         &self,
         start: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
+        ctx: &mut SearchContext,
     ) -> (Vec<(i32, i32)>, u32) {
         let mut current = start;
         let mut path: Vec<(i32, i32)> = vec![start];
@@ -461,33 +1020,39 @@ impl PathfindingAlgorithm for GreedySearch {
         let max_steps = map.len() as u32 * 2;
 
         fn heuristic(pos: &(i32, i32), goal: &(i32, i32)) -> i32 {
-            return (goal.0 as i32 - pos.0 as i32).abs() + (goal.1 as i32 - pos.1 as i32).abs();
+            (goal.0 - pos.0).abs() + (goal.1 - pos.1).abs()
         }
         loop {
             steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
             if current == goal {
                 path.reverse();
                 return (path, steps);
             }
             if steps >= max_steps {
+                ctx.fail(FailureReason::StepLimit);
                 break;
             }
 
             let mut good_moves: Vec<(i32, i32)> = vec![];
             let mut bad_moves: Vec<(i32, i32)> = vec![];
 
-            let neighbors = get_possible_moves(current, map, width, height);
+            let neighbors = get_possible_moves(current, map, width, height, self.movement);
             for neighbor in neighbors {
                 if black_list.contains(&neighbor) {
                     continue;
                 }
+                ctx.record(neighbor, TraceCell::Opened);
                 if heuristic(&neighbor, &goal) < heuristic(&current, &goal) {
                     good_moves.push(neighbor);
                 } else {
                     bad_moves.push(neighbor);
                 }
             }
-            good_moves.sort_by(|a, b| heuristic(a, &goal).cmp(&heuristic(b, &goal)));
+            good_moves.sort_by_key(|a| heuristic(a, &goal));
             if let Some(chosen_move) = good_moves.first() {
                 current = *chosen_move;
                 path.push(*chosen_move);
@@ -523,19 +1088,24 @@ impl PathfindingAlgorithm for GreedySearch {
 /// - Explores nodes in order of distance from start
 /// - Always finds shortest path (in terms of moves)
 /// - Does not consider tile weights
-pub struct BreadthFirstSearch;
+#[derive(Default)]
+pub struct BreadthFirstSearch {
+    /// Neighbors the search may move to
+    pub movement: MovementRule,
+}
 
 impl PathfindingAlgorithm for BreadthFirstSearch {
     /// Find the shortest path using BFS.
     ///
     /// Explores all neighbors at distance N before any at distance N+1.
-    fn find_path(
+    fn search(
         &self,
         start: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
+        ctx: &mut SearchContext,
     ) -> (Vec<(i32, i32)>, u32) {
         if start == goal {
             return (vec![start], 1);
@@ -547,11 +1117,16 @@ impl PathfindingAlgorithm for BreadthFirstSearch {
 
         queue.push_back(start);
         visited.insert(start);
+        ctx.record(start, TraceCell::Opened);
 
         let mut steps = 0;
 
         while let Some(current) = queue.pop_front() {
             steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
             if current == goal {
                 // reconstruct path
                 let mut path = vec![goal];
@@ -563,12 +1138,13 @@ impl PathfindingAlgorithm for BreadthFirstSearch {
                 return (path, steps);
             }
 
-            let neighbors = get_possible_moves(current, map, width, height);
+            let neighbors = get_possible_moves(current, map, width, height, self.movement);
             for neighbor in neighbors {
                 if !visited.contains(&neighbor) {
                     visited.insert(neighbor);
                     parent.insert(neighbor, current);
                     queue.push_back(neighbor);
+                    ctx.record(neighbor, TraceCell::Opened);
                 }
             }
         }
@@ -595,33 +1171,38 @@ impl PathfindingAlgorithm for BreadthFirstSearch {
 /// estimate to the goal (h-score) to efficiently find optimal paths.
 ///
 /// ## Characteristics
-/// - Uses Manhattan distance heuristic (admissible for 4-directional movement)
-/// - Considers tile weights for accurate cost calculation
-/// - Guaranteed optimal if heuristic is admissible
+/// - Uses the heuristic, weight and cost model from its `SearchConfig`
+/// - Keeps g-scores in the fixed-point units of the cost model
+/// - Guaranteed optimal if heuristic is admissible and the weight is 1.0
 /// - More efficient than Dijkstra due to goal-directed search
-pub struct AStarSearch;
+#[derive(Default)]
+pub struct AStarSearch {
+    /// Heuristic, heuristic weight and cost model
+    pub config: SearchConfig,
+}
 
 impl PathfindingAlgorithm for AStarSearch {
     /// Find the optimal path using A* search.
     ///
     /// Uses a priority queue (min-heap) ordered by f-score = g-score + h-score.
-    fn find_path(
+    fn search(
         &self,
         start: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
+        ctx: &mut SearchContext,
     ) -> (Vec<(i32, i32)>, u32) {
-        #[derive(Clone, Eq, PartialEq)]
+        #[derive(Clone)]
         struct Node {
-            cost: i32,
+            cost: f32,
             position: (i32, i32),
         }
 
         impl Ord for Node {
             fn cmp(&self, other: &Self) -> Ordering {
-                other.cost.cmp(&self.cost)
+                other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
             }
         }
 
@@ -631,10 +1212,17 @@ impl PathfindingAlgorithm for AStarSearch {
             }
         }
 
-        fn heuristic(pos: (i32, i32), goal: (i32, i32)) -> i32 {
-            return (goal.0 as i32 - pos.0 as i32).abs() + (goal.1 as i32 - pos.1 as i32).abs();
+        impl PartialEq for Node {
+            fn eq(&self, other: &Self) -> bool {
+                self.cost == other.cost && self.position == other.position
+            }
         }
 
+        impl Eq for Node {}
+
+        let heuristic =
+            |pos: (i32, i32), goal: (i32, i32)| self.config.estimate(pos, goal) * COST_SCALE;
+
         let mut open_set: BinaryHeap<Node> = BinaryHeap::new();
         let mut g_score: HashMap<(i32, i32), i32> = HashMap::new();
         let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
@@ -652,6 +1240,10 @@ impl PathfindingAlgorithm for AStarSearch {
         }) = open_set.pop()
         {
             steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
             if current == goal {
                 // reconstruct path
                 let mut path = vec![goal];
@@ -663,28 +1255,23 @@ impl PathfindingAlgorithm for AStarSearch {
                 return (path, steps);
             }
 
-            let neighbors = get_possible_moves(current, map, width, height);
+            let neighbors = get_possible_moves(current, map, width, height, self.config.movement);
             for neighbor in neighbors {
-                if let Some(tile) = util::get_idx_from_coordinate(neighbor, width, height)
-                    .and_then(|idx| map.get(idx))
+                if let Some(move_cost) = self
+                    .config
+                    .cost_model
+                    .step_units(current, neighbor, map, width, height)
                 {
-                    if tile.is_traversable() {
-                        let move_cost: i32 = tile.weight as i32
-                            * ((((current.0 - neighbor.0).abs() * (current.1 - neighbor.1).abs())
-                                as f32
-                                * SQRT_2) as u8)
-                                .max(1) as i32;
-
-                        let tentative_g = g_score.get(&current).unwrap_or(&i32::MAX) + move_cost;
-                        if tentative_g < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
-                            parent.insert(neighbor, current);
-                            g_score.insert(neighbor, tentative_g);
-                            let f = tentative_g + heuristic(neighbor, goal);
-                            open_set.push(Node {
-                                cost: f,
-                                position: neighbor,
-                            });
-                        }
+                    let tentative_g = g_score.get(&current).unwrap_or(&i32::MAX) + move_cost;
+                    if tentative_g < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
+                        parent.insert(neighbor, current);
+                        g_score.insert(neighbor, tentative_g);
+                        let f = tentative_g as f32 + heuristic(neighbor, goal);
+                        open_set.push(Node {
+                            cost: f,
+                            position: neighbor,
+                        });
+                        ctx.record(neighbor, TraceCell::Opened);
                     }
                 }
             }
@@ -706,51 +1293,948 @@ impl PathfindingAlgorithm for AStarSearch {
     }
 }
 
-/// Jump Point Search with Weights (JPSW) implementation.
+/// Bidirectional A* implementation.
 ///
-/// An optimization of A* that reduces the number of nodes expanded by
-/// "jumping" over intermediate nodes in open areas.
+/// Runs one A* search forward from the start (towards the goal) and one
+/// backward from the goal (towards the start), always expanding the side
+/// with the smaller open set. Whenever a node has been reached from both
+/// sides the path through it becomes a candidate.
 ///
 /// ## Characteristics
-/// - Caches successor calculations for efficiency
-/// - Adapted for weighted grids (stops at weight changes)
-/// - Significantly faster than A* in large open areas
-/// - Returns jump points that must be expanded to full path
-///
-/// ## Caching
-/// Uses neighborhood hashing to cache:
-/// - Successor calculations for pruned neighbor detection
-/// - Orthogonal jump results for repeated queries
-pub struct JPSW {
-    /// Cache for neighborhood successors: (parent_dir_index, neighborhood_hash) -> successor bitmask
-    /// Bits 0-7 correspond to DELTAS directions
-    successor_cache: RefCell<HashMap<(u8, u64), u8>>,
-    /// Cache for orthogonal jumps: (pos, dir_index, start_weight) -> (end_pos, cost)
-    jump_cache: RefCell<HashMap<(usize, u8, u8), Option<((i32, i32), f32)>>>,
+/// - Uses the heuristic and weight from its `SearchConfig` in both directions
+/// - Optimises the same cost model as A*, so path costs are comparable
+/// - Stops once the best f-score of either open set is no lower than the
+///   best candidate, which keeps the result optimal whenever A* would be
+/// - Expands far fewer nodes than A* when one end would otherwise flood a
+///   large area (long corridors, dead ends near the goal)
+#[derive(Default)]
+pub struct BidirectionalAStar {
+    /// Heuristic, heuristic weight and cost model
+    pub config: SearchConfig,
 }
 
-impl Default for JPSW {
-    fn default() -> Self {
-        JPSW {
-            successor_cache: RefCell::new(HashMap::new()),
-            jump_cache: RefCell::new(HashMap::new()),
+impl PathfindingAlgorithm for BidirectionalAStar {
+    /// Find the optimal path by searching from both ends.
+    ///
+    /// Moves are symmetric, so the backward search walks the same edges in
+    /// reverse and charges each the cost of the forward step towards the
+    /// goal.
+    fn search(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        #[derive(Clone)]
+        struct Node {
+            cost: f32,
+            g: i32,
+            position: (i32, i32),
         }
-    }
-}
 
-/// Map a direction (dx,dy) to a DELTAS index (0-7), or 8 for no parent.
-#[inline]
-fn dir_to_index(dx: i32, dy: i32) -> u8 {
-    match (dx, dy) {
-        (-1, -1) => 0,
-        (0, -1) => 1,
-        (1, -1) => 2,
-        (-1, 0) => 3,
-        (1, 0) => 4,
-        (-1, 1) => 5,
-        (0, 1) => 6,
-        (1, 1) => 7,
-        _ => 8,
+        impl Ord for Node {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other
+                    .cost
+                    .partial_cmp(&self.cost)
+                    .unwrap_or(Ordering::Equal)
+            }
+        }
+
+        impl PartialOrd for Node {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialEq for Node {
+            fn eq(&self, other: &Self) -> bool {
+                self.cost == other.cost && self.position == other.position
+            }
+        }
+
+        impl Eq for Node {}
+
+        /// Open set, costs and parents of one search direction.
+        struct Frontier {
+            open_set: BinaryHeap<Node>,
+            g_score: HashMap<(i32, i32), i32>,
+            parent: HashMap<(i32, i32), (i32, i32)>,
+            /// End the heuristic of this direction estimates the distance to
+            target: (i32, i32),
+        }
+
+        impl Frontier {
+            fn new(origin: (i32, i32), target: (i32, i32), estimate: f32) -> Self {
+                let mut open_set = BinaryHeap::new();
+                open_set.push(Node {
+                    cost: estimate,
+                    g: 0,
+                    position: origin,
+                });
+                Frontier {
+                    open_set,
+                    g_score: HashMap::from([(origin, 0)]),
+                    parent: HashMap::new(),
+                    target,
+                }
+            }
+
+            /// Drop queue entries superseded by a cheaper path.
+            fn discard_stale(&mut self) {
+                while let Some(node) = self.open_set.peek() {
+                    if node.g <= *self.g_score.get(&node.position).unwrap_or(&i32::MAX) {
+                        break;
+                    }
+                    self.open_set.pop();
+                }
+            }
+
+            /// Lowest f-score still open.
+            fn min_cost(&self) -> f32 {
+                self.open_set.peek().map_or(f32::INFINITY, |node| node.cost)
+            }
+        }
+
+        let estimate =
+            |from: (i32, i32), to: (i32, i32)| self.config.estimate(from, to) * COST_SCALE;
+        let mut forward = Frontier::new(start, goal, estimate(start, goal));
+        let mut backward = Frontier::new(goal, start, estimate(goal, start));
+
+        // Cost of the best path found so far and the node where its halves meet
+        let mut best: Option<(i32, (i32, i32))> = None;
+        if start == goal {
+            best = Some((0, start));
+        }
+
+        let mut steps: u32 = 0;
+
+        loop {
+            forward.discard_stale();
+            backward.discard_stale();
+            // A cheaper path would have to pass an open node of each side,
+            // whose f-scores are lower bounds on its cost
+            if let Some((cost, _)) = best {
+                if forward.min_cost() >= cost as f32 || backward.min_cost() >= cost as f32 {
+                    break;
+                }
+            }
+            // An exhausted side has labelled everything it can reach, so any
+            // meeting node would already have been found
+            if forward.open_set.is_empty() || backward.open_set.is_empty() {
+                break;
+            }
+
+            let is_forward = forward.open_set.len() <= backward.open_set.len();
+            let (side, other) = if is_forward {
+                (&mut forward, &backward)
+            } else {
+                (&mut backward, &forward)
+            };
+            let Some(Node {
+                g,
+                position: current,
+                ..
+            }) = side.open_set.pop()
+            else {
+                break;
+            };
+            steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
+
+            for neighbor in get_possible_moves(current, map, width, height, self.config.movement) {
+                // Backward steps are walked from `neighbor` to `current`
+                let (from, to) = if is_forward {
+                    (current, neighbor)
+                } else {
+                    (neighbor, current)
+                };
+                let model = &self.config.cost_model;
+                let Some(move_cost) = model.step_units(from, to, map, width, height) else {
+                    continue;
+                };
+                let tentative_g = g + move_cost;
+                if tentative_g >= *side.g_score.get(&neighbor).unwrap_or(&i32::MAX) {
+                    continue;
+                }
+                side.parent.insert(neighbor, current);
+                side.g_score.insert(neighbor, tentative_g);
+                side.open_set.push(Node {
+                    cost: tentative_g as f32 + estimate(neighbor, side.target),
+                    g: tentative_g,
+                    position: neighbor,
+                });
+                ctx.record(neighbor, TraceCell::Opened);
+
+                if let Some(&other_g) = other.g_score.get(&neighbor) {
+                    let total = tentative_g + other_g;
+                    if best.is_none_or(|(cost, _)| total < cost) {
+                        best = Some((total, neighbor));
+                    }
+                }
+            }
+        }
+
+        let Some((_, meeting)) = best else {
+            return (vec![], steps); // no path found
+        };
+        // Goal-first: the backward half from the goal to the meeting node,
+        // then the forward half back to the start
+        let mut path = vec![meeting];
+        let mut node = meeting;
+        while let Some(&next) = backward.parent.get(&node) {
+            path.push(next);
+            node = next;
+        }
+        path.reverse();
+        node = meeting;
+        while let Some(&prev) = forward.parent.get(&node) {
+            path.push(prev);
+            node = prev;
+        }
+        (path, steps)
+    }
+
+    fn returns_full_path(&self) -> bool {
+        true
+    }
+
+    fn reconstruct_path(&self, _: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        vec![]
+    }
+
+    fn name(&self) -> &str {
+        "Bidirectional A*"
+    }
+}
+
+/// D* Lite queue priority: (min(g, rhs) + h + km, min(g, rhs)).
+type DStarKey = (i32, i32);
+
+/// D* Lite incremental planner.
+///
+/// Searches backwards from the goal and keeps its g and rhs values between
+/// calls, so after tiles change only the part of the search they affect is
+/// repaired instead of planning again from scratch. The agent may move
+/// between replans; the key modifier `km` keeps the queued keys valid
+/// without reordering the queue.
+///
+/// ## Usage
+/// 1. `new` and `compute` once to plan the initial path
+/// 2. While walking: `move_to` the agent's position, `update_tiles` with
+///    the tiles that changed, then `compute` and `path` again
+///
+/// ## Characteristics
+/// - Fixed-point costs of its `CostModel` with the Chebyshev distance times
+///   the cheapest step as heuristic, so paths are optimal
+/// - Relies on moves being symmetric, which `get_possible_moves` guarantees
+pub struct DStarLite {
+    /// Current position of the agent
+    start: (i32, i32),
+    /// Goal the search is rooted at
+    goal: (i32, i32),
+    /// Agent position when `km` was last updated
+    last_start: (i32, i32),
+    /// Sum of the heuristic distances the agent moved
+    km: i32,
+    width: u32,
+    height: u32,
+    /// Cost of moves
+    cost_model: CostModel,
+    /// Neighbors the agent may move to
+    movement: MovementRule,
+    /// Cost-to-goal of the last expansion of each tile
+    g: Vec<i32>,
+    /// One-step lookahead cost-to-goal of each tile
+    rhs: Vec<i32>,
+    /// Inconsistent tiles by key; outdated entries are skipped when popped
+    open: BinaryHeap<Reverse<(DStarKey, (i32, i32))>>,
+}
+
+impl DStarLite {
+    /// Create a planner for an agent at `start` heading to `goal`, moving
+    /// by `movement` and charging moves by `cost_model`.
+    ///
+    /// Nothing is searched until `compute` is called.
+    pub fn new(
+        start: (i32, i32),
+        goal: (i32, i32),
+        cost_model: CostModel,
+        movement: MovementRule,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let tile_amount = (width * height) as usize;
+        let mut planner = DStarLite {
+            start,
+            goal,
+            last_start: start,
+            km: 0,
+            width,
+            height,
+            cost_model,
+            movement,
+            g: vec![i32::MAX; tile_amount],
+            rhs: vec![i32::MAX; tile_amount],
+            open: BinaryHeap::new(),
+        };
+        if let Some(idx) = get_idx_from_coordinate(goal, width, height) {
+            planner.rhs[idx] = 0;
+            planner.open.push(Reverse((planner.key(goal), goal)));
+        }
+        planner
+    }
+
+    /// Admissible distance between two tiles: every move costs at least the
+    /// model's cheapest step.
+    #[inline]
+    fn heuristic(&self, from: (i32, i32), to: (i32, i32)) -> i32 {
+        let steps = (from.0 - to.0).abs().max((from.1 - to.1).abs());
+        steps * crate::cost::to_units(self.cost_model.min_step())
+    }
+
+    #[inline]
+    fn idx(&self, pos: (i32, i32)) -> usize {
+        pos.1 as usize * self.width as usize + pos.0 as usize
+    }
+
+    /// Priority of a tile: (min(g, rhs) + h + km, min(g, rhs)).
+    fn key(&self, pos: (i32, i32)) -> DStarKey {
+        let idx = self.idx(pos);
+        let best = self.g[idx].min(self.rhs[idx]);
+        (
+            best.saturating_add(self.heuristic(self.start, pos) + self.km),
+            best,
+        )
+    }
+
+    /// Moves out of `pos` with their costs; obstacles have none.
+    fn successors(&self, pos: (i32, i32), map: &[Tile]) -> Vec<((i32, i32), i32)> {
+        if map[self.idx(pos)].tile_type() == TileType::Obstacle {
+            return vec![];
+        }
+        get_possible_moves(pos, map, self.width, self.height, self.movement)
+            .into_iter()
+            .filter_map(|next| {
+                let cost = self
+                    .cost_model
+                    .step_units(pos, next, map, self.width, self.height)?;
+                Some((next, cost))
+            })
+            .collect()
+    }
+
+    /// Recompute the rhs value of `pos` and queue it if it is inconsistent.
+    fn update_vertex(&mut self, pos: (i32, i32), map: &[Tile]) {
+        let idx = self.idx(pos);
+        if pos != self.goal {
+            self.rhs[idx] = self
+                .successors(pos, map)
+                .into_iter()
+                .map(|(next, cost)| self.g[self.idx(next)].saturating_add(cost))
+                .min()
+                .unwrap_or(i32::MAX);
+        }
+        if self.g[idx] != self.rhs[idx] {
+            self.open.push(Reverse((self.key(pos), pos)));
+        }
+    }
+
+    /// Expand inconsistent tiles until the agent's cost-to-goal is settled,
+    /// or until the budget in `ctx` runs out (`ctx.failure` is then set).
+    ///
+    /// # Returns
+    /// The number of tiles expanded by this call
+    pub fn compute(&mut self, map: &[Tile], ctx: &mut SearchContext) -> u32 {
+        let mut steps: u32 = 0;
+        let start_idx = self.idx(self.start);
+        while let Some(&Reverse((old_key, pos))) = self.open.peek() {
+            if old_key >= self.key(self.start) && self.rhs[start_idx] == self.g[start_idx] {
+                break;
+            }
+            self.open.pop();
+            let idx = self.idx(pos);
+            if self.g[idx] == self.rhs[idx] {
+                continue; // outdated entry
+            }
+            let new_key = self.key(pos);
+            if old_key < new_key {
+                self.open.push(Reverse((new_key, pos)));
+                continue;
+            }
+            steps += 1;
+            ctx.record(pos, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                break;
+            }
+            if self.g[idx] > self.rhs[idx] {
+                self.g[idx] = self.rhs[idx];
+            } else {
+                self.g[idx] = i32::MAX;
+                self.update_vertex(pos, map);
+            }
+            for (neighbor, _) in self.successors(pos, map) {
+                self.update_vertex(neighbor, map);
+                ctx.record(neighbor, TraceCell::Opened);
+            }
+        }
+        steps
+    }
+
+    /// Move the agent to `pos` before the next replan.
+    pub fn move_to(&mut self, pos: (i32, i32)) {
+        self.km += self.heuristic(self.last_start, pos);
+        self.last_start = pos;
+        self.start = pos;
+    }
+
+    /// Tell the planner that the tiles at `changed` were edited in `map`.
+    ///
+    /// Only the changed tiles and their neighbors are re-evaluated; call
+    /// `compute` afterwards to repair the search.
+    pub fn update_tiles(&mut self, changed: &[(i32, i32)], map: &[Tile]) {
+        for &pos in changed {
+            for (dx, dy) in DELTAS.iter().chain(&[(0, 0)]) {
+                let neighbor = (pos.0 + dx, pos.1 + dy);
+                if get_idx_from_coordinate(neighbor, self.width, self.height).is_some() {
+                    self.update_vertex(neighbor, map);
+                }
+            }
+        }
+    }
+
+    /// Follow the cheapest moves from the agent to the goal.
+    ///
+    /// # Returns
+    /// The path goal first, ending at the agent's position, or an empty path
+    /// if the goal cannot be reached
+    pub fn path(&self, map: &[Tile]) -> Vec<(i32, i32)> {
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while current != self.goal {
+            if self.g[self.idx(current)] == i32::MAX || path.len() > self.g.len() {
+                return vec![];
+            }
+            let next = self
+                .successors(current, map)
+                .into_iter()
+                .min_by_key(|&(next, cost)| self.g[self.idx(next)].saturating_add(cost))
+                .map(|(next, _)| next);
+            match next {
+                Some(next) => {
+                    path.push(next);
+                    current = next;
+                }
+                None => return vec![],
+            }
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// D* Lite planning from scratch.
+///
+/// Runs a fresh `DStarLite` for every query so it can be used and
+/// benchmarked like the other planners. Replanning incrementally needs a
+/// `DStarLite` that is kept between queries (see `replanning::Replanner`).
+#[derive(Default)]
+pub struct DStarLiteSearch {
+    /// Cost of moves
+    pub cost_model: CostModel,
+    /// Neighbors the search may move to
+    pub movement: MovementRule,
+}
+
+impl PathfindingAlgorithm for DStarLiteSearch {
+    fn search(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        if get_idx_from_coordinate(start, width, height).is_none()
+            || get_idx_from_coordinate(goal, width, height).is_none()
+        {
+            return (vec![], 0);
+        }
+        let mut planner =
+            DStarLite::new(start, goal, self.cost_model, self.movement, width, height);
+        let steps = planner.compute(map, ctx);
+        if ctx.failure.is_some() {
+            return (vec![], steps);
+        }
+        (planner.path(map), steps)
+    }
+
+    fn returns_full_path(&self) -> bool {
+        true
+    }
+
+    fn reconstruct_path(&self, path: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        path
+    }
+
+    fn name(&self) -> &str {
+        "D* Lite"
+    }
+}
+
+/// Grid cells crossed by the straight line from `from` to `to`, both included.
+///
+/// Uses Bresenham's algorithm, so consecutive cells are 8-connected and the
+/// result is a valid step sequence whenever every step is a possible move.
+pub fn line_cells(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let dx = (to.0 - from.0).abs();
+    let dy = -(to.1 - from.1).abs();
+    let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let mut error = dx + dy;
+    let mut cell = from;
+    let mut cells = vec![from];
+    while cell != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            cell.0 += step.0;
+        }
+        if doubled <= dx {
+            error += dx;
+            cell.1 += step.1;
+        }
+        cells.push(cell);
+    }
+    cells
+}
+
+/// Cost of moving in a straight line from `from` to `to`.
+///
+/// Priced by `CostModel::segment_cost`, so a segment across weight 1 floor
+/// costs its length in orthogonal steps.
+///
+/// # Returns
+/// `None` if the line is blocked: every step along it must be a possible
+/// move, so obstacles, cut corners and water boundaries break line of sight
+fn segment_cost(
+    from: (i32, i32),
+    to: (i32, i32),
+    model: &CostModel,
+    rule: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> Option<f32> {
+    let cells = line_cells(from, to);
+    for step in cells.windows(2) {
+        if !get_possible_moves(step[0], map, width, height, rule).contains(&step[1]) {
+            return None;
+        }
+    }
+    model.segment_cost(&cells, map, width, height)
+}
+
+/// Check if an agent moving by `rule` can move in a straight line from
+/// `from` to `to`.
+///
+/// See `segment_cost` for the rules.
+pub fn line_of_sight(
+    from: (i32, i32),
+    to: (i32, i32),
+    rule: MovementRule,
+    map: &[Tile],
+    width: u32,
+    height: u32,
+) -> bool {
+    segment_cost(from, to, &CostModel::default(), rule, map, width, height).is_some()
+}
+
+/// Euclidean length of a path through the given points.
+///
+/// For grid paths this is 1 per straight step and √2 per diagonal step; for
+/// any-angle waypoints it is the length of the drawn segments.
+pub fn path_length(path: &[(i32, i32)]) -> f64 {
+    path.windows(2)
+        .map(|step| ((step[0].0 - step[1].0) as f64).hypot((step[0].1 - step[1].1) as f64))
+        .sum()
+}
+
+/// Theta* and Lazy Theta* any-angle pathfinding.
+///
+/// Like A*, but a node may take its parent's parent as its own parent when
+/// the two can see each other, so paths become straight segments between
+/// corners instead of 8-connected zig-zags.
+///
+/// ## Characteristics
+/// - Uses the heuristic and weight from its `SearchConfig` (Euclidean is the
+///   natural choice for straight-line costs)
+/// - Segment costs follow `CostModel::segment_cost`: Euclidean length scaled
+///   by the weights of the crossed tiles
+/// - Returns waypoints that `reconstruct_path` expands into grid cells
+/// - The lazy variant assumes line of sight when a node is generated and
+///   only checks it once the node is expanded, trading a few worse parents
+///   for far fewer line checks
+/// - Not guaranteed optimal, but usually shorter than A*'s grid paths
+#[derive(Default)]
+pub struct ThetaStar {
+    /// Heuristic, heuristic weight and cost model
+    pub config: SearchConfig,
+    /// Defer line-of-sight checks until expansion (Lazy Theta*)
+    pub lazy: bool,
+}
+
+impl PathfindingAlgorithm for ThetaStar {
+    /// Find an any-angle path.
+    ///
+    /// # Returns
+    /// The waypoints goal first (ending at the start) and the number of
+    /// expanded nodes
+    fn search(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        #[derive(Clone)]
+        struct Node {
+            cost: f32,
+            position: (i32, i32),
+        }
+
+        impl Ord for Node {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other
+                    .cost
+                    .partial_cmp(&self.cost)
+                    .unwrap_or(Ordering::Equal)
+            }
+        }
+
+        impl PartialOrd for Node {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl PartialEq for Node {
+            fn eq(&self, other: &Self) -> bool {
+                self.cost == other.cost && self.position == other.position
+            }
+        }
+
+        impl Eq for Node {}
+
+        let model = &self.config.cost_model;
+        let movement = self.config.movement;
+        let heuristic = |pos: (i32, i32)| self.config.estimate(pos, goal);
+        let segment_cost = |from: (i32, i32), to: (i32, i32)| {
+            segment_cost(from, to, model, movement, map, width, height)
+        };
+        let step_cost =
+            |from: (i32, i32), to: (i32, i32)| segment_cost(from, to).unwrap_or(f32::INFINITY);
+
+        let mut open_set: BinaryHeap<Node> = BinaryHeap::new();
+        let mut g_score: HashMap<(i32, i32), f32> = HashMap::new();
+        let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        let mut closed: HashSet<(i32, i32)> = HashSet::new();
+
+        open_set.push(Node {
+            cost: heuristic(start),
+            position: start,
+        });
+        g_score.insert(start, 0.0);
+        parent.insert(start, start);
+
+        let mut steps: u32 = 0;
+
+        while let Some(Node {
+            position: current, ..
+        }) = open_set.pop()
+        {
+            if !closed.insert(current) {
+                continue;
+            }
+            if self.lazy && current != start {
+                // The parent was assumed visible; fall back to the best
+                // expanded neighbor if it is not
+                let assumed = parent[&current];
+                if let Some(cost) = segment_cost(assumed, current) {
+                    g_score.insert(current, g_score[&assumed] + cost);
+                } else if let Some((g, neighbor)) =
+                    get_possible_moves(current, map, width, height, movement)
+                        .into_iter()
+                        .filter(|neighbor| closed.contains(neighbor))
+                        .map(|neighbor| {
+                            (g_score[&neighbor] + step_cost(neighbor, current), neighbor)
+                        })
+                        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal))
+                {
+                    g_score.insert(current, g);
+                    parent.insert(current, neighbor);
+                }
+            }
+            steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
+            if current == goal {
+                let mut path = vec![goal];
+                let mut node = goal;
+                while node != start {
+                    node = parent[&node];
+                    path.push(node);
+                }
+                return (path, steps);
+            }
+
+            let current_g = g_score[&current];
+            let grandparent = parent[&current];
+            for neighbor in get_possible_moves(current, map, width, height, movement) {
+                if closed.contains(&neighbor) {
+                    continue;
+                }
+                // Path 2: straight from the grandparent if it can see the
+                // neighbor (assumed for now when lazy), else path 1 via current
+                let (via, tentative_g) = if self.lazy {
+                    let length = ((neighbor.0 - grandparent.0) as f32)
+                        .hypot((neighbor.1 - grandparent.1) as f32);
+                    (
+                        grandparent,
+                        g_score[&grandparent] + length * model.orthogonal,
+                    )
+                } else {
+                    match segment_cost(grandparent, neighbor) {
+                        Some(cost) => (grandparent, g_score[&grandparent] + cost),
+                        None => (current, current_g + step_cost(current, neighbor)),
+                    }
+                };
+                if tentative_g < *g_score.get(&neighbor).unwrap_or(&f32::INFINITY) {
+                    parent.insert(neighbor, via);
+                    g_score.insert(neighbor, tentative_g);
+                    open_set.push(Node {
+                        cost: tentative_g + heuristic(neighbor),
+                        position: neighbor,
+                    });
+                    ctx.record(neighbor, TraceCell::Opened);
+                }
+            }
+        }
+
+        (vec![], steps) // no path found
+    }
+
+    fn returns_full_path(&self) -> bool {
+        false
+    }
+
+    fn is_any_angle(&self) -> bool {
+        true
+    }
+
+    /// Expand the waypoints into the grid cells along each segment.
+    ///
+    /// Segments are traced from parent to child, the direction their line of
+    /// sight was checked in; Bresenham lines are not symmetric, so the reverse
+    /// line may cross other cells. The start cell is left out, as for JPSW.
+    fn reconstruct_path(&self, path: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        let mut full_path: Vec<(i32, i32)> = Vec::new();
+        for segment in path.windows(2) {
+            let cells = line_cells(segment[1], segment[0]);
+            full_path.extend(cells[1..].iter().rev());
+        }
+        full_path
+    }
+
+    fn name(&self) -> &str {
+        if self.lazy {
+            "Lazy Theta*"
+        } else {
+            "Theta*"
+        }
+    }
+}
+
+/// Dijkstra's algorithm.
+///
+/// Uniform-cost search: expands nodes strictly in order of their cost from
+/// the start, with no heuristic guidance.
+///
+/// ## Characteristics
+/// - Optimises the same cost model as A*, in the same fixed-point units
+/// - Always optimal, which makes it the reference for checking other planners
+/// - Expands every node cheaper than the goal, so it is slower than A*
+#[derive(Default)]
+pub struct DijkstraSearch {
+    /// Cost of moves
+    pub cost_model: CostModel,
+    /// Neighbors the search may move to
+    pub movement: MovementRule,
+}
+
+impl PathfindingAlgorithm for DijkstraSearch {
+    /// Find the cheapest path using Dijkstra's algorithm.
+    ///
+    /// Uses a priority queue (min-heap) ordered by the cost from the start.
+    fn search(
+        &self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+        ctx: &mut SearchContext,
+    ) -> (Vec<(i32, i32)>, u32) {
+        #[derive(Clone, Eq, PartialEq)]
+        struct Node {
+            cost: i32,
+            position: (i32, i32),
+        }
+
+        impl Ord for Node {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other.cost.cmp(&self.cost)
+            }
+        }
+
+        impl PartialOrd for Node {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        let mut open_set: BinaryHeap<Node> = BinaryHeap::new();
+        let mut distance: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+
+        open_set.push(Node {
+            cost: 0,
+            position: start,
+        });
+        distance.insert(start, 0);
+
+        let mut steps: u32 = 0;
+
+        while let Some(Node {
+            cost,
+            position: current,
+        }) = open_set.pop()
+        {
+            // Skip stale queue entries for nodes already settled cheaper
+            if cost > *distance.get(&current).unwrap_or(&i32::MAX) {
+                continue;
+            }
+            steps += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(steps) {
+                return (vec![], steps);
+            }
+            if current == goal {
+                let mut path = vec![goal];
+                let mut node = goal;
+                while let Some(&prev) = parent.get(&node) {
+                    path.push(prev);
+                    node = prev;
+                }
+                return (path, steps);
+            }
+
+            for neighbor in get_possible_moves(current, map, width, height, self.movement) {
+                if let Some(move_cost) = self
+                    .cost_model
+                    .step_units(current, neighbor, map, width, height)
+                {
+                    let tentative = cost + move_cost;
+                    if tentative < *distance.get(&neighbor).unwrap_or(&i32::MAX) {
+                        parent.insert(neighbor, current);
+                        distance.insert(neighbor, tentative);
+                        open_set.push(Node {
+                            cost: tentative,
+                            position: neighbor,
+                        });
+                        ctx.record(neighbor, TraceCell::Opened);
+                    }
+                }
+            }
+        }
+
+        (vec![], steps) // no path found
+    }
+
+    fn returns_full_path(&self) -> bool {
+        true
+    }
+
+    fn reconstruct_path(&self, _: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
+        vec![]
+    }
+
+    fn name(&self) -> &str {
+        "Dijkstra"
+    }
+}
+
+/// End of a jump and the cost of getting there, `None` if the jump ran into
+/// an obstacle or the edge of the map.
+type Jump = Option<((i32, i32), f32)>;
+
+/// Jump Point Search with Weights (JPSW) implementation.
+///
+/// An optimization of A* that reduces the number of nodes expanded by
+/// "jumping" over intermediate nodes in open areas.
+///
+/// ## Characteristics
+/// - Caches successor calculations for efficiency
+/// - Adapted for weighted grids (stops beside weight and obstacle changes,
+///   so paths cost the same as Dijkstra's under every cost model)
+/// - Honours every `MovementRule`; when diagonals may pass blocked sides,
+///   jumps also stop next to obstacles
+/// - Significantly faster than A* in large open areas
+/// - Returns jump points that must be expanded to full path
+///
+/// ## Caching
+/// Uses neighborhood hashing to cache:
+/// - Successor calculations for pruned neighbor detection
+/// - Orthogonal jump results for repeated queries
+pub struct JPSW {
+    /// Cache for neighborhood successors: (parent_dir_index, neighborhood_hash) -> successor bitmask
+    /// Bits 0-7 correspond to DELTAS directions
+    successor_cache: RefCell<HashMap<(u8, u64), u8>>,
+    /// Cache for orthogonal jumps: (pos, dir_index, start_weight) -> (end_pos, cost)
+    jump_cache: RefCell<HashMap<(usize, u8, u8), Jump>>,
+    /// Heuristic, heuristic weight and cost model
+    pub config: SearchConfig,
+}
+
+impl Default for JPSW {
+    fn default() -> Self {
+        JPSW::with_config(SearchConfig::default())
+    }
+}
+
+/// Map a direction (dx,dy) to a DELTAS index (0-7), or 8 for no parent.
+#[inline]
+fn dir_to_index(dx: i32, dy: i32) -> u8 {
+    match (dx, dy) {
+        (-1, -1) => 0,
+        (0, -1) => 1,
+        (1, -1) => 2,
+        (-1, 0) => 3,
+        (1, 0) => 4,
+        (-1, 1) => 5,
+        (0, 1) => 6,
+        (1, 1) => 7,
+        _ => 8,
     }
 }
 
@@ -761,15 +2245,24 @@ fn index_to_dir(idx: u8) -> (i32, i32) {
 }
 
 impl JPSW {
+    /// Create a JPSW planner with empty caches and the given search parameters.
+    pub fn with_config(config: SearchConfig) -> Self {
+        JPSW {
+            successor_cache: RefCell::new(HashMap::new()),
+            jump_cache: RefCell::new(HashMap::new()),
+            config,
+        }
+    }
+
     /// Compact hash of the 3x3 neighborhood weights + traversability.
     /// Encodes both weight and traversability into a single u64.
     #[inline]
-    fn hash_neighborhood(center: (i32, i32), map: &Vec<Tile>, width: u32, height: u32) -> u64 {
+    fn hash_neighborhood(center: (i32, i32), map: &[Tile], width: u32, height: u32) -> u64 {
         let mut hash = 0u64;
         // Fixed iteration order for deterministic hashing
         for dy in -1..=1i32 {
             for dx in -1..=1i32 {
-                let val = match util::get_idx_from_coordinate(
+                let val = match get_idx_from_coordinate(
                     (center.0 + dx, center.1 + dy),
                     width,
                     height,
@@ -790,7 +2283,7 @@ impl JPSW {
         &self,
         parent: Option<(i32, i32)>,
         current: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
     ) -> u8 {
@@ -816,7 +2309,7 @@ impl JPSW {
         &self,
         parent: Option<(i32, i32)>,
         current: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
     ) -> u8 {
@@ -835,7 +2328,7 @@ impl JPSW {
         if let Some(p) = parent {
             let pdx = p.0 - current.0;
             let pdy = p.1 - current.1;
-            if pdx >= -1 && pdx <= 1 && pdy >= -1 && pdy <= 1 {
+            if (-1..=1).contains(&pdx) && (-1..=1).contains(&pdy) {
                 let pidx = ((pdy + 1) * 3 + (pdx + 1)) as usize;
                 best_cost[pidx] = 0.0;
                 via_center[pidx] = false;
@@ -870,7 +2363,7 @@ impl JPSW {
                     }
                     let nx = sdx + ndx;
                     let ny = sdy + ndy;
-                    if nx < -1 || nx > 1 || ny < -1 || ny > 1 {
+                    if !(-1..=1).contains(&nx) || !(-1..=1).contains(&ny) {
                         continue;
                     }
                     let nidx = ((ny + 1) * 3 + (nx + 1)) as usize;
@@ -879,7 +2372,7 @@ impl JPSW {
                     }
 
                     let npos = (current.0 + nx, current.1 + ny);
-                    let mc = Self::move_cost_static(npos, spos, map, width, height);
+                    let mc = self.move_cost(npos, spos, map, width, height);
                     if mc.is_infinite() {
                         continue;
                     }
@@ -914,10 +2407,10 @@ impl JPSW {
         start: (i32, i32),
         dir: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
-    ) -> Option<((i32, i32), f32)> {
+    ) -> Jump {
         if dir.0 != 0 && dir.1 != 0 {
             self.jump_diagonal(start, dir, goal, map, width, height)
         } else {
@@ -931,14 +2424,11 @@ impl JPSW {
         start: (i32, i32),
         dir: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
-    ) -> Option<((i32, i32), f32)> {
-        let start_idx = match util::get_idx_from_coordinate(start, width, height) {
-            Some(idx) => idx,
-            None => return None,
-        };
+    ) -> Jump {
+        let start_idx = get_idx_from_coordinate(start, width, height)?;
         let start_weight = map.get(start_idx).map(|t| t.weight).unwrap_or(1);
         let dir_idx = dir_to_index(dir.0, dir.1);
         let cache_key = (start_idx, dir_idx, start_weight);
@@ -947,7 +2437,7 @@ impl JPSW {
             return cached;
         }
 
-        let result = self.jump_orthogonal_inner(start, dir, goal, map, start_weight, width, height);
+        let result = self.jump_orthogonal_inner(start, dir, goal, map, width, height);
         self.jump_cache.borrow_mut().insert(cache_key, result);
         result
     }
@@ -957,11 +2447,11 @@ impl JPSW {
         start: (i32, i32),
         dir: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
-        start_weight: u8,
+        map: &[Tile],
         width: u32,
         height: u32,
-    ) -> Option<((i32, i32), f32)> {
+    ) -> Jump {
+        let start_weight = map[get_idx_from_coordinate(start, width, height)?].weight;
         let mut pos = start;
         let mut cost = 0.0f32;
         let perp1 = (-dir.1, dir.0);
@@ -970,8 +2460,8 @@ impl JPSW {
         // Effective weight: tile weight if traversable, sentinel otherwise.
         // Used to detect any neighborhood change (obstacles, weight regions).
         #[inline]
-        fn eff_weight(p: (i32, i32), map: &Vec<Tile>, w: u32, h: u32) -> u16 {
-            util::get_idx_from_coordinate(p, w, h)
+        fn eff_weight(p: (i32, i32), map: &[Tile], w: u32, h: u32) -> u16 {
+            get_idx_from_coordinate(p, w, h)
                 .and_then(|idx| map.get(idx))
                 .map(|t| {
                     if t.is_traversable() {
@@ -985,13 +2475,9 @@ impl JPSW {
 
         loop {
             let next = (pos.0 + dir.0, pos.1 + dir.1);
-            match util::get_idx_from_coordinate(next, width, height).and_then(|idx| map.get(idx)) {
+            match get_idx_from_coordinate(next, width, height).and_then(|idx| map.get(idx)) {
                 Some(tile) if tile.is_traversable() => {
-                    let w_from = util::get_idx_from_coordinate(pos, width, height)
-                        .and_then(|idx| map.get(idx))
-                        .map(|t| t.weight as f32)
-                        .unwrap_or(f32::INFINITY);
-                    cost += (w_from + tile.weight as f32) * 0.5;
+                    cost += self.move_cost(next, pos, map, width, height);
                     pos = next;
 
                     if pos == goal {
@@ -1001,20 +2487,45 @@ impl JPSW {
                         return Some((pos, cost));
                     }
 
+                    // Without diagonals the path can only turn at a jump point, so
+                    // horizontal jumps stop where a vertical jump finds one
+                    if !self.config.movement.allows_diagonals()
+                        && dir.1 == 0
+                        && (self
+                            .jump_orthogonal(pos, (0, 1), goal, map, width, height)
+                            .is_some()
+                            || self
+                                .jump_orthogonal(pos, (0, -1), goal, map, width, height)
+                                .is_some())
+                    {
+                        return Some((pos, cost));
+                    }
+
                     // Detect perpendicular neighborhood changes (forced neighbors
                     // from obstacles, or weight region boundaries adjacent to the
-                    // scan line). Compare each perpendicular neighbor's effective
-                    // weight at the current position vs the previous position.
+                    // scan line). The neighborhood of `pos` spans the tiles behind
+                    // and ahead of it, so a change on either side makes it a jump
+                    // point; stopping only after a boundary misses paths that turn
+                    // one tile before it.
                     let prev = (pos.0 - dir.0, pos.1 - dir.1);
+                    let ahead = (pos.0 + dir.0, pos.1 + dir.1);
                     for &perp in &[perp1, perp2] {
-                        let curr_side =
-                            eff_weight((pos.0 + perp.0, pos.1 + perp.1), map, width, height);
-                        let prev_side =
-                            eff_weight((prev.0 + perp.0, prev.1 + perp.1), map, width, height);
-                        if curr_side != prev_side {
+                        let side = |p: (i32, i32)| {
+                            eff_weight((p.0 + perp.0, p.1 + perp.1), map, width, height)
+                        };
+                        let curr_side = side(pos);
+                        if side(prev) != curr_side || side(ahead) != curr_side {
                             return Some((pos, cost));
                         }
                     }
+
+                    // Diagonals that pass blocked sides can go around the end of
+                    // the scan line, so the last tile before a wall is a jump point
+                    if self.config.movement.passes_blocked_sides()
+                        && eff_weight(ahead, map, width, height) == 0xFFFF
+                    {
+                        return Some((pos, cost));
+                    }
                 }
                 _ => return None,
             }
@@ -1027,11 +2538,11 @@ impl JPSW {
         start: (i32, i32),
         dir: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
-    ) -> Option<((i32, i32), f32)> {
-        let start_weight = util::get_idx_from_coordinate(start, width, height)
+    ) -> Jump {
+        let start_weight = get_idx_from_coordinate(start, width, height)
             .and_then(|idx| map.get(idx))
             .map(|t| t.weight)
             .unwrap_or(1);
@@ -1041,26 +2552,18 @@ impl JPSW {
         loop {
             let next = (pos.0 + dir.0, pos.1 + dir.1);
 
-            // Check diagonal is traversable, and both sides for corner-cutting
-            let tile = match util::get_idx_from_coordinate(next, width, height)
+            // Check diagonal is traversable, and the sides under the movement rule
+            let tile = match get_idx_from_coordinate(next, width, height)
                 .and_then(|idx| map.get(idx))
             {
                 Some(t) if t.is_traversable() => t,
                 _ => return None,
             };
-            let side1 = (pos.0 + dir.0, pos.1);
-            let side2 = (pos.0, pos.1 + dir.1);
-            if !util::get_idx_from_coordinate(side1, width, height)
-                .and_then(|idx| map.get(idx))
-                .map_or(false, |t| t.is_traversable())
-                || !util::get_idx_from_coordinate(side2, width, height)
-                    .and_then(|idx| map.get(idx))
-                    .map_or(false, |t| t.is_traversable())
-            {
+            if !get_possible_moves(pos, map, width, height, self.config.movement).contains(&next) {
                 return None;
             }
 
-            cost += Self::move_cost_static(next, pos, map, width, height);
+            cost += self.move_cost(next, pos, map, width, height);
             pos = next;
 
             if pos == goal {
@@ -1072,6 +2575,18 @@ impl JPSW {
                 return Some((pos, cost));
             }
 
+            // Diagonals that pass blocked sides turn around every obstacle
+            // next to the scan line, so any blocked side makes a jump point
+            if self.config.movement.passes_blocked_sides()
+                && [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|&(dx, dy)| {
+                    !get_idx_from_coordinate((pos.0 + dx, pos.1 + dy), width, height)
+                        .and_then(|idx| map.get(idx))
+                        .is_some_and(|tile| tile.is_traversable())
+                })
+            {
+                return Some((pos, cost));
+            }
+
             // Check if orthogonal jumps from here find something —
             // this is cheaper than full get_successors
             let ortho_h = (dir.0, 0);
@@ -1088,45 +2603,36 @@ impl JPSW {
         }
     }
 
-    /// Static move cost computation (no &self needed).
+    /// Cost of the move from `from` to `to` under the configured cost model,
+    /// infinite if either tile is not traversable or the movement rule does
+    /// not allow the diagonal.
     #[inline]
-    fn move_cost_static(
+    fn move_cost(
+        &self,
         to: (i32, i32),
         from: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
     ) -> f32 {
-        let w_from =
-            match util::get_idx_from_coordinate(from, width, height).and_then(|idx| map.get(idx)) {
-                Some(t) if t.is_traversable() => t.weight as f32,
-                _ => return f32::INFINITY,
-            };
-        let w_to =
-            match util::get_idx_from_coordinate(to, width, height).and_then(|idx| map.get(idx)) {
-                Some(t) if t.is_traversable() => t.weight as f32,
-                _ => return f32::INFINITY,
-            };
-
-        let dx = (to.0 - from.0).abs();
-        let dy = (to.1 - from.1).abs();
-
-        if dx + dy == 1 {
-            (w_from + w_to) * 0.5
-        } else {
-            // Diagonal: also include the two side cells
-            let s1 = (from.0, to.1);
-            let s2 = (to.0, from.1);
-            let w_s1 = util::get_idx_from_coordinate(s1, width, height)
+        let traversable = |pos| {
+            get_idx_from_coordinate(pos, width, height)
                 .and_then(|idx| map.get(idx))
-                .map(|t| t.weight as f32)
-                .unwrap_or(w_from);
-            let w_s2 = util::get_idx_from_coordinate(s2, width, height)
-                .and_then(|idx| map.get(idx))
-                .map(|t| t.weight as f32)
-                .unwrap_or(w_from);
-            ((w_from + w_to + w_s1 + w_s2) * 0.25) * std::f32::consts::SQRT_2
+                .is_some_and(|tile: &Tile| tile.is_traversable())
+        };
+        if !traversable(from) || !traversable(to) {
+            return f32::INFINITY;
+        }
+        if from.0 != to.0
+            && from.1 != to.1
+            && !get_possible_moves(from, map, width, height, self.config.movement).contains(&to)
+        {
+            return f32::INFINITY;
         }
+        self.config
+            .cost_model
+            .step_cost(from, to, map, width, height)
+            .unwrap_or(f32::INFINITY)
     }
 
     /// Reconstruct full path between two jump points
@@ -1151,13 +2657,14 @@ impl JPSW {
 }
 
 impl PathfindingAlgorithm for JPSW {
-    fn find_path(
+    fn search(
         &self,
         start: (i32, i32),
         goal: (i32, i32),
-        map: &Vec<Tile>,
+        map: &[Tile],
         width: u32,
         height: u32,
+        ctx: &mut SearchContext,
     ) -> (Vec<(i32, i32)>, u32) {
         #[derive(Clone)]
         struct Node {
@@ -1187,12 +2694,7 @@ impl PathfindingAlgorithm for JPSW {
         let mut parent: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
         let mut closed = HashSet::new();
 
-        let h = |p: (i32, i32)| -> f32 {
-            let dx = (p.0 - goal.0).abs() as f32;
-            let dy = (p.1 - goal.1).abs() as f32;
-            let diag = dx.min(dy);
-            diag * std::f32::consts::SQRT_2 + (dx.max(dy) - diag)
-        };
+        let h = |p: (i32, i32)| -> f32 { self.config.estimate(p, goal) };
 
         g_score.insert(start, 0.0);
         open.push(Node {
@@ -1208,6 +2710,10 @@ impl PathfindingAlgorithm for JPSW {
             }
             closed.insert(current);
             expansions += 1;
+            ctx.record(current, TraceCell::Expanded);
+            if ctx.out_of_budget(expansions) {
+                return (vec![], expansions);
+            }
 
             if current == goal {
                 // Reconstruct jump point path
@@ -1249,6 +2755,7 @@ impl PathfindingAlgorithm for JPSW {
                             f: tentative_g + h(jp),
                             pos: jp,
                         });
+                        ctx.record(jp, TraceCell::JumpPoint);
                     }
                 }
             }
@@ -1267,7 +2774,7 @@ impl PathfindingAlgorithm for JPSW {
 
     fn reconstruct_path(&self, path: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
         let mut full_path: Vec<(i32, i32)> = Vec::new();
-        if path.len() > 0 {
+        if !path.is_empty() {
             for i in 0..path.len() - 1 {
                 let segment = Self::reconstruct_segment(path[i], path[i + 1]);
                 // Add all but the last cell (to avoid duplicates)
@@ -1282,17 +2789,14 @@ impl PathfindingAlgorithm for JPSW {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        colors::WHITE,
-        components::board::{Tile, TileType},
-    };
+    use crate::grid::{Tile, TileType};
 
     /// Helper: build an NxN grid of floor tiles with weight=1
     fn make_floor_grid(n: i32) -> Vec<Tile> {
         let mut map = Vec::with_capacity((n * n) as usize);
         for y in 0..n {
             for x in 0..n {
-                map.push(Tile::new((x, y), TileType::Floor, 10, 10, 1, false, WHITE));
+                map.push(Tile::new((x, y), TileType::Floor, 1));
             }
         }
         map
@@ -1300,23 +2804,45 @@ mod tests {
 
     /// Helper: place an obstacle on an existing grid
     fn set_obstacle(map: &mut Vec<Tile>, pos: (i32, i32), n: u32) {
-        if let Some(idx) = util::get_idx_from_coordinate(pos, n, n) {
-            map[idx] = Tile::new(pos, TileType::Obstacle, 10, 10, 1, false, WHITE);
+        if let Some(idx) = get_idx_from_coordinate(pos, n, n) {
+            map[idx] = Tile::new(pos, TileType::Obstacle, 1);
+        }
+    }
+
+    /// Helper: random 9-weight map with the given obstacle percentage and
+    /// free corners
+    fn make_random_grid(n: u32, obstacles: usize, seed: u64) -> Vec<Tile> {
+        let mut grid = crate::grid::Grid::new(n, n);
+        crate::generation::random_grid(&mut grid, 9, obstacles, 20, false, Some(seed));
+        for pos in [(0, 0), (n as i32 - 1, n as i32 - 1)] {
+            let idx = get_idx_from_coordinate(pos, n, n).unwrap();
+            grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
         }
+        grid.tiles
+    }
+
+    /// Helper: cost of the path `name` finds between opposite corners
+    fn corner_to_corner_cost(
+        name: &str,
+        config: &SearchConfig,
+        map: &[Tile],
+        n: u32,
+    ) -> Option<f64> {
+        let mut agent = Agent {
+            start: (0, 0),
+            goal: (n as i32 - 1, n as i32 - 1),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let result = agent.get_path(name, config, map, n, n);
+        result.is_success().then_some(result.cost)
     }
 
     /// Helper: set a weighted tile
     fn set_weight(map: &mut Vec<Tile>, pos: (i32, i32), weight: u8, n: u32) {
-        if let Some(idx) = util::get_idx_from_coordinate(pos, n, n) {
-            map[idx] = Tile::new(
-                pos,
-                TileType::Weighted(weight),
-                10,
-                10,
-                weight,
-                false,
-                WHITE,
-            );
+        if let Some(idx) = get_idx_from_coordinate(pos, n, n) {
+            map[idx] = Tile::new(pos, TileType::Weighted(weight), weight);
         }
     }
 
@@ -1325,7 +2851,7 @@ mod tests {
     #[test]
     fn test_possible_moves_center_of_open_grid() {
         let map = make_floor_grid(5);
-        let moves = get_possible_moves((2, 2), &map, 5, 5);
+        let moves = get_possible_moves((2, 2), &map, 5, 5, MovementRule::default());
         // All 8 neighbors should be reachable
         assert_eq!(moves.len(), 8);
         for &(dx, dy) in DELTAS.iter() {
@@ -1336,7 +2862,7 @@ mod tests {
     #[test]
     fn test_possible_moves_corner_of_grid() {
         let map = make_floor_grid(5);
-        let moves = get_possible_moves((0, 0), &map, 5, 5);
+        let moves = get_possible_moves((0, 0), &map, 5, 5, MovementRule::default());
         // Only 3 neighbors exist: (1,0), (0,1), (1,1)
         assert_eq!(moves.len(), 3);
         assert!(moves.contains(&(1, 0)));
@@ -1349,7 +2875,7 @@ mod tests {
         let mut map = make_floor_grid(5);
         // Place obstacle to the right of (2,2) → (3,2)
         set_obstacle(&mut map, (3, 2), 5);
-        let moves = get_possible_moves((2, 2), &map, 5, 5);
+        let moves = get_possible_moves((2, 2), &map, 5, 5, MovementRule::default());
         // (3,2) is blocked, and corner-cutting diagonals (3,1) and (3,3) should be blocked too
         assert!(!moves.contains(&(3, 2)));
         assert!(!moves.contains(&(3, 1)));
@@ -1361,186 +2887,832 @@ mod tests {
     }
 
     #[test]
-    fn test_possible_moves_surrounded_by_obstacles() {
-        let mut map = make_floor_grid(5);
-        for &(dx, dy) in DELTAS.iter() {
-            set_obstacle(&mut map, (2 + dx, 2 + dy), 5);
+    fn test_possible_moves_surrounded_by_obstacles() {
+        let mut map = make_floor_grid(5);
+        for &(dx, dy) in DELTAS.iter() {
+            set_obstacle(&mut map, (2 + dx, 2 + dy), 5);
+        }
+        let moves = get_possible_moves((2, 2), &map, 5, 5, MovementRule::default());
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn test_possible_moves_player_tile_is_traversable() {
+        let mut map = make_floor_grid(3);
+        if let Some(idx) = get_idx_from_coordinate((1, 0), 3, 3) {
+            map[idx] = Tile::new((1, 0), TileType::Player, 1);
+        }
+        let moves = get_possible_moves((0, 0), &map, 3, 3, MovementRule::default());
+        assert!(moves.contains(&(1, 0)));
+    }
+
+    #[test]
+    fn test_possible_moves_enemy_tile_is_traversable() {
+        let mut map = make_floor_grid(3);
+        if let Some(idx) = get_idx_from_coordinate((1, 0), 3, 3) {
+            map[idx] = Tile::new((1, 0), TileType::Enemy, 1);
+        }
+        let moves = get_possible_moves((0, 0), &map, 3, 3, MovementRule::default());
+        assert!(moves.contains(&(1, 0)));
+    }
+
+    #[test]
+    fn test_possible_moves_water_only_connects_to_water() {
+        // Left column is water, the rest is land
+        let mut map = make_floor_grid(3);
+        for y in 0..3 {
+            map[y * 3] = Tile::new((0, y as i32), TileType::Water, 1);
+        }
+        let from_water = get_possible_moves((0, 1), &map, 3, 3, MovementRule::default());
+        assert_eq!(from_water.len(), 2);
+        assert!(from_water.contains(&(0, 0)));
+        assert!(from_water.contains(&(0, 2)));
+
+        let from_land = get_possible_moves((1, 1), &map, 3, 3, MovementRule::default());
+        assert!(!from_land.contains(&(0, 1)));
+        // Water blocks corner cutting for land moves too
+        assert!(!from_land.contains(&(0, 0)));
+        assert_eq!(from_land.len(), 5);
+    }
+
+    // ------- Search trace -------
+
+    #[test]
+    fn test_untraced_search_records_nothing() {
+        let map = make_floor_grid(5);
+        let mut ctx = SearchContext::default();
+        BreadthFirstSearch::default().search((0, 0), (4, 4), &map, 5, 5, &mut ctx);
+        assert!(ctx.trace.is_none());
+    }
+
+    #[test]
+    fn test_trace_expansions_match_steps() {
+        let mut map = make_floor_grid(8);
+        set_obstacle(&mut map, (3, 3), 8);
+        for name in ["Breadth First Search", "Dijkstra", "A* search"] {
+            let algo = get_algorithm(name);
+            let mut ctx = SearchContext::traced();
+            let (_, steps) = algo.search((0, 0), (7, 7), &map, 8, 8, &mut ctx);
+            let trace = ctx.trace.unwrap();
+            assert_eq!(trace.count(TraceCell::Expanded), steps as usize, "{}", name);
+            assert!(trace.count(TraceCell::Opened) > 0, "{}", name);
+            assert!(trace.events.iter().all(|e| e.position != (3, 3)));
+        }
+    }
+
+    #[test]
+    fn test_jpsw_trace_records_jump_points() {
+        let mut map = make_floor_grid(10);
+        set_obstacle(&mut map, (5, 4), 10);
+        set_obstacle(&mut map, (5, 5), 10);
+        let agent = Agent {
+            start: (0, 0),
+            goal: (9, 9),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let trace = agent.trace_search("JPSW", &SearchConfig::default(), &map, 10, 10);
+        assert!(trace.count(TraceCell::JumpPoint) > 0);
+        assert!(trace
+            .events
+            .iter()
+            .any(|e| e.position == (9, 9) && e.cell == TraceCell::JumpPoint));
+    }
+
+    // ------- Heuristics -------
+
+    #[test]
+    fn test_heuristic_distances() {
+        let (a, b) = ((0, 0), (3, 4));
+        assert_eq!(Heuristic::Manhattan.distance(a, b), 7.0);
+        assert_eq!(Heuristic::Chebyshev.distance(a, b), 4.0);
+        assert_eq!(Heuristic::Euclidean.distance(a, b), 5.0);
+        assert!((Heuristic::Octile.distance(a, b) - (3.0 * SQRT_2 + 1.0)).abs() < 1e-5);
+        assert_eq!(Heuristic::Zero.distance(a, b), 0.0);
+    }
+
+    #[test]
+    fn test_heuristic_costs_follow_the_cost_model() {
+        let (a, b) = ((0, 0), (3, 4));
+        // Diagonals cost the same as straight steps, so Octile is Chebyshev
+        assert_eq!(Heuristic::Octile.cost(a, b, &CostModel::UNIFORM), 4.0);
+        assert!((Heuristic::Euclidean.cost(a, b, &CostModel::UNIFORM) - 5.0 / SQRT_2).abs() < 1e-5);
+        for heuristic in Heuristic::ALL {
+            let cost = heuristic.cost(a, b, &CostModel::OCTILE);
+            assert!((cost - heuristic.distance(a, b)).abs() < 1e-5);
+        }
+        // Diagonals dearer than two straight steps are never worth taking
+        let detour = CostModel {
+            diagonal: 3.0,
+            ..CostModel::UNIFORM
+        };
+        assert_eq!(Heuristic::Octile.cost(a, b, &detour), 7.0);
+    }
+
+    #[test]
+    fn test_default_astar_matches_dijkstra_cost() {
+        // The default heuristic must stay admissible under the default costs
+        for movement in MovementRule::ALL {
+            let config = SearchConfig {
+                movement,
+                ..SearchConfig::default()
+            };
+            for seed in 0..30 {
+                let map = make_random_grid(24, 25, seed);
+                let optimal = corner_to_corner_cost("Dijkstra", &config, &map, 24);
+                for name in ["A* search", "Bidirectional A*"] {
+                    let cost = corner_to_corner_cost(name, &config, &map, 24);
+                    let context = format!("{} {} seed {}", name, movement.name(), seed);
+                    assert_eq!(cost.is_some(), optimal.is_some(), "{}", context);
+                    if let (Some(cost), Some(optimal)) = (cost, optimal) {
+                        assert!(
+                            (cost - optimal).abs() < 1e-6,
+                            "{}: {} vs {}",
+                            context,
+                            cost,
+                            optimal
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_heuristic_from_name() {
+        for heuristic in Heuristic::ALL {
+            assert_eq!(Heuristic::from_name(heuristic.name()), Some(heuristic));
+        }
+        assert_eq!(Heuristic::from_name(" octile "), Some(Heuristic::Octile));
+        assert_eq!(Heuristic::from_name("Taxicab"), None);
+    }
+
+    #[test]
+    fn test_search_config_weight_scales_estimate() {
+        let config = SearchConfig {
+            heuristic: Heuristic::Chebyshev,
+            heuristic_weight: 2.5,
+            ..SearchConfig::default()
+        };
+        assert_eq!(config.estimate((0, 0), (4, 1)), 10.0);
+    }
+
+    #[test]
+    fn test_zero_heuristic_astar_matches_dijkstra_cost() {
+        let mut map = make_floor_grid(8);
+        set_weight(&mut map, (3, 3), 50, 8);
+        set_weight(&mut map, (4, 4), 50, 8);
+        set_obstacle(&mut map, (2, 5), 8);
+        let astar = AStarSearch {
+            config: SearchConfig {
+                heuristic: Heuristic::Zero,
+                heuristic_weight: 1.0,
+                ..SearchConfig::default()
+            },
+        };
+        let (astar_path, _) = astar.find_path((0, 0), (7, 7), &map, 8, 8);
+        let (dijkstra_path, _) = DijkstraSearch::default().find_path((0, 0), (7, 7), &map, 8, 8);
+        let model = CostModel::default();
+        assert_eq!(
+            model.path_cost(&astar_path, &map, 8, 8),
+            model.path_cost(&dijkstra_path, &map, 8, 8)
+        );
+    }
+
+    #[test]
+    fn test_heuristic_changes_expansions() {
+        let map = make_floor_grid(20);
+        let expansions = |heuristic: Heuristic| {
+            let astar = AStarSearch {
+                config: SearchConfig {
+                    heuristic,
+                    heuristic_weight: 1.0,
+                    ..SearchConfig::default()
+                },
+            };
+            astar.find_path((0, 0), (19, 10), &map, 20, 20).1
+        };
+        // An uninformed search floods far more of the open grid
+        assert!(expansions(Heuristic::Zero) > expansions(Heuristic::Chebyshev));
+    }
+
+    #[test]
+    fn test_jpsw_uses_configured_heuristic() {
+        let map = make_floor_grid(10);
+        for heuristic in Heuristic::ALL {
+            let jpsw = JPSW::with_config(SearchConfig {
+                heuristic,
+                heuristic_weight: 1.5,
+                ..SearchConfig::default()
+            });
+            let (path, _) = jpsw.find_path((0, 0), (9, 6), &map, 10, 10);
+            assert_eq!(path[0], (9, 6), "{:?} should reach the goal", heuristic);
+        }
+    }
+
+    // ------- get_algorithm factory -------
+
+    #[test]
+    fn test_get_algorithm_greedy() {
+        let algo = get_algorithm("Greedy");
+        assert_eq!(algo.name(), "Greedy");
+        assert!(algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_bfs() {
+        let algo = get_algorithm("Breadth First Search");
+        assert_eq!(algo.name(), "Breadth-First Search");
+        assert!(algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_astar() {
+        let algo = get_algorithm("A* search");
+        assert_eq!(algo.name(), "A* Search");
+        assert!(algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_bidirectional_astar() {
+        let algo = get_algorithm("Bidirectional A*");
+        assert_eq!(algo.name(), "Bidirectional A*");
+        assert!(algo.returns_full_path());
+        assert!(ALGORITHMS.contains(&"Bidirectional A*"));
+    }
+
+    #[test]
+    fn test_get_algorithm_theta_star() {
+        for name in ["Theta*", "Lazy Theta*"] {
+            let algo = get_algorithm(name);
+            assert_eq!(algo.name(), name);
+            assert!(!algo.returns_full_path());
+            assert!(algo.is_any_angle());
+            assert!(ALGORITHMS.contains(&name));
+        }
+    }
+
+    #[test]
+    fn test_get_algorithm_dstar_lite() {
+        let algo = get_algorithm("D* Lite");
+        assert_eq!(algo.name(), "D* Lite");
+        assert!(algo.returns_full_path());
+        assert!(ALGORITHMS.contains(&"D* Lite"));
+    }
+
+    #[test]
+    fn test_get_algorithm_dijkstra() {
+        let algo = get_algorithm("Dijkstra");
+        assert_eq!(algo.name(), "Dijkstra");
+        assert!(algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_jpsw() {
+        let algo = get_algorithm("JPSW");
+        assert_eq!(algo.name(), "JPSW");
+        assert!(!algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_hpa_star() {
+        let algo = get_algorithm("HPA*");
+        assert_eq!(algo.name(), "HPA*");
+        assert!(algo.returns_full_path());
+    }
+
+    #[test]
+    fn test_get_algorithm_unknown_defaults_to_greedy() {
+        let algo = get_algorithm("UnknownAlgorithm");
+        assert_eq!(algo.name(), "Greedy");
+    }
+
+    // ------- BFS -------
+
+    #[test]
+    fn test_bfs_finds_path_on_open_grid() {
+        let map = make_floor_grid(10);
+        let bfs = BreadthFirstSearch::default();
+        let (path, steps) = bfs.find_path((0, 0), (9, 9), &map, 10, 10);
+        assert!(!path.is_empty());
+        assert!(steps > 0);
+        assert!(path.contains(&(9, 9)));
+        assert!(path.contains(&(0, 0)));
+    }
+
+    #[test]
+    fn test_bfs_start_equals_goal() {
+        let map = make_floor_grid(5);
+        let bfs = BreadthFirstSearch::default();
+        let (path, steps) = bfs.find_path((2, 2), (2, 2), &map, 5, 5);
+        assert_eq!(path, vec![(2, 2)]);
+        assert_eq!(steps, 1);
+    }
+
+    #[test]
+    fn test_bfs_no_path_when_blocked() {
+        let mut map = make_floor_grid(5);
+        // Wall off (4,*) completely
+        for y in 0..5 {
+            set_obstacle(&mut map, (3, y), 5);
+        }
+        let bfs = BreadthFirstSearch::default();
+        let (path, _) = bfs.find_path((0, 0), (4, 4), &map, 5, 5);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_bfs_adjacent_goal() {
+        let map = make_floor_grid(5);
+        let bfs = BreadthFirstSearch::default();
+        let (path, _) = bfs.find_path((0, 0), (1, 0), &map, 5, 5);
+        assert!(!path.is_empty());
+        assert!(path.contains(&(0, 0)));
+        assert!(path.contains(&(1, 0)));
+    }
+
+    // ------- A* -------
+
+    #[test]
+    fn test_astar_finds_path_on_open_grid() {
+        let map = make_floor_grid(10);
+        let astar = AStarSearch::default();
+        let (path, steps) = astar.find_path((0, 0), (9, 9), &map, 10, 10);
+        assert!(!path.is_empty());
+        assert!(steps > 0);
+        assert!(path.contains(&(9, 9)));
+        assert!(path.contains(&(0, 0)));
+    }
+
+    #[test]
+    fn test_astar_prefers_lower_weight_path() {
+        // Create a 5x3 grid, weight the middle row heavily
+        let mut map = make_floor_grid(5);
+        for x in 1..4 {
+            set_weight(&mut map, (x, 1), 100, 5);
+        }
+        let astar = AStarSearch::default();
+        let (path, _) = astar.find_path((0, 1), (4, 1), &map, 5, 5);
+        assert!(!path.is_empty());
+        // The path should exist (A* found something)
+        assert!(path.contains(&(4, 1)));
+    }
+
+    #[test]
+    fn test_astar_no_path_when_blocked() {
+        let mut map = make_floor_grid(5);
+        for y in 0..5 {
+            set_obstacle(&mut map, (3, y), 5);
+        }
+        let astar = AStarSearch::default();
+        let (path, _) = astar.find_path((0, 0), (4, 4), &map, 5, 5);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_astar_start_equals_goal() {
+        let map = make_floor_grid(5);
+        let astar = AStarSearch::default();
+        let (path, steps) = astar.find_path((2, 2), (2, 2), &map, 5, 5);
+        // A* should return immediately with just the start node
+        assert!(!path.is_empty());
+        assert_eq!(steps, 1);
+    }
+
+    // ------- Dijkstra -------
+
+    #[test]
+    fn test_dijkstra_finds_path_on_open_grid() {
+        let map = make_floor_grid(10);
+        let (path, steps) = DijkstraSearch::default().find_path((0, 0), (9, 9), &map, 10, 10);
+        assert_eq!(path[0], (9, 9));
+        assert_eq!(*path.last().unwrap(), (0, 0));
+        // Diagonal steps cost the same as straight ones, so the diagonal is optimal
+        assert_eq!(path.len(), 10);
+        assert!(steps > 0);
+    }
+
+    #[test]
+    fn test_dijkstra_avoids_heavy_tiles() {
+        let mut map = make_floor_grid(5);
+        for x in 1..4 {
+            set_weight(&mut map, (x, 1), 100, 5);
+        }
+        let (path, _) = DijkstraSearch::default().find_path((0, 1), (4, 1), &map, 5, 5);
+        assert!(path.iter().all(|p| !(1..4).contains(&p.0) || p.1 != 1));
+        let cost = CostModel::default().path_cost(&path, &map, 5, 5);
+        assert_eq!(cost, (path.len() - 1) as f64);
+    }
+
+    #[test]
+    fn test_dijkstra_no_path_when_blocked() {
+        let mut map = make_floor_grid(5);
+        for y in 0..5 {
+            set_obstacle(&mut map, (3, y), 5);
+        }
+        let (path, _) = DijkstraSearch::default().find_path((0, 0), (4, 4), &map, 5, 5);
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn test_dijkstra_cost_never_exceeds_astar() {
+        let mut map = make_floor_grid(8);
+        for (i, pos) in [(2, 0), (2, 1), (2, 2), (5, 7), (5, 6), (5, 5)].iter().enumerate() {
+            set_weight(&mut map, *pos, 10 + i as u8, 8);
         }
-        let moves = get_possible_moves((2, 2), &map, 5, 5);
-        assert!(moves.is_empty());
+        set_obstacle(&mut map, (4, 3), 8);
+        let (dijkstra, _) = DijkstraSearch::default().find_path((0, 0), (7, 7), &map, 8, 8);
+        let (astar, _) = AStarSearch::default().find_path((0, 0), (7, 7), &map, 8, 8);
+        let model = CostModel::default();
+        assert!(model.path_cost(&dijkstra, &map, 8, 8) <= model.path_cost(&astar, &map, 8, 8));
     }
 
-    #[test]
-    fn test_possible_moves_player_tile_is_traversable() {
-        let mut map = make_floor_grid(3);
-        if let Some(idx) = util::get_idx_from_coordinate((1, 0), 3, 3) {
-            map[idx] = Tile::new((1, 0), TileType::Player, 10, 10, 1, false, WHITE);
+    // ------- Bidirectional A* -------
+
+    /// Helper: cost of a goal-first path under the default cost model
+    fn move_cost(path: &[(i32, i32)], map: &[Tile], n: u32) -> f64 {
+        CostModel::default().path_cost(path, map, n, n)
+    }
+
+    /// Helper: A* variants with the admissible heuristic for the integer cost model
+    fn chebyshev() -> SearchConfig {
+        SearchConfig {
+            heuristic: Heuristic::Chebyshev,
+            heuristic_weight: 1.0,
+            ..SearchConfig::default()
         }
-        let moves = get_possible_moves((0, 0), &map, 3, 3);
-        assert!(moves.contains(&(1, 0)));
     }
 
     #[test]
-    fn test_possible_moves_enemy_tile_is_traversable() {
-        let mut map = make_floor_grid(3);
-        if let Some(idx) = util::get_idx_from_coordinate((1, 0), 3, 3) {
-            map[idx] = Tile::new((1, 0), TileType::Enemy, 10, 10, 1, false, WHITE);
+    fn test_bidirectional_astar_returns_connected_goal_first_path() {
+        let mut map = make_floor_grid(10);
+        for y in 0..8 {
+            set_obstacle(&mut map, (5, y), 10);
+        }
+        let search = BidirectionalAStar::default();
+        let (path, steps) = search.find_path((0, 0), (9, 0), &map, 10, 10);
+        assert_eq!(path[0], (9, 0));
+        assert_eq!(*path.last().unwrap(), (0, 0));
+        assert!(steps > 0);
+        for step in path.windows(2) {
+            assert!(
+                get_possible_moves(step[1], &map, 10, 10, MovementRule::default())
+                    .contains(&step[0])
+            );
         }
-        let moves = get_possible_moves((0, 0), &map, 3, 3);
-        assert!(moves.contains(&(1, 0)));
     }
 
-    // ------- get_overall_path_weight -------
+    #[test]
+    fn test_bidirectional_astar_matches_dijkstra_cost() {
+        for seed in 0..20 {
+            let mut grid = crate::grid::Grid::new(24, 24);
+            crate::generation::random_grid(&mut grid, 50, 25, 30, false, Some(seed));
+            let (start, goal) = ((0, 0), (23, 23));
+            for pos in [start, goal] {
+                let idx = get_idx_from_coordinate(pos, 24, 24).unwrap();
+                grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
+            }
+            let (dijkstra, _) =
+                DijkstraSearch::default().find_path(start, goal, &grid.tiles, 24, 24);
+            let search = BidirectionalAStar {
+                config: chebyshev(),
+            };
+            let (path, _) = search.find_path(start, goal, &grid.tiles, 24, 24);
+            assert_eq!(path.is_empty(), dijkstra.is_empty(), "seed {}", seed);
+            assert_eq!(
+                move_cost(&path, &grid.tiles, 24),
+                move_cost(&dijkstra, &grid.tiles, 24),
+                "seed {}",
+                seed
+            );
+        }
+    }
 
     #[test]
-    fn test_path_weight_uniform() {
-        let map = make_floor_grid(5);
-        let path = vec![(0, 0), (1, 0), (2, 0)];
-        assert_eq!(get_overall_path_weight(&path, &map, 5, 5), 3);
+    fn test_bidirectional_astar_expands_less_than_astar_on_city_maps() {
+        let n = 64;
+        let (mut astar_steps, mut steps) = (0, 0);
+        for seed in 0..10 {
+            let mut grid = crate::grid::Grid::new(n, n);
+            crate::generation::organic_city(&mut grid, 0, 2, 8, 0.7, 2, 6, true, Some(seed));
+            let start = crate::grid::get_coordinate_from_idx(grid.starts[0], n, n);
+            let goal = crate::grid::get_coordinate_from_idx(grid.goals[0], n, n);
+            let astar = AStarSearch {
+                config: chebyshev(),
+            }
+            .find_path(start, goal, &grid.tiles, n, n);
+            let bidirectional = BidirectionalAStar {
+                config: chebyshev(),
+            }
+            .find_path(start, goal, &grid.tiles, n, n);
+            assert_eq!(
+                move_cost(&bidirectional.0, &grid.tiles, n),
+                move_cost(&astar.0, &grid.tiles, n),
+                "seed {}",
+                seed
+            );
+            astar_steps += astar.1;
+            steps += bidirectional.1;
+        }
+        assert!(steps < astar_steps, "{} >= {}", steps, astar_steps);
     }
 
     #[test]
-    fn test_path_weight_with_weighted_tiles() {
+    fn test_bidirectional_astar_no_path_when_blocked() {
         let mut map = make_floor_grid(5);
-        set_weight(&mut map, (1, 0), 5, 5);
-        set_weight(&mut map, (2, 0), 10, 5);
-        let path = vec![(0, 0), (1, 0), (2, 0)];
-        assert_eq!(get_overall_path_weight(&path, &map, 5, 5), 1 + 5 + 10);
+        for y in 0..5 {
+            set_obstacle(&mut map, (3, y), 5);
+        }
+        let (path, _) = BidirectionalAStar::default().find_path((0, 0), (4, 4), &map, 5, 5);
+        assert!(path.is_empty());
     }
 
     #[test]
-    fn test_path_weight_empty_path() {
+    fn test_bidirectional_astar_start_equals_goal() {
         let map = make_floor_grid(5);
-        let path: Vec<(i32, i32)> = vec![];
-        assert_eq!(get_overall_path_weight(&path, &map, 5, 5), 0);
+        let (path, _) = BidirectionalAStar::default().find_path((2, 2), (2, 2), &map, 5, 5);
+        assert_eq!(path, vec![(2, 2)]);
     }
 
-    // ------- get_algorithm factory -------
+    // ------- Theta* -------
 
     #[test]
-    fn test_get_algorithm_greedy() {
-        let algo = get_algorithm("Greedy");
-        assert_eq!(algo.name(), "Greedy");
-        assert!(algo.returns_full_path());
+    fn test_path_length_of_grid_steps_is_octile() {
+        let diagonal = std::f64::consts::SQRT_2;
+        assert_eq!(path_length(&[]), 0.0);
+        assert_eq!(path_length(&[(0, 0), (1, 0), (2, 0)]), 2.0);
+        assert!((path_length(&[(0, 0), (1, 1), (2, 1)]) - (1.0 + diagonal)).abs() < 1e-12);
     }
 
     #[test]
-    fn test_get_algorithm_bfs() {
-        let algo = get_algorithm("Breadth First Search");
-        assert_eq!(algo.name(), "Breadth-First Search");
-        assert!(algo.returns_full_path());
+    fn test_theta_star_expanded_paths_only_take_possible_moves() {
+        let mut agent = make_walled_agent();
+        agent.goal = (23, 23);
+        for movement in MovementRule::ALL {
+            let config = SearchConfig {
+                movement,
+                ..SearchConfig::default()
+            };
+            for seed in 0..30 {
+                let map = make_random_grid(24, 30, seed);
+                for name in ["Theta*", "Lazy Theta*"] {
+                    let result = agent.get_path(name, &config, &map, 24, 24);
+                    assert_eq!(
+                        result.path.last(),
+                        result.is_success().then_some(&agent.start)
+                    );
+                    // Goal first, so every step leads from step[1] to step[0]
+                    for step in result.path.windows(2) {
+                        assert!(
+                            get_possible_moves(step[1], &map, 24, 24, movement).contains(&step[0]),
+                            "{} {} seed {} {:?}",
+                            name,
+                            movement.name(),
+                            seed,
+                            step
+                        );
+                    }
+                }
+            }
+        }
     }
 
     #[test]
-    fn test_get_algorithm_astar() {
-        let algo = get_algorithm("A* search");
-        assert_eq!(algo.name(), "A* Search");
-        assert!(algo.returns_full_path());
+    fn test_line_cells_are_connected_and_inclusive() {
+        let cells = line_cells((0, 0), (5, 2));
+        assert_eq!(cells[0], (0, 0));
+        assert_eq!(*cells.last().unwrap(), (5, 2));
+        assert_eq!(cells.len(), 6);
+        for step in cells.windows(2) {
+            assert!((step[0].0 - step[1].0).abs() <= 1 && (step[0].1 - step[1].1).abs() <= 1);
+        }
+        assert_eq!(line_cells((3, 3), (3, 3)), vec![(3, 3)]);
     }
 
     #[test]
-    fn test_get_algorithm_jpsw() {
-        let algo = get_algorithm("JPSW");
-        assert_eq!(algo.name(), "JPSW");
-        assert!(!algo.returns_full_path());
+    fn test_line_of_sight_respects_obstacles_and_corners() {
+        let mut map = make_floor_grid(6);
+        assert!(line_of_sight(
+            (0, 0),
+            (5, 3),
+            MovementRule::default(),
+            &map,
+            6,
+            6
+        ));
+        set_obstacle(&mut map, (2, 2), 6);
+        assert!(!line_of_sight(
+            (0, 0),
+            (5, 5),
+            MovementRule::default(),
+            &map,
+            6,
+            6
+        ));
+        assert!(line_of_sight(
+            (0, 5),
+            (5, 5),
+            MovementRule::default(),
+            &map,
+            6,
+            6
+        ));
+        // Squeezing diagonally between two obstacles is blocked like a move
+        set_obstacle(&mut map, (4, 3), 6);
+        assert!(!line_of_sight(
+            (3, 3),
+            (4, 2),
+            MovementRule::default(),
+            &map,
+            6,
+            6
+        ));
     }
 
     #[test]
-    fn test_get_algorithm_unknown_defaults_to_greedy() {
-        let algo = get_algorithm("UnknownAlgorithm");
-        assert_eq!(algo.name(), "Greedy");
+    fn test_segment_cost_scales_with_weights() {
+        let mut map = make_floor_grid(5);
+        let model = CostModel::default();
+        assert_eq!(
+            segment_cost((0, 0), (4, 0), &model, MovementRule::default(), &map, 5, 5),
+            Some(4.0)
+        );
+        map[get_idx_from_coordinate((2, 0), 5, 5).unwrap()].weight = 5;
+        assert_eq!(
+            segment_cost((0, 0), (4, 0), &model, MovementRule::default(), &map, 5, 5),
+            Some(8.0)
+        );
+        assert_eq!(
+            segment_cost((1, 1), (1, 1), &model, MovementRule::default(), &map, 5, 5),
+            Some(0.0)
+        );
     }
 
-    // ------- BFS -------
-
     #[test]
-    fn test_bfs_finds_path_on_open_grid() {
+    fn test_theta_star_goes_straight_across_open_space() {
         let map = make_floor_grid(10);
-        let bfs = BreadthFirstSearch;
-        let (path, steps) = bfs.find_path((0, 0), (9, 9), &map, 10, 10);
-        assert!(!path.is_empty());
-        assert!(steps > 0);
-        assert!(path.contains(&(9, 9)));
-        assert!(path.contains(&(0, 0)));
+        for lazy in [false, true] {
+            let search = ThetaStar {
+                lazy,
+                ..Default::default()
+            };
+            let (waypoints, _) = search.find_path((0, 0), (9, 4), &map, 10, 10);
+            assert_eq!(waypoints, vec![(9, 4), (0, 0)]);
+        }
     }
 
     #[test]
-    fn test_bfs_start_equals_goal() {
-        let map = make_floor_grid(5);
-        let bfs = BreadthFirstSearch;
-        let (path, steps) = bfs.find_path((2, 2), (2, 2), &map, 5, 5);
-        assert_eq!(path, vec![(2, 2)]);
-        assert_eq!(steps, 1);
+    fn test_theta_star_waypoints_expand_to_connected_path() {
+        let mut map = make_floor_grid(10);
+        for y in 0..8 {
+            set_obstacle(&mut map, (5, y), 10);
+        }
+        for lazy in [false, true] {
+            let mut agent = Agent {
+                start: (0, 0),
+                goal: (9, 0),
+                position: (0, 0),
+                path: vec![],
+                waypoints: vec![],
+            };
+            let name = if lazy { "Lazy Theta*" } else { "Theta*" };
+            let result = agent.get_path(name, &SearchConfig::default(), &map, 10, 10);
+            assert!(result.is_success());
+            assert!(result.waypoints.len() > 2);
+            assert_eq!(result.path[0], (9, 0));
+            assert_eq!(*result.path.last().unwrap(), (0, 0));
+            for step in result.path.windows(2) {
+                assert!(
+                    get_possible_moves(step[1], &map, 10, 10, MovementRule::default())
+                        .contains(&step[0])
+                );
+            }
+            assert!((result.length - path_length(&result.waypoints)).abs() < 1e-9);
+        }
     }
 
     #[test]
-    fn test_bfs_no_path_when_blocked() {
+    fn test_theta_star_is_never_longer_than_astar() {
+        for seed in 0..20 {
+            let mut grid = crate::grid::Grid::new(32, 32);
+            crate::generation::random_grid(&mut grid, 1, 20, 0, false, Some(seed));
+            let (start, goal) = ((0, 0), (31, 31));
+            for pos in [start, goal] {
+                let idx = get_idx_from_coordinate(pos, 32, 32).unwrap();
+                grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
+            }
+            let mut agent = Agent {
+                start,
+                goal,
+                position: start,
+                path: vec![],
+                waypoints: vec![],
+            };
+            let config = SearchConfig {
+                heuristic: Heuristic::Euclidean,
+                heuristic_weight: 1.0,
+                ..SearchConfig::default()
+            };
+            let astar = agent.get_path("A* search", &config, &grid.tiles, 32, 32);
+            for name in ["Theta*", "Lazy Theta*"] {
+                let theta = agent.get_path(name, &config, &grid.tiles, 32, 32);
+                assert_eq!(theta.is_success(), astar.is_success(), "seed {}", seed);
+                assert!(
+                    theta.length <= astar.length + 1e-6,
+                    "{} seed {}: {} > {}",
+                    name,
+                    seed,
+                    theta.length,
+                    astar.length
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_theta_star_no_path_when_blocked() {
         let mut map = make_floor_grid(5);
-        // Wall off (4,*) completely
         for y in 0..5 {
             set_obstacle(&mut map, (3, y), 5);
         }
-        let bfs = BreadthFirstSearch;
-        let (path, _) = bfs.find_path((0, 0), (4, 4), &map, 5, 5);
+        let (path, _) = ThetaStar::default().find_path((0, 0), (4, 4), &map, 5, 5);
         assert!(path.is_empty());
     }
 
-    #[test]
-    fn test_bfs_adjacent_goal() {
-        let map = make_floor_grid(5);
-        let bfs = BreadthFirstSearch;
-        let (path, _) = bfs.find_path((0, 0), (1, 0), &map, 5, 5);
-        assert!(!path.is_empty());
-        assert!(path.contains(&(0, 0)));
-        assert!(path.contains(&(1, 0)));
-    }
-
-    // ------- A* -------
+    // ------- D* Lite -------
 
     #[test]
-    fn test_astar_finds_path_on_open_grid() {
-        let map = make_floor_grid(10);
-        let astar = AStarSearch;
-        let (path, steps) = astar.find_path((0, 0), (9, 9), &map, 10, 10);
-        assert!(!path.is_empty());
-        assert!(steps > 0);
-        assert!(path.contains(&(9, 9)));
-        assert!(path.contains(&(0, 0)));
+    fn test_dstar_lite_matches_astar_cost() {
+        for seed in 0..20 {
+            let mut grid = crate::grid::Grid::new(24, 24);
+            crate::generation::random_grid(&mut grid, 50, 25, 30, false, Some(seed));
+            let (start, goal) = ((0, 0), (23, 23));
+            for pos in [start, goal] {
+                let idx = get_idx_from_coordinate(pos, 24, 24).unwrap();
+                grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
+            }
+            let (astar, _) = AStarSearch {
+                config: chebyshev(),
+            }
+            .find_path(start, goal, &grid.tiles, 24, 24);
+            let (path, _) = DStarLiteSearch::default().find_path(start, goal, &grid.tiles, 24, 24);
+            assert_eq!(path.is_empty(), astar.is_empty(), "seed {}", seed);
+            assert_eq!(
+                move_cost(&path, &grid.tiles, 24),
+                move_cost(&astar, &grid.tiles, 24),
+                "seed {}",
+                seed
+            );
+        }
     }
 
     #[test]
-    fn test_astar_prefers_lower_weight_path() {
-        // Create a 5x3 grid, weight the middle row heavily
-        let mut map = make_floor_grid(5);
-        for x in 1..4 {
-            set_weight(&mut map, (x, 1), 100, 5);
+    fn test_dstar_lite_repairs_after_obstacle_removed() {
+        let mut map = make_floor_grid(10);
+        for y in 0..9 {
+            set_obstacle(&mut map, (5, y), 10);
         }
-        let astar = AStarSearch;
-        let (path, _) = astar.find_path((0, 1), (4, 1), &map, 5, 5);
-        assert!(!path.is_empty());
-        // The path should exist (A* found something)
-        assert!(path.contains(&(4, 1)));
+        let mut planner = DStarLite::new(
+            (0, 0),
+            (9, 0),
+            CostModel::default(),
+            MovementRule::default(),
+            10,
+            10,
+        );
+        let initial = planner.compute(&map, &mut SearchContext::default());
+        let detour = planner.path(&map);
+        assert_eq!(detour[0], (9, 0));
+        assert_eq!(*detour.last().unwrap(), (0, 0));
+
+        // Open a gap next to the agent after it took two steps
+        map[get_idx_from_coordinate((5, 1), 10, 10).unwrap()] =
+            Tile::new((5, 1), TileType::Floor, 1);
+        let position = detour[detour.len() - 3];
+        planner.move_to(position);
+        planner.update_tiles(&[(5, 1)], &map);
+        let repaired = planner.compute(&map, &mut SearchContext::default());
+        let path = planner.path(&map);
+        assert!(repaired < initial);
+        assert!(path.contains(&(5, 1)));
+        let (astar, _) = AStarSearch {
+            config: chebyshev(),
+        }
+        .find_path(position, (9, 0), &map, 10, 10);
+        assert_eq!(move_cost(&path, &map, 10), move_cost(&astar, &map, 10));
     }
 
     #[test]
-    fn test_astar_no_path_when_blocked() {
+    fn test_dstar_lite_no_path_when_blocked() {
         let mut map = make_floor_grid(5);
         for y in 0..5 {
             set_obstacle(&mut map, (3, y), 5);
         }
-        let astar = AStarSearch;
-        let (path, _) = astar.find_path((0, 0), (4, 4), &map, 5, 5);
+        let (path, _) = DStarLiteSearch::default().find_path((0, 0), (4, 4), &map, 5, 5);
         assert!(path.is_empty());
-    }
-
-    #[test]
-    fn test_astar_start_equals_goal() {
-        let map = make_floor_grid(5);
-        let astar = AStarSearch;
-        let (path, steps) = astar.find_path((2, 2), (2, 2), &map, 5, 5);
-        // A* should return immediately with just the start node
-        assert!(!path.is_empty());
-        assert_eq!(steps, 1);
+        let (path, _) = DStarLiteSearch::default().find_path((2, 2), (2, 2), &map, 5, 5);
+        assert_eq!(path, vec![(2, 2)]);
     }
 
     // ------- Greedy -------
@@ -1548,7 +3720,7 @@ mod tests {
     #[test]
     fn test_greedy_finds_path_on_open_grid() {
         let map = make_floor_grid(10);
-        let greedy = GreedySearch;
+        let greedy = GreedySearch::default();
         let (path, steps) = greedy.find_path((0, 0), (5, 5), &map, 10, 10);
         assert!(!path.is_empty());
         assert!(steps > 0);
@@ -1560,7 +3732,7 @@ mod tests {
         for y in 0..5 {
             set_obstacle(&mut map, (2, y), 5);
         }
-        let greedy = GreedySearch;
+        let greedy = GreedySearch::default();
         let (path, _) = greedy.find_path((0, 0), (4, 4), &map, 5, 5);
         assert!(path.is_empty());
     }
@@ -1611,10 +3783,18 @@ mod tests {
         assert_eq!(segment, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
     }
 
+    /// Helper: JPSW charging moves by the averaged cost model
+    fn averaged_jpsw() -> JPSW {
+        JPSW::with_config(SearchConfig {
+            cost_model: CostModel::AVERAGED,
+            ..SearchConfig::default()
+        })
+    }
+
     #[test]
     fn test_jpsw_move_cost_orthogonal() {
         let map = make_floor_grid(5);
-        let cost = JPSW::move_cost_static((1, 0), (0, 0), &map, 5, 5);
+        let cost = averaged_jpsw().move_cost((1, 0), (0, 0), &map, 5, 5);
         // Orthogonal: (w1 + w2) / 2 = (1 + 1) / 2 = 1.0
         assert!((cost - 1.0).abs() < 0.01);
     }
@@ -1622,7 +3802,7 @@ mod tests {
     #[test]
     fn test_jpsw_move_cost_diagonal() {
         let map = make_floor_grid(5);
-        let cost = JPSW::move_cost_static((1, 1), (0, 0), &map, 5, 5);
+        let cost = averaged_jpsw().move_cost((1, 1), (0, 0), &map, 5, 5);
         // Diagonal: avg weight * sqrt(2) ≈ 1.414
         assert!(cost > 1.0);
         assert!(cost < 2.0);
@@ -1640,6 +3820,32 @@ mod tests {
         assert!(jpsw.jump_cache.borrow().is_empty());
     }
 
+    #[test]
+    fn test_jpsw_matches_dijkstra_cost_under_every_cost_model() {
+        for cost_model in CostModel::ALL {
+            let config = SearchConfig {
+                cost_model,
+                ..SearchConfig::default()
+            };
+            for seed in 0..40 {
+                let map = make_random_grid(24, 10 + seed as usize % 4 * 10, seed);
+                let optimal = corner_to_corner_cost("Dijkstra", &config, &map, 24);
+                let cost = corner_to_corner_cost("JPSW", &config, &map, 24);
+                let context = format!("{} seed {}", cost_model.name(), seed);
+                assert_eq!(cost.is_some(), optimal.is_some(), "{}", context);
+                if let (Some(cost), Some(optimal)) = (cost, optimal) {
+                    assert!(
+                        (cost - optimal).abs() < 1e-3,
+                        "{}: {} vs {}",
+                        context,
+                        cost,
+                        optimal
+                    );
+                }
+            }
+        }
+    }
+
     // ------- Agent -------
 
     #[test]
@@ -1649,6 +3855,7 @@ mod tests {
             goal: (5, 5),
             position: (5, 5),
             path: vec![],
+            waypoints: vec![],
         };
         assert!(agent.goal_reached());
     }
@@ -1660,6 +3867,7 @@ mod tests {
             goal: (5, 5),
             position: (3, 3),
             path: vec![],
+            waypoints: vec![],
         };
         assert!(!agent.goal_reached());
     }
@@ -1672,8 +3880,9 @@ mod tests {
             goal: (2, 2),
             position: (2, 2),
             path: vec![],
+            waypoints: vec![],
         };
-        assert!(agent.is_path_possible(&map, 5, 5));
+        assert!(agent.is_path_possible(&map, 5, 5, MovementRule::default()));
     }
 
     #[test]
@@ -1684,8 +3893,9 @@ mod tests {
             goal: (9, 9),
             position: (0, 0),
             path: vec![],
+            waypoints: vec![],
         };
-        assert!(agent.is_path_possible(&map, 10, 10));
+        assert!(agent.is_path_possible(&map, 10, 10, MovementRule::default()));
     }
 
     #[test]
@@ -1700,8 +3910,138 @@ mod tests {
             goal: (4, 4),
             position: (0, 0),
             path: vec![],
+            waypoints: vec![],
+        };
+        assert!(!agent.is_path_possible(&map, 5, 5, MovementRule::default()));
+    }
+
+    #[test]
+    fn test_agent_get_path_reports_result() {
+        let map = make_floor_grid(5);
+        let mut agent = Agent {
+            start: (0, 0),
+            goal: (4, 4),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let result = agent.get_path("JPSW", &SearchConfig::default(), &map, 5, 5);
+        assert!(result.is_success());
+        assert_eq!(result.algorithm, "JPSW");
+        // Reconstructed into a full path, goal first
+        assert_eq!(result.path.first(), Some(&(4, 4)));
+        assert_eq!(result.path.last(), Some(&(0, 0)));
+        // Every tile has weight 1, so the cost is the number of steps
+        assert_eq!(result.cost as usize, result.path.len() - 1);
+        assert!(result.expansions > 0);
+    }
+
+    #[test]
+    fn test_agent_get_path_failure_reasons() {
+        let mut map = make_floor_grid(5);
+        for y in 0..5 {
+            set_obstacle(&mut map, (2, y), 5);
+        }
+        let mut agent = Agent {
+            start: (0, 0),
+            goal: (4, 4),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        };
+        let config = SearchConfig::default();
+
+        let result = agent.get_path("A* search", &config, &map, 5, 5);
+        assert_eq!(result.failure, Some(FailureReason::Unreachable));
+        assert!(result.path.is_empty());
+        assert_eq!(result.cost, 0.0);
+
+        // Greedy wanders until its step limit instead of exhausting the grid
+        let result = agent.get_path("Greedy", &config, &map, 5, 5);
+        assert_eq!(result.failure, Some(FailureReason::StepLimit));
+    }
+
+    // ------- Budgets and cancellation -------
+
+    /// Helper: 128x128 floor with a wall that leaves a gap at the bottom, so
+    /// every algorithm needs well over a hundred expansions
+    fn make_walled_grid() -> Vec<Tile> {
+        let mut map = make_floor_grid(128);
+        for y in 0..127 {
+            set_obstacle(&mut map, (64, y), 128);
+        }
+        map
+    }
+
+    fn make_walled_agent() -> Agent {
+        Agent {
+            start: (0, 0),
+            goal: (127, 0),
+            position: (0, 0),
+            path: vec![],
+            waypoints: vec![],
+        }
+    }
+
+    #[test]
+    fn test_every_algorithm_honours_expansion_budget() {
+        let map = make_walled_grid();
+        let mut agent = make_walled_agent();
+        // Greedy breaks ties at random, so it needs a different count each run
+        for name in ALGORITHMS.into_iter().filter(|&name| name != "Greedy") {
+            let needed = agent
+                .get_path(name, &SearchConfig::default(), &map, 128, 128)
+                .expansions;
+            let mut config = SearchConfig::default();
+            config.budget.max_expansions = Some(needed);
+            assert!(agent.get_path(name, &config, &map, 128, 128).is_success(), "{}", name);
+
+            config.budget.max_expansions = Some(needed - 1);
+            let result = agent.get_path(name, &config, &map, 128, 128);
+            assert_eq!(result.failure, Some(FailureReason::ExpansionLimit), "{}", name);
+            assert!(result.path.is_empty(), "{}", name);
+            assert!(result.failure.is_some_and(|reason| reason.is_exhausted()));
+        }
+    }
+
+    #[test]
+    fn test_every_algorithm_stops_when_cancelled() {
+        let map = make_walled_grid();
+        let mut agent = make_walled_agent();
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        for name in ALGORITHMS {
+            let needed = agent
+                .get_path(name, &SearchConfig::default(), &map, 128, 128)
+                .expansions;
+            let result =
+                agent.get_path_cancellable(name, &SearchConfig::default(), &cancel, &map, 128, 128);
+            // The token is only read every `BUDGET_CHECK_INTERVAL` expansions
+            if needed >= BUDGET_CHECK_INTERVAL {
+                assert_eq!(result.failure, Some(FailureReason::Cancelled), "{}", name);
+                assert!(result.expansions < needed, "{}", name);
+            }
+        }
+        assert!(!FailureReason::Cancelled.is_exhausted());
+    }
+
+    #[test]
+    fn test_time_budget_reports_timeout() {
+        let map = make_walled_grid();
+        let mut agent = make_walled_agent();
+        let config = SearchConfig {
+            budget: SearchBudget {
+                max_time: Some(Duration::ZERO),
+                ..Default::default()
+            },
+            ..Default::default()
         };
-        assert!(!agent.is_path_possible(&map, 5, 5));
+        let result = agent.get_path("Dijkstra", &config, &map, 128, 128);
+        assert_eq!(result.failure, Some(FailureReason::Timeout));
+        assert_ne!(
+            FailureReason::Timeout.description(),
+            FailureReason::Unreachable.description()
+        );
     }
 
     // ------- All algorithms find same reachable goals -------
@@ -1712,8 +4052,8 @@ mod tests {
         let start = (0, 0);
         let goal = (7, 7);
 
-        let bfs = BreadthFirstSearch;
-        let astar = AStarSearch;
+        let bfs = BreadthFirstSearch::default();
+        let astar = AStarSearch::default();
         let jpsw = JPSW::default();
 
         let (bfs_path, _) = bfs.find_path(start, goal, &map, 8, 8);
@@ -1735,8 +4075,8 @@ mod tests {
         let start = (0, 0);
         let goal = (5, 5);
 
-        let bfs = BreadthFirstSearch;
-        let astar = AStarSearch;
+        let bfs = BreadthFirstSearch::default();
+        let astar = AStarSearch::default();
         let jpsw = JPSW::default();
 
         let (bfs_path, _) = bfs.find_path(start, goal, &map, 6, 6);
@@ -1767,7 +4107,7 @@ mod tests {
         set_obstacle(&mut map, (3, 3), 5);
         set_obstacle(&mut map, (1, 4), 5);
 
-        let bfs = BreadthFirstSearch;
+        let bfs = BreadthFirstSearch::default();
         let (path, _) = bfs.find_path((0, 0), (4, 4), &map, 5, 5);
         assert!(!path.is_empty());
     }
@@ -1783,8 +4123,200 @@ mod tests {
         set_obstacle(&mut map, (3, 3), 5);
         set_obstacle(&mut map, (1, 4), 5);
 
-        let astar = AStarSearch;
+        let astar = AStarSearch::default();
         let (path, _) = astar.find_path((0, 0), (4, 4), &map, 5, 5);
         assert!(!path.is_empty());
     }
+
+    // ------- Cost models -------
+
+    #[test]
+    fn test_reported_cost_is_the_optimised_cost_under_every_model() {
+        // HPA* is not guaranteed to find the cheapest path
+        let exact = [
+            "Dijkstra",
+            "A* search",
+            "Bidirectional A*",
+            "D* Lite",
+            "JPSW",
+        ];
+        for cost_model in CostModel::ALL {
+            let config = SearchConfig {
+                heuristic: Heuristic::Chebyshev,
+                cost_model,
+                ..SearchConfig::default()
+            };
+            for seed in 0..5 {
+                let mut grid = crate::grid::Grid::new(24, 24);
+                crate::generation::random_grid(&mut grid, 50, 25, 30, false, Some(seed));
+                let mut agent = make_walled_agent();
+                agent.goal = (23, 23);
+                for pos in [agent.start, agent.goal] {
+                    let idx = get_idx_from_coordinate(pos, 24, 24).unwrap();
+                    grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
+                }
+                let optimal = agent.get_path("Dijkstra", &config, &grid.tiles, 24, 24);
+                if !optimal.is_success() {
+                    continue;
+                }
+                for name in exact.into_iter().chain(["HPA*"]) {
+                    let result = agent.get_path(name, &config, &grid.tiles, 24, 24);
+                    if !result.is_success() && !exact.contains(&name) {
+                        continue;
+                    }
+                    let cost = cost_model.path_cost(&result.path, &grid.tiles, 24, 24);
+                    let context = format!("{} {} seed {}", name, cost_model.name(), seed);
+                    assert!((result.cost - cost).abs() < 1e-3, "{}", context);
+                    match exact.contains(&name) {
+                        true => assert!((cost - optimal.cost).abs() < 1e-3, "{}", context),
+                        false => assert!(cost >= optimal.cost - 1e-3, "{}", context),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_model_changes_the_chosen_path() {
+        // Two diagonal steps around the heavy tile, or two straight ones
+        // across it and a third to the goal
+        let mut map = make_floor_grid(3);
+        set_weight(&mut map, (1, 0), 3, 3);
+        let mut agent = make_walled_agent();
+        agent.goal = (2, 0);
+        let mut config = SearchConfig::default();
+        let uniform = agent.get_path("Dijkstra", &config, &map, 3, 3);
+        assert_eq!(uniform.cost, 2.0);
+        assert!(uniform.path.contains(&(1, 1)));
+
+        config.cost_model = CostModel {
+            diagonal: 2.5,
+            ..CostModel::UNIFORM
+        };
+        let expensive_diagonals = agent.get_path("Dijkstra", &config, &map, 3, 3);
+        assert_eq!(expensive_diagonals.path, vec![(2, 0), (1, 0), (0, 0)]);
+        assert_eq!(expensive_diagonals.cost, 4.0);
+    }
+
+    // ------- Movement rules -------
+
+    #[test]
+    fn test_possible_moves_under_every_rule() {
+        // Obstacles north and west of the center
+        let mut map = make_floor_grid(3);
+        set_obstacle(&mut map, (1, 0), 3);
+        set_obstacle(&mut map, (0, 1), 3);
+        let moves = |rule| get_possible_moves((1, 1), &map, 3, 3, rule);
+        assert_eq!(moves(MovementRule::FourConnected).len(), 2);
+        assert_eq!(moves(MovementRule::CornerCutting).len(), 6);
+        assert!(moves(MovementRule::CornerCutting).contains(&(0, 0)));
+        // North blocks NW and NE, west blocks NW and SW
+        let strict = moves(MovementRule::NoCornerCutting);
+        assert_eq!(strict.len(), 3);
+        assert!(!strict.contains(&(2, 0)) && !strict.contains(&(0, 2)));
+        // Only NW has both sides blocked
+        let one_side = moves(MovementRule::OneSideFree);
+        assert_eq!(one_side.len(), 5);
+        assert!(!one_side.contains(&(0, 0)));
+    }
+
+    #[test]
+    fn test_every_algorithm_honours_the_movement_rule() {
+        for movement in MovementRule::ALL {
+            let config = SearchConfig {
+                movement,
+                ..SearchConfig::default()
+            };
+            for seed in 0..3 {
+                let mut grid = crate::grid::Grid::new(24, 24);
+                crate::generation::random_grid(&mut grid, 0, 1, 30, false, Some(seed));
+                let mut agent = make_walled_agent();
+                agent.goal = (23, 23);
+                for pos in [agent.start, agent.goal] {
+                    let idx = get_idx_from_coordinate(pos, 24, 24).unwrap();
+                    grid.tiles[idx] = Tile::new(pos, TileType::Floor, 1);
+                }
+                let possible = agent.is_path_possible(&grid.tiles, 24, 24, movement);
+                for name in ALGORITHMS {
+                    let result = agent.get_path(name, &config, &grid.tiles, 24, 24);
+                    let context = format!("{} {} seed {}", name, movement.name(), seed);
+                    if !possible {
+                        assert!(!result.is_success(), "{}", context);
+                        continue;
+                    }
+                    // Greedy gives up on some maps
+                    if !result.is_success() && name == "Greedy" {
+                        continue;
+                    }
+                    assert!(result.is_success(), "{}", context);
+                    for step in result.path.windows(2) {
+                        let moves = get_possible_moves(step[1], &grid.tiles, 24, 24, movement);
+                        assert!(moves.contains(&step[0]), "{} {:?}", context, step);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_jpsw_matches_dijkstra_cost_under_every_movement_rule() {
+        for movement in MovementRule::ALL {
+            for cost_model in [CostModel::UNIFORM, CostModel::OCTILE] {
+                let config = SearchConfig {
+                    cost_model,
+                    movement,
+                    ..SearchConfig::default()
+                };
+                for seed in 0..40 {
+                    let map = make_random_grid(24, 10 + seed as usize % 4 * 10, seed);
+                    let optimal = corner_to_corner_cost("Dijkstra", &config, &map, 24);
+                    let cost = corner_to_corner_cost("JPSW", &config, &map, 24);
+                    let context =
+                        format!("{} {} seed {}", movement.name(), cost_model.name(), seed);
+                    assert_eq!(cost.is_some(), optimal.is_some(), "{}", context);
+                    if let (Some(cost), Some(optimal)) = (cost, optimal) {
+                        assert!(
+                            (cost - optimal).abs() < 1e-3,
+                            "{}: {} vs {}",
+                            context,
+                            cost,
+                            optimal
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_four_connected_paths_are_manhattan_on_open_grids() {
+        let map = make_floor_grid(10);
+        let config = SearchConfig {
+            movement: MovementRule::FourConnected,
+            ..SearchConfig::default()
+        };
+        let mut agent = make_walled_agent();
+        agent.goal = (6, 9);
+        for name in ALGORITHMS.into_iter().filter(|&name| name != "Greedy") {
+            let result = agent.get_path(name, &config, &map, 10, 10);
+            assert_eq!(result.path.len(), 16, "{}", name);
+        }
+        assert!(
+            !get_possible_moves((5, 5), &map, 10, 10, MovementRule::FourConnected)
+                .contains(&(6, 6))
+        );
+    }
+
+    #[test]
+    fn test_movement_rule_names_round_trip() {
+        for rule in MovementRule::ALL {
+            assert_eq!(MovementRule::from_name(rule.name()), Some(rule));
+        }
+        assert_eq!(
+            MovementRule::from_name(" corner cutting"),
+            Some(MovementRule::CornerCutting)
+        );
+        assert_eq!(MovementRule::from_name("Hex"), None);
+        assert_eq!(MovementRule::default(), MovementRule::NoCornerCutting);
+    }
 }
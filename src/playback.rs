@@ -0,0 +1,204 @@
+//! # Playback Module
+//!
+//! This module controls how the path animation advances:
+//! - `Playback`: play/pause state, speed multiplier and the frames shown so far
+//! - `PlaybackFrame`: the changes made by one animation frame, kept so the
+//!   frame can be undone when stepping back
+//!
+//! The board applies frames; this module only decides how many to apply each
+//! rendered frame and remembers how to revert them.
+
+use crate::grid::TileType;
+
+/// Speed multipliers offered by the playback controls, slowest first.
+pub const PLAYBACK_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Index into `PLAYBACK_SPEEDS` of the normal (1x) speed.
+pub const DEFAULT_SPEED_INDEX: usize = 2;
+
+/// Tile and agent changes made by one animation frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaybackFrame {
+    /// Changed tile indices with their type before the change, in order
+    pub tiles: Vec<(usize, TileType)>,
+    /// Moved agent indices with their position before the move
+    pub moves: Vec<(usize, (i32, i32))>,
+}
+
+/// Play/pause state, speed and undo history of the path animation.
+#[derive(Clone, Debug)]
+pub struct Playback {
+    /// Whether the animation is paused
+    paused: bool,
+    /// Index into `PLAYBACK_SPEEDS`
+    speed_index: usize,
+    /// Fraction of a frame carried over between ticks (speeds below 1x)
+    progress: f32,
+    /// Frames applied so far, oldest first
+    history: Vec<PlaybackFrame>,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Playback {
+            paused: false,
+            speed_index: DEFAULT_SPEED_INDEX,
+            progress: 0.0,
+            history: vec![],
+        }
+    }
+}
+
+impl Playback {
+    /// Check if the animation is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pause or resume the animation.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.progress = 0.0;
+    }
+
+    /// Switch between playing and paused.
+    ///
+    /// # Returns
+    /// `true` if the animation is now paused
+    pub fn toggle_pause(&mut self) -> bool {
+        self.set_paused(!self.paused);
+        self.paused
+    }
+
+    /// Current speed multiplier.
+    pub fn speed(&self) -> f32 {
+        PLAYBACK_SPEEDS[self.speed_index]
+    }
+
+    /// Current index into `PLAYBACK_SPEEDS`.
+    pub fn speed_index(&self) -> usize {
+        self.speed_index
+    }
+
+    /// Select a speed, clamped to the available multipliers.
+    pub fn set_speed_index(&mut self, index: usize) {
+        self.speed_index = index.min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    /// Select the next faster speed, if any.
+    pub fn faster(&mut self) {
+        self.set_speed_index(self.speed_index + 1);
+    }
+
+    /// Select the next slower speed, if any.
+    pub fn slower(&mut self) {
+        self.set_speed_index(self.speed_index.saturating_sub(1));
+    }
+
+    /// Advance the clock by one rendered frame.
+    ///
+    /// # Returns
+    /// Number of animation frames to apply now (0 while paused)
+    pub fn tick(&mut self) -> usize {
+        if self.paused {
+            return 0;
+        }
+        self.progress += self.speed();
+        let frames = self.progress.floor();
+        self.progress -= frames;
+        frames as usize
+    }
+
+    /// Remember an applied frame so it can be undone.
+    pub fn record(&mut self, frame: PlaybackFrame) {
+        self.history.push(frame);
+    }
+
+    /// Take the most recently applied frame.
+    ///
+    /// # Returns
+    /// The frame to revert, or `None` at the start of the animation
+    pub fn undo(&mut self) -> Option<PlaybackFrame> {
+        self.history.pop()
+    }
+
+    /// Number of frames applied so far.
+    pub fn frame(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Forget the applied frames (the animation they belong to is gone).
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.progress = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ------- Speed -------
+
+    #[test]
+    fn test_tick_follows_speed() {
+        let mut playback = Playback::default();
+        assert_eq!(playback.tick(), 1);
+
+        playback.set_speed_index(0);
+        let frames: usize = (0..8).map(|_| playback.tick()).sum();
+        assert_eq!(frames, 2);
+
+        playback.set_speed_index(PLAYBACK_SPEEDS.len() - 1);
+        assert_eq!(playback.tick(), 8);
+    }
+
+    #[test]
+    fn test_speed_is_clamped() {
+        let mut playback = Playback::default();
+        playback.slower();
+        playback.slower();
+        playback.slower();
+        assert_eq!(playback.speed_index(), 0);
+        playback.set_speed_index(100);
+        assert_eq!(playback.speed(), PLAYBACK_SPEEDS[PLAYBACK_SPEEDS.len() - 1]);
+        playback.faster();
+        assert_eq!(playback.speed_index(), PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    // ------- Pause -------
+
+    #[test]
+    fn test_paused_playback_does_not_tick() {
+        let mut playback = Playback::default();
+        assert!(playback.toggle_pause());
+        assert_eq!(playback.tick(), 0);
+        assert!(!playback.toggle_pause());
+        assert_eq!(playback.tick(), 1);
+    }
+
+    // ------- History -------
+
+    #[test]
+    fn test_undo_returns_frames_in_reverse() {
+        let mut playback = Playback::default();
+        let first = PlaybackFrame {
+            tiles: vec![(0, TileType::Floor)],
+            moves: vec![(0, (0, 0))],
+        };
+        let second = PlaybackFrame {
+            tiles: vec![(1, TileType::Player)],
+            moves: vec![(0, (1, 0))],
+        };
+        playback.record(first.clone());
+        playback.record(second.clone());
+        assert_eq!(playback.frame(), 2);
+        assert_eq!(playback.undo(), Some(second));
+        assert_eq!(playback.undo(), Some(first));
+        assert_eq!(playback.undo(), None);
+
+        playback.record(PlaybackFrame::default());
+        playback.clear_history();
+        assert_eq!(playback.frame(), 0);
+    }
+}
@@ -0,0 +1,573 @@
+//! # Replanning Module
+//!
+//! This module supports experiments in changing environments, where tiles
+//! turn into obstacles while agents are walking their paths:
+//! - `Replanner`: replans an agent after tile changes, incrementally with
+//!   D* Lite, on a locally updated cluster graph with HPA*, or from scratch
+//!   with any other planner
+//! - `ReplanStats`: number, expansions and time of the replans of a run
+//! - `obstacle_ahead`: picks a tile on the remaining path to block
+//! - `compare_replanning`: walks a path while obstacles appear and replans
+//!   it both incrementally and from scratch
+//!
+//! `run_replanning_benchmark` repeats the comparison on generated grids and
+//! writes one CSV row per run.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::SeedableRng;
+
+use crate::benchmarks::BenchmarkConfig;
+use crate::grid::{get_coordinate_from_idx, get_idx_from_coordinate, Grid, Tile, TileType};
+use crate::hierarchical::{HpaGraph, DEFAULT_CLUSTER_SIZE, HPA_ALGORITHM};
+use crate::pathfinding::{Agent, DStarLite, SearchConfig, SearchContext};
+
+/// Name of the planner that replans incrementally.
+pub const INCREMENTAL_ALGORITHM: &str = "D* Lite";
+
+/// Measurements of the replans of one run.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ReplanStats {
+    /// Number of replans
+    pub replans: u32,
+    /// Replans that found no path
+    pub failures: u32,
+    /// Nodes expanded over all replans
+    pub expansions: u64,
+    /// Time taken by all replans
+    pub time: Duration,
+}
+
+impl std::ops::AddAssign for ReplanStats {
+    fn add_assign(&mut self, other: Self) {
+        self.replans += other.replans;
+        self.failures += other.failures;
+        self.expansions += other.expansions;
+        self.time += other.time;
+    }
+}
+
+impl ReplanStats {
+    /// Add the measurements of a single replan.
+    fn record(&mut self, expansions: u32, time: Duration, failed: bool) {
+        self.replans += 1;
+        self.failures += failed as u32;
+        self.expansions += expansions as u64;
+        self.time += time;
+    }
+}
+
+impl fmt::Display for ReplanStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            " Replans: {}\n Replan Failures: {}\n Replan Steps: {}\n Replan Time: {:#?}",
+            self.replans, self.failures, self.expansions, self.time
+        )
+    }
+}
+
+/// Replans one agent's path after tiles change.
+///
+/// With `INCREMENTAL_ALGORITHM` the search state is kept between replans and
+/// only repaired. With HPA* the cluster graph is kept and only the clusters
+/// around changed tiles are rebuilt; any other planner searches again from
+/// the agent's current position. Every change to the map must be reported with `tiles_changed`,
+/// even when the agent does not need to replan yet.
+pub struct Replanner {
+    algorithm: String,
+    search: SearchConfig,
+    /// Incremental search state, once `plan` has run (D* Lite only)
+    planner: Option<DStarLite>,
+    /// Cluster graph, once `plan` has run (HPA* only)
+    hierarchy: Option<HpaGraph>,
+    /// Tiles changed since the last replan
+    changed: Vec<(i32, i32)>,
+    /// Measurements of the replans so far (the initial plan is not counted)
+    pub stats: ReplanStats,
+}
+
+impl Replanner {
+    /// Create a replanner using the named algorithm.
+    ///
+    /// # Arguments
+    /// * `algorithm` - Algorithm name as accepted by `get_configured_algorithm`
+    /// * `search` - Heuristic settings for the informed planners and the
+    ///   cost model of every planner
+    pub fn new(algorithm: &str, search: SearchConfig) -> Self {
+        Replanner {
+            algorithm: algorithm.to_string(),
+            search,
+            planner: None,
+            hierarchy: None,
+            changed: vec![],
+            stats: ReplanStats::default(),
+        }
+    }
+
+    /// Check if replans reuse state built by `plan` instead of starting over.
+    pub fn is_incremental(&self) -> bool {
+        self.algorithm == INCREMENTAL_ALGORITHM || self.algorithm == HPA_ALGORITHM
+    }
+
+    /// Record that the tiles at `changed` were edited.
+    pub fn tiles_changed(&mut self, changed: &[(i32, i32)]) {
+        if self.is_incremental() {
+            self.changed.extend_from_slice(changed);
+        }
+    }
+
+    /// Plan the initial path, which is not counted in `stats`.
+    ///
+    /// For D* Lite this is the search later replans repair and for HPA* the
+    /// cluster graph they update, so call it before the first tile changes.
+    ///
+    /// # Returns
+    /// The path goal first, ending at `position`, or an empty path if the
+    /// goal cannot be reached
+    pub fn plan(
+        &mut self,
+        position: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> Vec<(i32, i32)> {
+        if !self.is_incremental() {
+            return self.full_plan(position, goal, map, width, height).0;
+        }
+        self.changed.clear();
+        if self.algorithm == HPA_ALGORITHM {
+            let graph = HpaGraph::build(
+                map,
+                width,
+                height,
+                DEFAULT_CLUSTER_SIZE,
+                self.search.cost_model,
+                self.search.movement,
+            );
+            let (path, _) = graph.find_path(position, goal, map, &mut SearchContext::default());
+            self.hierarchy = Some(graph);
+            return path;
+        }
+        let mut planner = DStarLite::new(
+            position,
+            goal,
+            self.search.cost_model,
+            self.search.movement,
+            width,
+            height,
+        );
+        planner.compute(map, &mut SearchContext::default());
+        let path = planner.path(map);
+        self.planner = Some(planner);
+        path
+    }
+
+    /// Replan for an agent at `position` after the changes reported by
+    /// `tiles_changed`.
+    ///
+    /// # Returns
+    /// The new path goal first, ending at `position`, or an empty path if
+    /// the goal can no longer be reached
+    pub fn replan(
+        &mut self,
+        position: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> Vec<(i32, i32)> {
+        if self.is_incremental() && self.planner.is_none() && self.hierarchy.is_none() {
+            self.plan(position, goal, map, width, height);
+        }
+        let now = Instant::now();
+        let (path, expansions, time) = match (self.planner.as_mut(), self.hierarchy.as_mut()) {
+            (Some(planner), _) => {
+                planner.move_to(position);
+                planner.update_tiles(&self.changed, map);
+                self.changed.clear();
+                let expansions = planner.compute(map, &mut SearchContext::default());
+                let path = planner.path(map);
+                (path, expansions, now.elapsed())
+            }
+            (None, Some(graph)) => {
+                graph.update_tiles(&self.changed, map);
+                self.changed.clear();
+                let (path, expansions) =
+                    graph.find_path(position, goal, map, &mut SearchContext::default());
+                (path, expansions, now.elapsed())
+            }
+            (None, None) => self.full_plan(position, goal, map, width, height),
+        };
+        self.stats.record(expansions, time, path.is_empty());
+        path
+    }
+
+    /// Plan from scratch with the configured algorithm.
+    ///
+    /// # Returns
+    /// The full path, the nodes expanded and the search time
+    fn full_plan(
+        &self,
+        position: (i32, i32),
+        goal: (i32, i32),
+        map: &[Tile],
+        width: u32,
+        height: u32,
+    ) -> (Vec<(i32, i32)>, u32, Duration) {
+        let mut agent = Agent {
+            start: position,
+            goal,
+            position,
+            path: vec![],
+            waypoints: vec![],
+        };
+        let result = agent.get_path(&self.algorithm, &self.search, map, width, height);
+        (result.path, result.expansions, result.time)
+    }
+}
+
+/// Pick a floor tile on the remaining part of a path to turn into an obstacle.
+///
+/// # Arguments
+/// * `path` - Remaining path, goal first and ending at the agent's position
+///
+/// # Returns
+/// A position strictly between the agent and its goal, or `None` if there
+/// is no floor tile left there
+pub fn obstacle_ahead(
+    path: &[(i32, i32)],
+    map: &[Tile],
+    width: u32,
+    height: u32,
+    rng: &mut StdRng,
+) -> Option<(i32, i32)> {
+    if path.len() < 3 {
+        return None;
+    }
+    let candidates: Vec<(i32, i32)> = path[1..path.len() - 1]
+        .iter()
+        .copied()
+        .filter(|&pos| {
+            get_idx_from_coordinate(pos, width, height)
+                .and_then(|idx| map.get(idx))
+                .is_some_and(|tile| tile.is_floor())
+        })
+        .collect();
+    candidates.choose(rng).copied()
+}
+
+/// Walk a grid's first start towards its first goal while obstacles appear
+/// on the path ahead, replanning both incrementally and from scratch.
+///
+/// Before every step an obstacle is dropped on the remaining path and both
+/// replanners repair the path from the agent's position. The agent follows
+/// the incremental replanner's path, so both see the same changes.
+///
+/// # Arguments
+/// * `grid` - Grid with at least one start and one goal (left unchanged)
+/// * `changes` - Number of obstacles to drop
+/// * `algorithm` - Planner used for the full replans
+/// * `seed` - Seed choosing where the obstacles appear
+///
+/// # Returns
+/// The incremental and the full replan measurements, or `None` if the goal
+/// is unreachable from the start
+pub fn compare_replanning(
+    grid: &Grid,
+    changes: u32,
+    algorithm: &str,
+    search: &SearchConfig,
+    seed: u64,
+) -> Option<(ReplanStats, ReplanStats)> {
+    let (width, height) = (grid.width, grid.height);
+    let start = get_coordinate_from_idx(*grid.starts.first()?, width, height);
+    let goal = get_coordinate_from_idx(*grid.goals.first()?, width, height);
+    let mut tiles = grid.tiles.clone();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut incremental = Replanner::new(INCREMENTAL_ALGORITHM, *search);
+    let mut full = Replanner::new(algorithm, *search);
+    let mut path = incremental.plan(start, goal, &tiles, width, height);
+    if path.is_empty() {
+        return None;
+    }
+
+    for _ in 0..changes {
+        let Some(blocked) = obstacle_ahead(&path, &tiles, width, height, &mut rng) else {
+            break;
+        };
+        let idx = get_idx_from_coordinate(blocked, width, height)?;
+        tiles[idx].change_tile_type(TileType::Obstacle);
+        let position = *path.last()?;
+        incremental.tiles_changed(&[blocked]);
+        full.tiles_changed(&[blocked]);
+        let repaired = incremental.replan(position, goal, &tiles, width, height);
+        full.replan(position, goal, &tiles, width, height);
+        if repaired.len() < 2 {
+            break;
+        }
+        path = repaired;
+        path.pop();
+    }
+    Some((incremental.stats, full.stats))
+}
+
+/// Compare incremental and full replanning on generated grids, writing
+/// results to CSV.
+///
+/// For every configuration `iterations` random grids with a random start
+/// and goal are generated and walked with `compare_replanning`. Grids whose
+/// goal is unreachable are skipped. With a base seed in the config, run `n`
+/// uses `derive_seed(seed, n)` for both the grid and the obstacles.
+///
+/// # Arguments
+/// * `configs` - Grid configurations to test
+/// * `algorithm` - Planner used for the full replans
+/// * `changes` - Obstacles dropped per run
+/// * `iterations` - Number of runs per configuration
+/// * `output_path` - Path to the output CSV file
+pub fn run_replanning_benchmark(
+    configs: &[BenchmarkConfig],
+    algorithm: &str,
+    changes: u32,
+    iterations: u32,
+    output_path: &PathBuf,
+) -> Result<(), String> {
+    let mut wtr = csv::Writer::from_path(output_path).map_err(|e| e.to_string())?;
+    wtr.write_record([
+        "algorithm",
+        "grid_size",
+        "obstacle_pct",
+        "weighted_pct",
+        "weight_range",
+        "run",
+        "seed",
+        "replans",
+        "incremental_steps",
+        "incremental_ms",
+        "full_steps",
+        "full_ms",
+    ])
+    .map_err(|e| e.to_string())?;
+
+    for config in configs {
+        for run in 0..iterations {
+            let mut grid = Grid::new(config.grid_size, config.grid_size);
+            let seed = config.generate(&mut grid, run);
+            let Some((incremental, full)) =
+                compare_replanning(&grid, changes, algorithm, &config.search, seed)
+            else {
+                continue;
+            };
+            wtr.write_record([
+                algorithm.to_string(),
+                config.grid_size.to_string(),
+                config.obstacle_pct.to_string(),
+                config.weighted_pct.to_string(),
+                config.weight_range.to_string(),
+                run.to_string(),
+                seed.to_string(),
+                incremental.replans.to_string(),
+                incremental.expansions.to_string(),
+                format!("{:.4}", incremental.time.as_secs_f64() * 1000.0),
+                full.expansions.to_string(),
+                format!("{:.4}", full.time.as_secs_f64() * 1000.0),
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    wtr.flush().map_err(|e| e.to_string())?;
+    println!("Replanning results written to {:#?}", output_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generation;
+    use crate::pathfinding::{get_possible_moves, Heuristic, MovementRule, PathfindingAlgorithm};
+
+    fn chebyshev() -> SearchConfig {
+        SearchConfig {
+            heuristic: Heuristic::Chebyshev,
+            heuristic_weight: 1.0,
+            ..SearchConfig::default()
+        }
+    }
+
+    /// Helper: 32x32 random grid with its corners as start and goal
+    fn corner_grid(seed: u64) -> Grid {
+        let mut grid = Grid::new(32, 32);
+        generation::random_grid(&mut grid, 20, 20, 30, false, Some(seed));
+        grid.tiles[0] = Tile::new((0, 0), TileType::Player, 1);
+        grid.tiles[1023] = Tile::new((31, 31), TileType::Enemy, 1);
+        grid.starts = vec![0];
+        grid.goals = vec![1023];
+        grid
+    }
+
+    // ------- obstacle_ahead -------
+
+    #[test]
+    fn test_obstacle_ahead_skips_goal_and_agent() {
+        let grid = Grid::new(5, 1);
+        let path = vec![(4, 0), (3, 0), (2, 0), (1, 0), (0, 0)];
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let pos = obstacle_ahead(&path, &grid.tiles, 5, 1, &mut rng).unwrap();
+            assert!(pos != (4, 0) && pos != (0, 0));
+        }
+        assert_eq!(
+            obstacle_ahead(&path[3..], &grid.tiles, 5, 1, &mut rng),
+            None
+        );
+        assert_eq!(obstacle_ahead(&[], &grid.tiles, 5, 1, &mut rng), None);
+    }
+
+    // ------- Replanner -------
+
+    #[test]
+    fn test_incremental_replan_matches_full_replan_cost() {
+        let search = chebyshev();
+        for seed in 0..10 {
+            let mut grid = corner_grid(seed);
+            let mut incremental = Replanner::new(INCREMENTAL_ALGORITHM, search);
+            let mut path = incremental.plan((0, 0), (31, 31), &grid.tiles, 32, 32);
+            if path.is_empty() {
+                continue;
+            }
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..5 {
+                let Some(blocked) = obstacle_ahead(&path, &grid.tiles, 32, 32, &mut rng) else {
+                    break;
+                };
+                grid.tiles[(blocked.1 * 32 + blocked.0) as usize]
+                    .change_tile_type(TileType::Obstacle);
+                path.pop();
+                let position = *path.last().unwrap();
+                incremental.tiles_changed(&[blocked]);
+                path = incremental.replan(position, (31, 31), &grid.tiles, 32, 32);
+                let (fresh, _) = crate::pathfinding::DStarLiteSearch::default().find_path(
+                    position,
+                    (31, 31),
+                    &grid.tiles,
+                    32,
+                    32,
+                );
+                let (astar, _) = crate::pathfinding::AStarSearch { config: search }.find_path(
+                    position,
+                    (31, 31),
+                    &grid.tiles,
+                    32,
+                    32,
+                );
+                assert_eq!(path.is_empty(), astar.is_empty(), "seed {}", seed);
+                assert_eq!(
+                    path_cost(&fresh, &grid.tiles),
+                    path_cost(&astar, &grid.tiles),
+                    "seed {}",
+                    seed
+                );
+                assert_eq!(
+                    path_cost(&path, &grid.tiles),
+                    path_cost(&astar, &grid.tiles),
+                    "seed {}",
+                    seed
+                );
+                for step in path.windows(2) {
+                    assert!(get_possible_moves(
+                        step[1],
+                        &grid.tiles,
+                        32,
+                        32,
+                        MovementRule::default()
+                    )
+                    .contains(&step[0]));
+                }
+                if path.len() < 2 {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Helper: cost of a goal-first path, charging each entered tile's
+    /// weight (diagonals cost the same as straight moves)
+    fn path_cost(path: &[(i32, i32)], map: &[Tile]) -> u32 {
+        path.iter()
+            .rev()
+            .skip(1)
+            .map(|&(x, y)| map[(y * 32 + x) as usize].weight as u32)
+            .sum()
+    }
+
+    #[test]
+    fn test_compare_replanning_expands_less_incrementally() {
+        let (mut incremental_steps, mut full_steps) = (0, 0);
+        for seed in 0..5 {
+            let grid = corner_grid(seed);
+            if let Some((incremental, full)) =
+                compare_replanning(&grid, 8, "A* search", &chebyshev(), seed)
+            {
+                assert_eq!(incremental.replans, full.replans);
+                incremental_steps += incremental.expansions;
+                full_steps += full.expansions;
+            }
+        }
+        assert!(full_steps > 0);
+        assert!(
+            incremental_steps < full_steps,
+            "{} >= {}",
+            incremental_steps,
+            full_steps
+        );
+    }
+
+    #[test]
+    fn test_replanner_reports_unreachable_goal() {
+        let mut grid = Grid::new(5, 5);
+        let mut replanner = Replanner::new(INCREMENTAL_ALGORITHM, chebyshev());
+        assert!(!replanner.plan((0, 0), (4, 0), &grid.tiles, 5, 5).is_empty());
+        let wall: Vec<(i32, i32)> = (0..5).map(|y| (2, y)).collect();
+        for &(x, y) in &wall {
+            grid.tiles[(y * 5 + x) as usize].change_tile_type(TileType::Obstacle);
+        }
+        replanner.tiles_changed(&wall);
+        assert!(replanner
+            .replan((0, 0), (4, 0), &grid.tiles, 5, 5)
+            .is_empty());
+        assert_eq!(replanner.stats.replans, 1);
+        assert_eq!(replanner.stats.failures, 1);
+    }
+
+    #[test]
+    fn test_hierarchical_replan_avoids_new_obstacles() {
+        let mut grid = corner_grid(3);
+        let mut replanner = Replanner::new(HPA_ALGORITHM, chebyshev());
+        assert!(replanner.is_incremental());
+        let mut path = replanner.plan((0, 0), (31, 31), &grid.tiles, 32, 32);
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..5 {
+            let Some(blocked) = obstacle_ahead(&path, &grid.tiles, 32, 32, &mut rng) else {
+                break;
+            };
+            grid.tiles[(blocked.1 * 32 + blocked.0) as usize].change_tile_type(TileType::Obstacle);
+            replanner.tiles_changed(&[blocked]);
+            path = replanner.replan((0, 0), (31, 31), &grid.tiles, 32, 32);
+            assert!(!path.contains(&blocked));
+            for step in path.windows(2) {
+                assert!(
+                    get_possible_moves(step[1], &grid.tiles, 32, 32, MovementRule::default())
+                        .contains(&step[0])
+                );
+            }
+        }
+        assert!(replanner.stats.replans > 0);
+    }
+}
@@ -10,15 +10,66 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
+
+use crate::generation::{CaveRules, MazeAlgorithm, NoiseKind, NoiseTerrain};
+use crate::movingai::TerrainCosts;
+use crate::multi_agent::MultiAgentPlanner;
+use crate::palette::Palette;
+use crate::pathfinding::SearchConfig;
 use std::path::Path;
 
 /// Specifies the method used to generate the game board.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GenerationMode {
     /// Random placement of obstacles and weighted tiles
     Random,
     /// City-style generation with roads and buildings
     City,
+    /// Maze carved by the given algorithm
+    Maze(MazeAlgorithm),
+    /// Organic caverns smoothed by a cellular automaton
+    Cave,
+    /// Smooth weights from multi-octave noise, with mountains and lakes
+    Noise(NoiseKind),
+}
+
+impl GenerationMode {
+    /// Every generation mode, in the order shown in the UI.
+    pub const ALL: [GenerationMode; 10] = [
+        GenerationMode::Random,
+        GenerationMode::City,
+        GenerationMode::Maze(MazeAlgorithm::RecursiveBacktracker),
+        GenerationMode::Maze(MazeAlgorithm::Prim),
+        GenerationMode::Maze(MazeAlgorithm::Kruskal),
+        GenerationMode::Maze(MazeAlgorithm::Wilson),
+        GenerationMode::Cave,
+        GenerationMode::Noise(NoiseKind::Perlin),
+        GenerationMode::Noise(NoiseKind::Simplex),
+        GenerationMode::Noise(NoiseKind::Value),
+    ];
+
+    /// Display name of the mode.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GenerationMode::Random => "Random",
+            GenerationMode::City => "City",
+            GenerationMode::Maze(MazeAlgorithm::RecursiveBacktracker) => "Backtracker Maze",
+            GenerationMode::Maze(MazeAlgorithm::Prim) => "Prim Maze",
+            GenerationMode::Maze(MazeAlgorithm::Kruskal) => "Kruskal Maze",
+            GenerationMode::Maze(MazeAlgorithm::Wilson) => "Wilson Maze",
+            GenerationMode::Cave => "Cave",
+            GenerationMode::Noise(NoiseKind::Perlin) => "Perlin Terrain",
+            GenerationMode::Noise(NoiseKind::Simplex) => "Simplex Terrain",
+            GenerationMode::Noise(NoiseKind::Value) => "Value Terrain",
+        }
+    }
+
+    /// Look up a mode by name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<GenerationMode> {
+        GenerationMode::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name.trim()))
+    }
 }
 
 /// Complete application settings with serialization support.
@@ -48,6 +99,18 @@ pub struct GameSettings {
     pub enable_random_agents: bool,
     /// Currently selected pathfinding algorithm ("Greedy", "BFS", "A* search", "JPSW")
     pub selected_algorithm: String,
+    /// Heuristic and heuristic weight used by A* and JPSW
+    #[serde(default)]
+    pub search_config: SearchConfig,
+    /// Replay the final search (opened/expanded cells) before drawing paths
+    #[serde(default)]
+    pub show_search_trace: bool,
+    /// Drop obstacles on the paths while agents walk them and replan
+    #[serde(default)]
+    pub enable_dynamic_obstacles: bool,
+    /// Planner that keeps multiple agents from colliding
+    #[serde(default)]
+    pub multi_agent_planner: MultiAgentPlanner,
 
     // ----- Board Settings -----
     /// Width of the game board in pixels
@@ -70,6 +133,17 @@ pub struct GameSettings {
     pub save_file: String,
     /// Current grid generation mode
     pub gen_mode: GenerationMode,
+    /// Seed reused for every generated grid, random each time if `None`
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Smoothing rules of generated caves; the iteration count is taken from
+    /// the generation slider instead
+    #[serde(default)]
+    pub cave_rules: CaveRules,
+    /// Shape of generated noise terrain; the mountain and lake percentages
+    /// are taken from the generation sliders instead
+    #[serde(default)]
+    pub noise_terrain: NoiseTerrain,
 
     // ----- File Settings -----
     /// Path to the last opened map file
@@ -78,6 +152,20 @@ pub struct GameSettings {
     pub last_save_directory: String,
     /// Enable automatic saving
     pub auto_save_enabled: bool,
+    /// Colors recognised when importing PNG/BMP boards
+    #[serde(default)]
+    pub image_palette: Palette,
+    /// Weights of the terrain classes when loading MovingAI `.map` boards
+    #[serde(default)]
+    pub terrain_costs: TerrainCosts,
+    /// Pixels per tile of exported PNG images
+    #[serde(default = "default_export_scale")]
+    pub export_scale: u32,
+}
+
+/// Pixels per tile used for PNG exports unless configured otherwise.
+fn default_export_scale() -> u32 {
+    4
 }
 
 /// Provides sensible default values for all settings.
@@ -99,6 +187,10 @@ impl Default for GameSettings {
             enable_multiple_goals: false,
             enable_random_agents: false,
             selected_algorithm: String::from("Greedy"),
+            search_config: SearchConfig::default(),
+            show_search_trace: false,
+            enable_dynamic_obstacles: false,
+            multi_agent_planner: MultiAgentPlanner::default(),
             board_width: 800,
             board_height: 800,
             tiles_x: 40,
@@ -106,8 +198,14 @@ impl Default for GameSettings {
             last_opened_file: None,
             last_save_directory: String::from("/home"),
             auto_save_enabled: false,
+            image_palette: Palette::default(),
+            terrain_costs: TerrainCosts::default(),
+            export_scale: default_export_scale(),
             save_file: "test".to_string(),
             gen_mode: GenerationMode::Random,
+            seed: None,
+            cave_rules: CaveRules::default(),
+            noise_terrain: NoiseTerrain::default(),
             weight: 1,
             gen_obstacles: 0,
             weight_count: 0,
@@ -136,6 +234,24 @@ impl GameSettings {
         }
     }
 
+    /// Cave rules with the iteration count of the generation slider.
+    pub fn cave_rules(&self) -> CaveRules {
+        CaveRules {
+            iterations: self.weight_count,
+            ..self.cave_rules
+        }
+    }
+
+    /// Noise terrain with the mountain and lake percentages of the
+    /// generation sliders.
+    pub fn noise_terrain(&self) -> NoiseTerrain {
+        NoiseTerrain {
+            mountain_pct: self.gen_obstacles,
+            lake_pct: self.weight_count,
+            ..self.noise_terrain
+        }
+    }
+
     /// Save current settings to a JSON file.
     ///
     /// Creates a pretty-printed JSON file for human readability.
@@ -153,6 +269,7 @@ impl GameSettings {
     /// Platform-specific locations:
     /// - Linux: `~/.config/pathmaker/settings.json`
     /// - Windows: `%APPDATA%\pathmaker\settings.json`
+    ///
     /// Falls back to `./settings.json` if no home directory is found.
     pub fn get_default_path() -> String {
         if cfg!(target_os = "windows") {
@@ -228,6 +345,25 @@ mod tests {
         assert!(matches!(s.gen_mode, GenerationMode::Random));
     }
 
+    #[test]
+    fn test_generation_mode_names_round_trip() {
+        for mode in GenerationMode::ALL {
+            assert_eq!(GenerationMode::from_name(mode.name()), Some(mode));
+        }
+        assert_eq!(
+            GenerationMode::from_name("prim maze"),
+            Some(GenerationMode::Maze(MazeAlgorithm::Prim))
+        );
+        assert_eq!(GenerationMode::from_name("Swamp"), None);
+        let json = serde_json::to_string(&GenerationMode::Maze(MazeAlgorithm::Wilson)).unwrap();
+        let mode: GenerationMode = serde_json::from_str(&json).unwrap();
+        assert_eq!(mode, GenerationMode::Maze(MazeAlgorithm::Wilson));
+        assert_eq!(
+            GenerationMode::from_name("simplex terrain"),
+            Some(GenerationMode::Noise(NoiseKind::Simplex))
+        );
+    }
+
     #[test]
     fn test_default_weight_and_obstacles() {
         let s = GameSettings::default();
@@ -275,13 +411,29 @@ mod tests {
         let json = serde_json::to_string(&s).unwrap();
         let loaded: GameSettings = serde_json::from_str(&json).unwrap();
         assert_eq!(loaded.window_width, 1920);
-        assert_eq!(loaded.fullscreen, true);
+        assert!(loaded.fullscreen);
         assert_eq!(loaded.selected_algorithm, "A* search");
         assert_eq!(loaded.tiles_x, 100);
         assert_eq!(loaded.iterations, 50);
         assert!(loaded.enable_multiple_agents);
     }
 
+    #[test]
+    fn test_search_config_roundtrip_and_default() {
+        let mut s = GameSettings::default();
+        s.search_config.heuristic = crate::pathfinding::Heuristic::Chebyshev;
+        s.search_config.heuristic_weight = 2.0;
+        let json = serde_json::to_string(&s).unwrap();
+        let loaded: GameSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.search_config, s.search_config);
+
+        // Settings files written before the field existed still load
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("search_config");
+        let loaded: GameSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.search_config, SearchConfig::default());
+    }
+
     // ------- Save and Load -------
 
     #[test]
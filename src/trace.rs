@@ -0,0 +1,189 @@
+//! # Search Trace Module
+//!
+//! This module records what a planner did while searching so it can be
+//! replayed on the board:
+//! - `TraceEvent`: a node being opened, expanded, or chosen as a jump point
+//! - `SearchTrace`: the ordered events of one query
+//! - `TraceReplay`: steps through a trace and tracks the state of each cell
+//!
+//! Recording is opt-in through `SearchContext::traced`; untraced searches pay
+//! only for a branch per event.
+
+use std::collections::HashMap;
+
+/// State a cell reaches during a search.
+///
+/// Ordered by precedence: a cell that was expanded stays expanded even if it
+/// is opened again later, and jump points are never downgraded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TraceCell {
+    /// Added to the open set / frontier
+    Opened,
+    /// Removed from the open set and its neighbours examined
+    Expanded,
+    /// Selected as a jump point (JPSW)
+    JumpPoint,
+}
+
+/// A single step of a search.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TraceEvent {
+    /// Grid position the event applies to
+    pub position: (i32, i32),
+    /// What happened to the cell
+    pub cell: TraceCell,
+}
+
+/// Ordered list of events recorded during one search.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchTrace {
+    /// Events in the order the planner produced them
+    pub events: Vec<TraceEvent>,
+}
+
+impl SearchTrace {
+    /// Append an event to the trace.
+    #[inline]
+    pub fn record(&mut self, position: (i32, i32), cell: TraceCell) {
+        self.events.push(TraceEvent { position, cell });
+    }
+
+    /// Number of events of the given kind.
+    pub fn count(&self, cell: TraceCell) -> usize {
+        self.events.iter().filter(|e| e.cell == cell).count()
+    }
+
+    /// Append all events of another trace (used to combine several agents).
+    pub fn extend(&mut self, other: SearchTrace) {
+        self.events.extend(other.events);
+    }
+}
+
+/// Replays a `SearchTrace` a few events at a time.
+#[derive(Clone, Debug, Default)]
+pub struct TraceReplay {
+    /// The trace being replayed
+    trace: SearchTrace,
+    /// Number of events applied so far
+    cursor: usize,
+    /// Current state of every cell touched by the applied events
+    cells: HashMap<(i32, i32), TraceCell>,
+}
+
+impl TraceReplay {
+    /// Start replaying `trace` from the beginning.
+    pub fn new(trace: SearchTrace) -> Self {
+        TraceReplay {
+            trace,
+            cursor: 0,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Apply up to `amount` further events.
+    ///
+    /// # Returns
+    /// The positions whose state changed (to be redrawn)
+    pub fn advance(&mut self, amount: usize) -> Vec<(i32, i32)> {
+        let end = (self.cursor + amount).min(self.trace.events.len());
+        let mut changed = Vec::with_capacity(end - self.cursor);
+        for event in &self.trace.events[self.cursor..end] {
+            let state = self.cells.entry(event.position).or_insert(event.cell);
+            if event.cell >= *state {
+                *state = event.cell;
+                changed.push(event.position);
+            }
+        }
+        self.cursor = end;
+        changed
+    }
+
+    /// Apply every remaining event.
+    pub fn finish(&mut self) -> Vec<(i32, i32)> {
+        self.advance(self.trace.events.len())
+    }
+
+    /// Check if every event has been applied.
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.trace.events.len()
+    }
+
+    /// Number of events applied so far.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Total number of events in the trace.
+    pub fn len(&self) -> usize {
+        self.trace.events.len()
+    }
+
+    /// Check if the trace has no events.
+    pub fn is_empty(&self) -> bool {
+        self.trace.events.is_empty()
+    }
+
+    /// Current state of a cell, if any applied event touched it.
+    pub fn cell(&self, position: (i32, i32)) -> Option<TraceCell> {
+        self.cells.get(&position).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_trace() -> SearchTrace {
+        let mut trace = SearchTrace::default();
+        trace.record((0, 0), TraceCell::Expanded);
+        trace.record((1, 0), TraceCell::Opened);
+        trace.record((1, 0), TraceCell::Expanded);
+        trace.record((0, 0), TraceCell::Opened);
+        trace.record((2, 0), TraceCell::JumpPoint);
+        trace
+    }
+
+    // ------- SearchTrace -------
+
+    #[test]
+    fn test_trace_counts_events() {
+        let trace = sample_trace();
+        assert_eq!(trace.count(TraceCell::Expanded), 2);
+        assert_eq!(trace.count(TraceCell::Opened), 2);
+        assert_eq!(trace.count(TraceCell::JumpPoint), 1);
+    }
+
+    // ------- TraceReplay -------
+
+    #[test]
+    fn test_replay_advances_in_order() {
+        let mut replay = TraceReplay::new(sample_trace());
+        assert_eq!(replay.advance(2), vec![(0, 0), (1, 0)]);
+        assert_eq!(replay.cell((1, 0)), Some(TraceCell::Opened));
+        assert_eq!(replay.cell((2, 0)), None);
+        assert!(!replay.is_finished());
+
+        replay.finish();
+        assert!(replay.is_finished());
+        assert_eq!(replay.cursor(), replay.len());
+        assert_eq!(replay.cell((1, 0)), Some(TraceCell::Expanded));
+        assert_eq!(replay.cell((2, 0)), Some(TraceCell::JumpPoint));
+    }
+
+    #[test]
+    fn test_replay_never_downgrades_cells() {
+        let mut replay = TraceReplay::new(sample_trace());
+        replay.advance(3);
+        // (0, 0) is re-opened after being expanded; the event changes nothing
+        assert!(replay.advance(1).is_empty());
+        assert_eq!(replay.cell((0, 0)), Some(TraceCell::Expanded));
+    }
+
+    #[test]
+    fn test_replay_of_empty_trace_is_finished() {
+        let mut replay = TraceReplay::new(SearchTrace::default());
+        assert!(replay.is_empty());
+        assert!(replay.is_finished());
+        assert!(replay.advance(10).is_empty());
+    }
+}
@@ -14,6 +14,9 @@ use sdl2::rect::{Point, Rect};
 
 use crate::{components::button::*, fileDialog};
 
+// Grid coordinate helpers live in the core library
+pub use crate::grid::get_idx_from_coordinate;
+
 /// Check if the mouse position is within a given rectangle.
 ///
 /// # Arguments
@@ -140,21 +143,6 @@ pub fn add_file_to_dir_map(
     }
 }
 
-#[inline]
-pub fn get_coordinate_from_idx(idx: usize, x_size: u32, y_size: u32) -> (i32, i32) {
-    let x = (idx % x_size as usize) as i32;
-    let y = (idx / y_size as usize) as i32;
-    (x, y)
-}
-
-#[inline]
-pub fn get_idx_from_coordinate(pos: (i32, i32), width: u32, height: u32) -> Option<usize> {
-    if pos.0 < 0 || pos.1 < 0 || pos.0 >= width as i32 || pos.1 >= height as i32 {
-        return None;
-    }
-    Some(pos.1 as usize * width as usize + pos.0 as usize)
-}
-
 /// Convert a shallow directory tree into a flat HashMap for the initial view.
 ///
 /// Only processes the root node and its immediate children (one level).
@@ -235,68 +223,6 @@ mod tests {
     use sdl2::rect::{Point, Rect};
     use std::cell::RefCell;
 
-    // ------- get_coordinate_from_idx -------
-
-    #[test]
-    fn test_get_coordinate_from_idx_basic() {
-        let (x, y) = get_coordinate_from_idx(0, 10, 10);
-        assert_eq!(x, 0);
-        assert_eq!(y, 0);
-    }
-
-    #[test]
-    fn test_get_coordinate_from_idx_second_row() {
-        let (x, y) = get_coordinate_from_idx(10, 10, 10);
-        assert_eq!(x, 0);
-        assert_eq!(y, 1);
-    }
-
-    #[test]
-    fn test_get_coordinate_from_idx_middle() {
-        let (x, y) = get_coordinate_from_idx(15, 10, 10);
-        assert_eq!(x, 5);
-        assert_eq!(y, 1);
-    }
-
-    #[test]
-    fn test_get_coordinate_from_idx_last() {
-        let (x, y) = get_coordinate_from_idx(99, 10, 10);
-        assert_eq!(x, 9);
-        assert_eq!(y, 9);
-    }
-
-    // ------- get_idx_from_coordinate -------
-
-    #[test]
-    fn test_get_idx_from_coordinate_basic() {
-        let idx = get_idx_from_coordinate((0, 0), 10, 10);
-        assert_eq!(idx, Some(0));
-    }
-
-    #[test]
-    fn test_get_idx_from_coordinate_second_row() {
-        let idx = get_idx_from_coordinate((0, 1), 10, 10);
-        assert_eq!(idx, Some(10));
-    }
-
-    #[test]
-    fn test_get_idx_from_coordinate_middle() {
-        let idx = get_idx_from_coordinate((5, 1), 10, 10);
-        assert_eq!(idx, Some(15));
-    }
-
-    #[test]
-    fn test_get_idx_from_coordinate_out_of_bounds_negative() {
-        assert_eq!(get_idx_from_coordinate((-1, 0), 10, 10), None);
-        assert_eq!(get_idx_from_coordinate((0, -1), 10, 10), None);
-    }
-
-    #[test]
-    fn test_get_idx_from_coordinate_out_of_bounds_too_large() {
-        assert_eq!(get_idx_from_coordinate((10, 0), 10, 10), None);
-        assert_eq!(get_idx_from_coordinate((0, 10), 10, 10), None);
-    }
-
     // ------- add_file_to_dir_map -------
 
     #[test]